@@ -1,6 +1,7 @@
 use std::hint::black_box;
 
-use bevy_symbios_ground::HeightMapMeshBuilder;
+use bevy::prelude::*;
+use bevy_symbios_ground::{HeightMapMeshBuilder, sample_height_bilinear, sample_heights_bilinear};
 use criterion::{Criterion, criterion_group, criterion_main};
 use symbios_ground::HeightMap;
 
@@ -21,5 +22,63 @@ fn bench_mesh_generation(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_mesh_generation);
+fn bench_height_sampling(c: &mut Criterion) {
+    let mut map = HeightMap::new(256, 256, 1.0);
+    for z in 0..256 {
+        for x in 0..256 {
+            map.set(x, z, ((x + z) as f32 * 0.1).sin());
+        }
+    }
+    // Deterministic scatter of 10k points across the map.
+    let points: Vec<Vec2> = (0..10_000u32)
+        .map(|i| {
+            let t = i as f32;
+            Vec2::new(
+                (t * 0.618_034).fract() * 255.0,
+                (t * 0.754_877).fract() * 255.0,
+            )
+        })
+        .collect();
+
+    c.bench_function("sample_height_bilinear scalar 10k", |b| {
+        b.iter(|| {
+            points
+                .iter()
+                .map(|p| sample_height_bilinear(black_box(&map), p.x, p.y))
+                .collect::<Vec<_>>()
+        });
+    });
+    c.bench_function("sample_heights_bilinear batched 10k", |b| {
+        b.iter(|| sample_heights_bilinear(black_box(&map), black_box(&points)));
+    });
+}
+
+#[cfg(feature = "rayon")]
+fn bench_chunked_parallel(c: &mut Criterion) {
+    // 16x16 chunks of 16 cells each.
+    let mut map = HeightMap::new(257, 257, 1.0);
+    for z in 0..257 {
+        for x in 0..257 {
+            map.set(x, z, ((x + z) as f32 * 0.1).sin());
+        }
+    }
+    let builder = HeightMapMeshBuilder::new();
+
+    c.bench_function("build_chunked serial 16x16 chunks", |b| {
+        b.iter(|| builder.build_chunked(black_box(&map), 16));
+    });
+    c.bench_function("build_chunked_parallel 16x16 chunks", |b| {
+        b.iter(|| builder.build_chunked_parallel(black_box(&map), 16));
+    });
+}
+
+#[cfg(not(feature = "rayon"))]
+criterion_group!(benches, bench_mesh_generation, bench_height_sampling);
+#[cfg(feature = "rayon")]
+criterion_group!(
+    benches,
+    bench_mesh_generation,
+    bench_height_sampling,
+    bench_chunked_parallel
+);
 criterion_main!(benches);
@@ -0,0 +1,356 @@
+//! Terrain analysis over the height grid.
+//!
+//! Functions here derive per-cell data from a [`HeightMap`] for gameplay and
+//! procedural placement — for example [`flow_directions`], the standard D8
+//! hydrology pass used to carve rivers or drive splat wetness,
+//! [`aspect_map`] for slope orientation, [`slope_mask`] for placement rules,
+//! [`gradient_field`] for flow maps,
+//! [`find_pits`] for lake placement, [`distance_to_water`] for shoreline
+//! blending, [`deposition_potential`] for sediment layers, and
+//! [`bake_hillshade`] for static lighting.
+
+use bevy::prelude::*;
+use symbios_ground::HeightMap;
+
+use crate::mesher::{GridRect, compute_normals_sobel, sobel_sums};
+
+/// Grid offsets of the eight D8 neighbours, indexed by flow direction code.
+///
+/// Codes run clockwise as seen from above with `+X` right and `+Z` down:
+/// `0` = `+X`, `1` = `+X+Z`, `2` = `+Z`, `3` = `−X+Z`, `4` = `−X`,
+/// `5` = `−X−Z`, `6` = `−Z`, `7` = `+X−Z`.
+pub const D8_OFFSETS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+];
+
+/// Flow direction code for cells with no lower neighbour (pits and flats).
+pub const FLOW_PIT: u8 = u8::MAX;
+
+/// Computes the D8 flow direction of every vertex of `heightmap`.
+///
+/// Returns one code per vertex in row-major order (`z * width + x`). Each code
+/// indexes [`D8_OFFSETS`] and names the neighbour with the steepest downhill
+/// slope, where diagonal drops are divided by `√2` times the grid scale and
+/// axial drops by the scale. Ties resolve to the lowest code. Vertices with
+/// no strictly lower neighbour — pits, flats, and plateaus — are
+/// [`FLOW_PIT`]. Neighbours outside the map are ignored, so water never flows
+/// off the edge.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::analysis::{D8_OFFSETS, FLOW_PIT, flow_directions};
+///
+/// let flow = flow_directions(&heightmap);
+/// let code = flow[z * heightmap.width() + x];
+/// if code != FLOW_PIT {
+///     let downstream = IVec2::new(x as i32, z as i32) + D8_OFFSETS[code as usize];
+/// }
+/// ```
+pub fn flow_directions(heightmap: &HeightMap) -> Vec<u8> {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let s = heightmap.scale();
+
+    let mut flow = Vec::with_capacity(w * h);
+    for z in 0..h {
+        for x in 0..w {
+            let here = heightmap.get(x, z);
+            let mut best = (FLOW_PIT, 0.0);
+            for (code, offset) in D8_OFFSETS.iter().enumerate() {
+                let nx = x as i32 + offset.x;
+                let nz = z as i32 + offset.y;
+                if nx < 0 || nz < 0 || nx >= w as i32 || nz >= h as i32 {
+                    continue;
+                }
+                let distance = offset.as_vec2().length() * s;
+                let slope = (here - heightmap.get(nx as usize, nz as usize)) / distance;
+                if slope > best.1 {
+                    best = (code as u8, slope);
+                }
+            }
+            flow.push(best.0);
+        }
+    }
+    flow
+}
+
+/// Bakes directional hillshading: the brightness `N·L` of every vertex.
+///
+/// `light_dir` points *towards* the light (e.g. `Vec3::new(-1.0, 1.0, -1.0)`
+/// for a sun in the north-west) and is normalized internally. Normals come
+/// from the Sobel filter, as in [`NormalMethod::Sobel`](crate::NormalMethod::Sobel).
+/// Results are clamped to `[0, 1]` and returned in row-major order
+/// (`z * width + x`), ready to multiply into a texture or store as vertex
+/// colors for static lighting in top-down views.
+///
+/// A zero `light_dir` yields all zeros.
+pub fn bake_hillshade(heightmap: &HeightMap, light_dir: Vec3) -> Vec<f32> {
+    let light = light_dir.normalize_or_zero();
+    compute_normals_sobel(heightmap, GridRect::full(heightmap))
+        .into_iter()
+        .map(|n| Vec3::from(n).dot(light).clamp(0.0, 1.0))
+        .collect()
+}
+
+/// Aspect value reported by [`aspect_map`] for vertices without a slope.
+pub const ASPECT_FLAT: f32 = -1.0;
+
+/// Computes the aspect — the compass direction each slope faces — of every
+/// vertex of `heightmap`.
+///
+/// Returns one angle per vertex in row-major order (`z * width + x`), in
+/// radians clockwise from north in `[0, 2π)`, naming the downhill direction
+/// of the Sobel gradient. North is `−Z` and east is `+X`, so a slope falling
+/// towards `+X` faces east (`π/2`) and one falling towards `+Z` faces south
+/// (`π`). Vertices whose gradient is below `1e-6` rise per unit run are
+/// [`ASPECT_FLAT`].
+///
+/// Useful for effects biased by orientation, such as snow melting first on
+/// sun-facing slopes or vegetation favouring the shaded side.
+pub fn aspect_map(heightmap: &HeightMap) -> Vec<f32> {
+    compute_normals_sobel(heightmap, GridRect::full(heightmap))
+        .into_iter()
+        .map(|n| {
+            // The normal leans downhill, so its horizontal part points down the slope.
+            let downhill = Vec2::new(n[0], n[2]);
+            if downhill.length() < 1e-6 * n[1] {
+                return ASPECT_FLAT;
+            }
+            downhill
+                .x
+                .atan2(-downhill.y)
+                .rem_euclid(std::f32::consts::TAU)
+        })
+        .collect()
+}
+
+/// Marks every vertex whose slope is at most `max_slope_degrees`.
+///
+/// The slope is the angle between the Sobel normal, as in
+/// [`NormalMethod::Sobel`](crate::NormalMethod::Sobel), and `+Y`. Returns one
+/// flag per vertex in row-major order (`z * width + x`) — `true` where the
+/// ground is gentle enough to walk or build on. See
+/// [`buildable_mask_image`](crate::preview::buildable_mask_image) for the same
+/// mask as a GPU texture.
+pub fn slope_mask(heightmap: &HeightMap, max_slope_degrees: f32) -> Vec<bool> {
+    let min_up = max_slope_degrees.to_radians().cos();
+    compute_normals_sobel(heightmap, GridRect::full(heightmap))
+        .into_iter()
+        .map(|n| n[1] >= min_up)
+        .collect()
+}
+
+/// Computes the height gradient `(dh/dx, dh/dz)` of every vertex of
+/// `heightmap`, in world units (height per unit of horizontal distance).
+///
+/// Uses the same Sobel kernels as
+/// [`NormalMethod::Sobel`](crate::NormalMethod::Sobel), so the vectors agree
+/// with the shading; they point uphill and their length is the slope's rise
+/// over run. Samples beyond the map clamp to the border, which halves the
+/// gradient across the edge rows and columns. Returns one vector per vertex
+/// in row-major order (`z * width + x`), ready to bake into an RG texture for
+/// flow-map or wind shaders.
+pub fn gradient_field(heightmap: &HeightMap) -> Vec<Vec2> {
+    let inv = (8.0 * heightmap.scale()).recip();
+    sobel_sums(heightmap, GridRect::full(heightmap))
+        .into_iter()
+        .map(|g| g * inv)
+        .collect()
+}
+
+/// Scores how likely each vertex is to collect sediment, from `0` to `1`.
+///
+/// A cheap stand-in for running erosion: sediment settles where the ground
+/// is both gentle and concave, so the score is the cosine of the Sobel slope
+/// angle times the vertex's concavity — the amount the mean of its four
+/// axial neighbours rises above it (edges clamp to the border), normalized
+/// so the most concave vertex of the map reads `1`. Valley floors and basin
+/// bottoms score high; ridges, peaks, and convex or planar slopes score `0`.
+/// Returns one score per vertex in row-major order (`z * width + x`), ready
+/// to drive a sand or silt splat layer. A map with no concave vertex yields
+/// all zeros.
+pub fn deposition_potential(heightmap: &HeightMap) -> Vec<f32> {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let at = |x: isize, z: isize| {
+        heightmap.get(
+            x.clamp(0, w as isize - 1) as usize,
+            z.clamp(0, h as isize - 1) as usize,
+        )
+    };
+    let concavity: Vec<f32> = (0..h as isize)
+        .flat_map(|z| (0..w as isize).map(move |x| (x, z)))
+        .map(|(x, z)| {
+            let mean = (at(x - 1, z) + at(x + 1, z) + at(x, z - 1) + at(x, z + 1)) * 0.25;
+            (mean - at(x, z)).max(0.0)
+        })
+        .collect();
+    let max = concavity.iter().copied().fold(0.0, f32::max);
+    if max <= 0.0 {
+        return vec![0.0; w * h];
+    }
+
+    compute_normals_sobel(heightmap, GridRect::full(heightmap))
+        .into_iter()
+        .zip(concavity)
+        .map(|(n, c)| (n[1].max(0.0) * c / max).clamp(0.0, 1.0))
+        .collect()
+}
+
+/// Computes each vertex's horizontal distance to the nearest vertex below
+/// `sea_level`.
+///
+/// Returns one world-space distance per vertex in row-major order
+/// (`z * width + x`); underwater vertices read `0.0`. Uses a two-pass chamfer
+/// transform with axial steps of one grid scale and diagonal steps of `√2`
+/// scales, which stays within about 8% of the true Euclidean distance —
+/// smooth enough to drive sand and wetness bands along coasts. With no
+/// vertex below `sea_level`, every entry is `f32::INFINITY`.
+pub fn distance_to_water(heightmap: &HeightMap, sea_level: f32) -> Vec<f32> {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let axial = heightmap.scale();
+    let diagonal = axial * std::f32::consts::SQRT_2;
+
+    let mut dist: Vec<f32> = heightmap
+        .data()
+        .iter()
+        .map(|&height| {
+            if height < sea_level {
+                0.0
+            } else {
+                f32::INFINITY
+            }
+        })
+        .collect();
+
+    // Forward pass: neighbours above and to the left.
+    for z in 0..h {
+        for x in 0..w {
+            let mut d = dist[z * w + x];
+            if x > 0 {
+                d = d.min(dist[z * w + x - 1] + axial);
+            }
+            if z > 0 {
+                let up = (z - 1) * w;
+                d = d.min(dist[up + x] + axial);
+                if x > 0 {
+                    d = d.min(dist[up + x - 1] + diagonal);
+                }
+                if x + 1 < w {
+                    d = d.min(dist[up + x + 1] + diagonal);
+                }
+            }
+            dist[z * w + x] = d;
+        }
+    }
+    // Backward pass: neighbours below and to the right.
+    for z in (0..h).rev() {
+        for x in (0..w).rev() {
+            let mut d = dist[z * w + x];
+            if x + 1 < w {
+                d = d.min(dist[z * w + x + 1] + axial);
+            }
+            if z + 1 < h {
+                let down = (z + 1) * w;
+                d = d.min(dist[down + x] + axial);
+                if x + 1 < w {
+                    d = d.min(dist[down + x + 1] + diagonal);
+                }
+                if x > 0 {
+                    d = d.min(dist[down + x - 1] + diagonal);
+                }
+            }
+            dist[z * w + x] = d;
+        }
+    }
+    dist
+}
+
+/// Finds the pits of `heightmap` — vertices strictly lower than all eight
+/// neighbours — and how deep each one's basin is.
+///
+/// Returns `(x, z, depth)` per pit in row-major order, where `depth` is the
+/// height of the basin's lowest spill point above the pit: the level a lake
+/// centred on the pit can fill to before overflowing. The spill point is
+/// found by flooding outward from the pit, always advancing to the lowest
+/// vertex on the flood's rim, until the flood starts descending or reaches
+/// the map edge. Edge vertices are never pits, since water runs off the map
+/// there.
+pub fn find_pits(heightmap: &HeightMap) -> Vec<(usize, usize, f32)> {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let mut pits = Vec::new();
+    // Stamped with the flood that last visited each vertex, so one buffer
+    // serves every pit without clearing it in between.
+    let mut visited = vec![0u32; w * h];
+    for z in 1..h.saturating_sub(1) {
+        for x in 1..w.saturating_sub(1) {
+            let here = heightmap.get(x, z);
+            let lowest = D8_OFFSETS.iter().all(|o| {
+                let (nx, nz) = ((x as i32 + o.x) as usize, (z as i32 + o.y) as usize);
+                heightmap.get(nx, nz) > here
+            });
+            if lowest {
+                let stamp = pits.len() as u32 + 1;
+                let spill = spill_height(heightmap, x, z, &mut visited, stamp);
+                pits.push((x, z, spill - here));
+            }
+        }
+    }
+    pits
+}
+
+/// Height at which water poured into the pit at `(x, z)` overflows.
+///
+/// A vertex counts as visited by this flood when its `visited` entry equals
+/// `stamp`, which must differ from every earlier flood's.
+fn spill_height(heightmap: &HeightMap, x: usize, z: usize, visited: &mut [u32], stamp: u32) -> f32 {
+    #[derive(PartialEq)]
+    struct Rim(f32, usize);
+    impl Eq for Rim {}
+    impl PartialOrd for Rim {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Rim {
+        // Reversed, so the max-heap pops the lowest vertex first.
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            other.0.total_cmp(&self.0)
+        }
+    }
+
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let mut rim = std::collections::BinaryHeap::new();
+    rim.push(Rim(heightmap.get(x, z), z * w + x));
+    visited[z * w + x] = stamp;
+    let mut level = f32::NEG_INFINITY;
+    while let Some(Rim(height, i)) = rim.pop() {
+        if height < level {
+            return level;
+        }
+        level = height;
+        let (x, z) = ((i % w) as i32, (i / w) as i32);
+        if x == 0 || z == 0 || x == w as i32 - 1 || z == h as i32 - 1 {
+            return level;
+        }
+        for o in D8_OFFSETS {
+            let n = (z + o.y) as usize * w + (x + o.x) as usize;
+            if visited[n] != stamp {
+                visited[n] = stamp;
+                rim.push(Rim(heightmap.data()[n], n));
+            }
+        }
+    }
+    level
+}
@@ -0,0 +1,100 @@
+//! Stable content hashes for build caches.
+//!
+//! [`mesh_checksum`] and [`heightmap_checksum`] reduce baked terrain and its
+//! source data to a 64-bit FNV-1a hash. Unlike `std`'s `DefaultHasher`, the
+//! result does not change between runs, platforms, or compiler versions, so
+//! it can be stored next to a baked asset and compared on the next build to
+//! skip regenerating unchanged terrain.
+
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::prelude::*;
+use symbios_ground::HeightMap;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a over little-endian encoded values.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(FNV_OFFSET)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_floats<'a>(&mut self, values: impl IntoIterator<Item = &'a f32>) {
+        for value in values {
+            self.write_u32(value.to_bits());
+        }
+    }
+}
+
+/// Hashes the positions, normals, UV_0, and indices of `mesh` into a stable
+/// 64-bit value.
+///
+/// Floats are hashed by their exact bit patterns, so any change to the
+/// output — even one ULP — changes the checksum, while rebuilding identical
+/// input reproduces it. The primitive topology and the presence of each
+/// buffer are mixed in too, so a missing attribute never collides with an
+/// empty one. Other attributes (tangents, colors, custom data) are ignored.
+pub fn mesh_checksum(mesh: &Mesh) -> u64 {
+    let mut hasher = Fnv1a::new();
+    hasher.write_u32(mesh.primitive_topology() as u32);
+    for attribute in [
+        Mesh::ATTRIBUTE_POSITION,
+        Mesh::ATTRIBUTE_NORMAL,
+        Mesh::ATTRIBUTE_UV_0,
+    ] {
+        match mesh.attribute(attribute) {
+            Some(values) => {
+                hasher.write_u32(1);
+                hasher.write_u64(values.len() as u64);
+                match values {
+                    VertexAttributeValues::Float32x3(v) => hasher.write_floats(v.as_flattened()),
+                    VertexAttributeValues::Float32x2(v) => hasher.write_floats(v.as_flattened()),
+                    other => hasher.write(other.get_bytes()),
+                }
+            }
+            None => hasher.write_u32(0),
+        }
+    }
+    match mesh.indices() {
+        Some(indices) => {
+            hasher.write_u32(1);
+            hasher.write_u64(indices.len() as u64);
+            match indices {
+                Indices::U16(v) => v.iter().for_each(|&i| hasher.write_u32(i as u32)),
+                Indices::U32(v) => v.iter().for_each(|&i| hasher.write_u32(i)),
+            }
+        }
+        None => hasher.write_u32(0),
+    }
+    hasher.0
+}
+
+/// Hashes the dimensions, grid scale, and heights of `heightmap` into a
+/// stable 64-bit value.
+///
+/// Cheaper than building and hashing the mesh; combine it with a hash of the
+/// builder settings to key a bake cache on its inputs.
+pub fn heightmap_checksum(heightmap: &HeightMap) -> u64 {
+    let mut hasher = Fnv1a::new();
+    hasher.write_u64(heightmap.width() as u64);
+    hasher.write_u64(heightmap.height() as u64);
+    hasher.write_u32(heightmap.scale().to_bits());
+    hasher.write_floats(heightmap.data());
+    hasher.0
+}
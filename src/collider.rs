@@ -5,24 +5,55 @@
 //! shape for static terrain — far cheaper than `trimesh` for ray-casting and
 //! contact queries.
 
+use avian3d::parry::na::DMatrix;
+use avian3d::parry::shape::{HeightField, HeightFieldCellStatus, SharedShape};
 use avian3d::prelude::Collider;
 use bevy::prelude::*;
 use symbios_ground::HeightMap;
 
+use crate::mesher::{HeightMapMeshBuilder, QuadDiagonal};
+
+/// The diagonal Avian's heightfield uses to split each cell by default.
+///
+/// Parry interpolates each heightfield cell as two triangles sharing the
+/// `tr–bl` diagonal unless the cell is flagged for "zigzag" subdivision.
+/// [`HeightMapMeshBuilder`](crate::HeightMapMeshBuilder) uses the same
+/// diagonal by default, so a mesh and collider built from the same heightmap
+/// describe the same surface.
+pub const HEIGHTFIELD_DIAGONAL: QuadDiagonal = QuadDiagonal::TopRightBottomLeft;
+
+/// World axis along which the heightfield's matrix **rows** advance.
+///
+/// Parry stores heightfield heights in a matrix indexed `(row, column)`. Row
+/// `i` lies at local `z = -extent_z/2 + i·cell_size`, so rows correspond to
+/// the heightmap's `z` index. This is the opposite of the "rows along X"
+/// wording in Avian's `Collider::heightfield` documentation, and is why
+/// passing `heights[x][z]` naively produces a mirrored surface.
+pub const HEIGHTFIELD_ROW_AXIS: Vec3 = Vec3::Z;
+
+/// World axis along which the heightfield's matrix **columns** advance.
+///
+/// Column `j` lies at local `x = -extent_x/2 + j·cell_size`, matching the
+/// heightmap's `x` index. See [`HEIGHTFIELD_ROW_AXIS`].
+pub const HEIGHTFIELD_COLUMN_AXIS: Vec3 = Vec3::X;
+
 /// Builds an Avian3D `Collider::heightfield` from a [`HeightMap`].
 ///
 /// The collider is centered at the origin of its local space, spanning
-/// `[-world_width/2, world_width/2]` × `[-world_depth/2, world_depth/2]`
-/// in the XZ plane. Heights are in world units.
+/// `[-(w-1)·scale/2, (w-1)·scale/2]` × `[-(h-1)·scale/2, (h-1)·scale/2]`
+/// in the XZ plane — the same extent as the mesh produced by
+/// [`HeightMapMeshBuilder`](crate::HeightMapMeshBuilder). Heights are in
+/// world units. Cells are split along [`HEIGHTFIELD_DIAGONAL`].
 ///
 /// Attach the returned `Collider` to the same entity as your terrain mesh.
 /// Because the heightfield is centered at origin while the mesh starts at
-/// `(0, 0, 0)`, offset the entity's `Transform` by
-/// `(-world_width/2, 0, -world_depth/2)` if you want them to align.
+/// `(0, 0, 0)`, offset the collider by `((w-1)·scale/2, 0, (h-1)·scale/2)`
+/// relative to the mesh (e.g. on a child entity) if you want them to align.
 ///
 /// # Panics
 ///
-/// Panics if the heightmap has zero width or height (upheld by [`HeightMap::new`]).
+/// Panics if the heightmap is smaller than 2×2, as a heightfield needs at
+/// least one cell.
 ///
 /// # Example
 ///
@@ -35,20 +66,485 @@ use symbios_ground::HeightMap;
 /// // commands.spawn((collider, ...));
 /// ```
 pub fn build_heightfield_collider(heightmap: &HeightMap) -> Collider {
+    build_heightfield_collider_with_diagonal(heightmap, HEIGHTFIELD_DIAGONAL)
+}
+
+/// Builds an Avian3D heightfield collider whose cells are split along `diagonal`.
+///
+/// Use this together with
+/// [`HeightMapMeshBuilder::with_diagonal`](crate::HeightMapMeshBuilder::with_diagonal)
+/// when the mesh uses a non-default diagonal, so both surfaces interpolate
+/// each cell identically. See [`build_heightfield_collider`] for the layout.
+///
+/// # Panics
+///
+/// Panics if the heightmap is smaller than 2×2.
+pub fn build_heightfield_collider_with_diagonal(
+    heightmap: &HeightMap,
+    diagonal: QuadDiagonal,
+) -> Collider {
     let w = heightmap.width();
     let h = heightmap.height();
 
-    // Avian's 3D heightfield expects `heights[row][col]` where:
-    //   rows  → subdivisions along X axis (width)
-    //   cols  → subdivisions along Z axis (height)
-    // HeightMap stores data[z * width + x], so we transpose accordingly.
-    let heights: Vec<Vec<f32>> = (0..w)
-        .map(|x| (0..h).map(|z| heightmap.get(x, z)).collect())
+    // Parry's heightfield matrix is indexed `(i, j)` where:
+    //   i (rows)    → subdivisions along Z axis (HEIGHTFIELD_ROW_AXIS)
+    //   j (columns) → subdivisions along X axis (HEIGHTFIELD_COLUMN_AXIS)
+    // HeightMap stores data[z * width + x], so entry (z, x) = get(x, z).
+    let heights = DMatrix::from_fn(h, w, |z, x| heightmap.get(x, z));
+    heightfield_from_matrix(heights, heightmap, diagonal)
+}
+
+/// Builds a coarser heightfield collider that never dips below the terrain.
+///
+/// Keeps roughly every `step`-th vertex along each axis — the coarse grid
+/// has `ceil((n-1)/step) + 1` vertices per axis, spread evenly over the same
+/// extent as [`build_heightfield_collider`] — and gives each coarse vertex
+/// the **maximum** fine height over every coarse cell it touches. Each
+/// coarse cell's corners are then at least as high as every fine vertex
+/// inside it, so the interpolated collider surface lies on or above the
+/// rendered mesh and objects cannot sink into it. The trade-off is that
+/// they may hover slightly over pits and valleys.
+///
+/// `step = 1` reproduces [`build_heightfield_collider`].
+///
+/// # Panics
+///
+/// Panics if `step` is zero or the heightmap is smaller than 2×2.
+pub fn build_heightfield_collider_conservative(heightmap: &HeightMap, step: usize) -> Collider {
+    assert!(step > 0, "step must be at least 1");
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let cw = (w - 1).div_ceil(step) + 1;
+    let ch = (h - 1).div_ceil(step) + 1;
+
+    // Fine vertex range covered by the coarse cells around coarse vertex `i`.
+    let window = |i: usize, coarse: usize, fine: usize| {
+        let at = |j: usize| j as f32 * (fine - 1) as f32 / (coarse - 1) as f32;
+        let lo = at(i.saturating_sub(1)).floor() as usize;
+        let hi = (at((i + 1).min(coarse - 1)).ceil() as usize).min(fine - 1);
+        lo..=hi
+    };
+    let heights = DMatrix::from_fn(ch, cw, |cz, cx| {
+        let xs = window(cx, cw, w);
+        window(cz, ch, h)
+            .flat_map(|z| xs.clone().map(move |x| (x, z)))
+            .map(|(x, z)| heightmap.get(x, z))
+            .fold(f32::NEG_INFINITY, f32::max)
+    });
+    heightfield_from_matrix(heights, heightmap, HEIGHTFIELD_DIAGONAL)
+}
+
+/// Wraps a `(z, x)`-indexed height matrix in a heightfield collider spanning
+/// the same extent as `heightmap`'s mesh.
+fn heightfield_from_matrix(
+    heights: DMatrix<f32>,
+    heightmap: &HeightMap,
+    diagonal: QuadDiagonal,
+) -> Collider {
+    // `scale` is the total world extent of the heightfield on each axis,
+    // matching the mesh's `(n-1)·scale` span. Y scale = 1.0 because heights
+    // are already in world units.
+    let scale = Vec3::new(
+        (heightmap.width() - 1) as f32 * heightmap.scale(),
+        1.0,
+        (heightmap.height() - 1) as f32 * heightmap.scale(),
+    );
+
+    let mut field = HeightField::new(heights, scale.into());
+    if diagonal != HEIGHTFIELD_DIAGONAL {
+        field
+            .cells_statuses_mut()
+            .fill(HeightFieldCellStatus::ZIGZAG_SUBDIVISION);
+    }
+
+    SharedShape::new(field).into()
+}
+
+/// Builds a terrain mesh and its heightfield collider from the same coarse
+/// grid, so physics and visuals always share one LOD.
+///
+/// Downsamples `heightmap` once, keeping every `lod_step`-th vertex as
+/// [`downsample`](crate::ops::downsample) does, and builds the mesh from it
+/// with `builder`. The collider is built from the same grid after the
+/// builder's height preprocessing (NaN policy, geomorph, subdivision, radial
+/// taper, quantization), split along the builder's
+/// [`diagonal`](crate::HeightMapMeshBuilder::with_diagonal), so every
+/// triangle of the mesh lies on the collider surface. Like
+/// [`build_heightfield_collider`], the collider is centered; offset it by
+/// half the mesh extent to align it with the mesh.
+///
+/// Hole masks and other cell culling are not reflected in the heightfield,
+/// nor are the vertical risers of
+/// [`with_height_quantization`](crate::HeightMapMeshBuilder::with_height_quantization):
+/// the collider slopes across each step cell instead.
+///
+/// # Panics
+///
+/// Panics if `lod_step` is zero or the downsampled grid is smaller than 2×2.
+pub fn build_matched(
+    heightmap: &HeightMap,
+    lod_step: usize,
+    builder: &HeightMapMeshBuilder,
+) -> (Mesh, Collider) {
+    let coarse = crate::ops::downsample(heightmap, lod_step);
+    let builder = builder.for_lod(heightmap, lod_step);
+    let mesh = builder.build(&coarse);
+    let collider =
+        build_heightfield_collider_with_diagonal(&builder.prepare(&coarse), builder.diagonal());
+    (mesh, collider)
+}
+
+/// Builds a convex hull enclosing the whole terrain, for broad-phase or
+/// outer collision proxies.
+///
+/// The hull spans the terrain's footprint from its lowest to its highest
+/// height: the four footprint corners at both extremes. A hull through only
+/// the actual corner heights and the single highest and lowest vertices
+/// would not be conservative — a second peak can poke through it — so the
+/// extremes are lifted to full corner columns instead. The result is cheap
+/// to query and always encloses [`world_bounds`](crate::world_bounds).
+/// Non-finite heights are skipped, and flat terrain gets a 1 cm thick slab
+/// so the hull stays non-degenerate. Like [`build_heightfield_collider`], the
+/// collider is centered at its local origin.
+///
+/// # Panics
+///
+/// Panics if the heightmap is smaller than 2×2.
+pub fn build_hull_collider(heightmap: &HeightMap) -> Collider {
+    assert!(
+        heightmap.width() >= 2 && heightmap.height() >= 2,
+        "heightmap must be at least 2×2 to build a hull collider"
+    );
+    let bounds = crate::world_bounds(heightmap);
+    let half = trimesh_offset(heightmap);
+    let lo = bounds.min().y;
+    let hi = bounds.max().y.max(lo + 0.01);
+    let points = [lo, hi]
+        .into_iter()
+        .flat_map(|y| {
+            [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)]
+                .map(|(sx, sz)| Vec3::new(sx * half.x, y, sz * half.z))
+        })
+        .collect();
+    Collider::convex_hull(points).expect("terrain bounds span a non-degenerate box")
+}
+
+/// Builds a trimesh collider for `heightmap` that omits masked hole cells.
+///
+/// Avian's heightfield cannot represent holes, so terrain with cave or tunnel
+/// openings needs a trimesh collider instead. `holes` uses the same
+/// per-cell layout as
+/// [`HeightMapMeshBuilder::with_hole_mask`](crate::HeightMapMeshBuilder::with_hole_mask):
+/// one `bool` per grid cell, `holes[z * (width-1) + x]`, `true` for a hole.
+/// Both triangles of each hole cell are left out, so physics matches the
+/// rendered mesh.
+///
+/// The trimesh is centered at its local origin exactly like
+/// [`build_heightfield_collider`], so it can be swapped in without changing
+/// the collider's offset. Trimeshes are slower to query than heightfields;
+/// prefer the heightfield when the terrain has no holes.
+///
+/// # Panics
+///
+/// Panics if the heightmap is smaller than 2×2 or `holes` does not have
+/// `(width-1)·(height-1)` entries.
+pub fn build_trimesh_collider_with_holes(heightmap: &HeightMap, holes: &[bool]) -> Collider {
+    let (vertices, triangles) = holed_trimesh(heightmap, holes);
+    Collider::trimesh(vertices, triangles)
+}
+
+/// Builds the hole-cut trimesh of [`build_trimesh_collider_with_holes`]
+/// with cap geometry added around the holes.
+///
+/// Each cap is a `(vertices, triangles)` mesh — such as the funnel or
+/// tunnel mouth modelled around a cave entrance — given in the same local
+/// space as [`HeightMapMeshBuilder::build`](crate::HeightMapMeshBuilder::build)
+/// positions, so geometry authored against the render mesh lines up with
+/// the hole. Caps are centered along with the terrain.
+///
+/// Parry does not allow a trimesh inside a compound shape, so rather than a
+/// compound of separate trimeshes the result is a single trimesh: the terrain
+/// triangles first, then each cap's triangles in order.
+///
+/// # Panics
+///
+/// Panics if the heightmap is smaller than 2×2, `holes` does not have
+/// `(width-1)·(height-1)` entries, or a cap index is out of range.
+pub fn build_capped_trimesh_collider(
+    heightmap: &HeightMap,
+    holes: &[bool],
+    caps: impl IntoIterator<Item = (Vec<Vec3>, Vec<[u32; 3]>)>,
+) -> Collider {
+    let offset = trimesh_offset(heightmap);
+    let (mut vertices, mut triangles) = holed_trimesh(heightmap, holes);
+    for (cap_vertices, cap_triangles) in caps {
+        let base = vertices.len() as u32;
+        for tri in &cap_triangles {
+            assert!(
+                tri.iter().all(|&i| (i as usize) < cap_vertices.len()),
+                "cap triangle {tri:?} indexes past its {} vertices",
+                cap_vertices.len()
+            );
+        }
+        vertices.extend(cap_vertices.into_iter().map(|v| v - offset));
+        triangles.extend(cap_triangles.into_iter().map(|tri| tri.map(|i| base + i)));
+    }
+    Collider::trimesh(vertices, triangles)
+}
+
+/// Centered vertices and triangles of the terrain mesh with `holes` cut out.
+fn holed_trimesh(heightmap: &HeightMap, holes: &[bool]) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let mesh = crate::HeightMapMeshBuilder::new()
+        .with_hole_mask(holes)
+        .build(heightmap);
+
+    let offset = trimesh_offset(heightmap);
+    let vertices = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .and_then(|positions| positions.as_float3())
+        .expect("terrain mesh has Float32x3 positions")
+        .iter()
+        .map(|p| Vec3::from(*p) - offset)
+        .collect();
+    let indices: Vec<u32> = mesh
+        .indices()
+        .expect("terrain mesh is indexed")
+        .iter()
+        .map(|i| i as u32)
         .collect();
+    let triangles = indices
+        .chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect();
+    (vertices, triangles)
+}
 
-    // `scale` is the total world extent of the heightfield on each axis.
-    // Y scale = 1.0 because heights are already in world units.
-    let scale = Vec3::new(heightmap.world_width(), 1.0, heightmap.world_depth());
+/// Shift that centers mesh-space positions like the heightfield collider.
+fn trimesh_offset(heightmap: &HeightMap) -> Vec3 {
+    Vec3::new(
+        (heightmap.width() - 1) as f32,
+        0.0,
+        (heightmap.height() - 1) as f32,
+    ) * heightmap.scale()
+        * 0.5
+}
+
+/// Returns the diagonal a heightfield collider uses to split its cells.
+///
+/// Inspects the first cell of the heightfield; colliders produced by this
+/// module use a uniform split. Returns `None` if the collider is not a
+/// heightfield.
+pub fn heightfield_diagonal(collider: &Collider) -> Option<QuadDiagonal> {
+    let field = collider.shape().as_heightfield()?;
+    let status = field.cell_status(0, 0);
+    Some(
+        if status.contains(HeightFieldCellStatus::ZIGZAG_SUBDIVISION) {
+            QuadDiagonal::TopLeftBottomRight
+        } else {
+            QuadDiagonal::TopRightBottomLeft
+        },
+    )
+}
+
+/// Error returned when a heightfield collider does not match its heightmap's orientation.
+///
+/// Produced by [`verify_collider_orientation`] and, with the `debug` feature,
+/// `debug_check_collider_orientation`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColliderOrientationError {
+    /// World-space XZ probe point, relative to the mesh origin.
+    pub point: Vec2,
+    /// Surface height of the mesh at `point`.
+    pub expected: f32,
+    /// Surface height of the collider at `point`, or `None` if the probe ray missed.
+    pub actual: Option<f32>,
+}
+
+impl std::fmt::Display for ColliderOrientationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.actual {
+            Some(actual) => write!(
+                f,
+                "collider surface at ({}, {}) is {actual}, but the heightmap gives {}; \
+                 the collider is likely mirrored or transposed (rows must advance along Z, \
+                 columns along X)",
+                self.point.x, self.point.y, self.expected
+            ),
+            None => write!(
+                f,
+                "collider has no surface at ({}, {}), where the heightmap gives {}; \
+                 the collider extent does not match the mesh",
+                self.point.x, self.point.y, self.expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ColliderOrientationError {}
+
+/// Checks that `collider` reproduces `heightmap`'s surface in the expected orientation.
+///
+/// Probes a handful of asymmetric points near each corner and along the
+/// diagonals with vertical rays, and compares the collider's surface height to
+/// the mesh surface at the same point. The collider is assumed to be centered
+/// at its local origin as produced by [`build_heightfield_collider`]; probe
+/// points are given relative to the mesh origin in the error.
+///
+/// Use this to validate colliders assembled by hand. A symmetric heightmap
+/// cannot reveal a mirrored collider, so test with asymmetric data.
+pub fn verify_collider_orientation(
+    heightmap: &HeightMap,
+    collider: &Collider,
+) -> Result<(), ColliderOrientationError> {
+    use avian3d::parry::math::{Point, Vector};
+    use avian3d::parry::query::Ray;
+
+    let extent = Vec2::new(
+        (heightmap.width() - 1) as f32,
+        (heightmap.height() - 1) as f32,
+    ) * heightmap.scale();
+    let top = collider.shape().compute_local_aabb().maxs.y + 1.0;
+
+    // Fractions chosen so no probe maps onto itself under a transpose or mirror.
+    const PROBES: [(f32, f32); 7] = [
+        (0.05, 0.1),
+        (0.9, 0.15),
+        (0.1, 0.85),
+        (0.95, 0.9),
+        (0.3, 0.6),
+        (0.7, 0.4),
+        (0.55, 0.2),
+    ];
+    for (fx, fz) in PROBES {
+        let point = extent * Vec2::new(fx, fz);
+        let Some((verts, normal)) = crate::query::triangle_at(heightmap, point.x, point.y) else {
+            continue;
+        };
+        // Height of the triangle's plane at `point`.
+        let expected = verts[0].y
+            - (normal.x * (point.x - verts[0].x) + normal.z * (point.y - verts[0].z)) / normal.y;
+
+        let local = point - extent * 0.5;
+        let ray = Ray::new(
+            Point::new(local.x, top, local.y),
+            Vector::new(0.0, -1.0, 0.0),
+        );
+        let actual = collider
+            .shape()
+            .cast_local_ray(&ray, f32::MAX, true)
+            .map(|toi| top - toi);
+
+        let matches = actual.is_some_and(|a| (a - expected).abs() <= 1e-3 * (1.0 + expected.abs()));
+        if !matches {
+            return Err(ColliderOrientationError {
+                point,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Builds a heightfield collider for `heightmap` and verifies its orientation.
+///
+/// A development aid for catching mirrored or rotated physics surfaces: it
+/// runs [`build_heightfield_collider`] and then [`verify_collider_orientation`]
+/// on the result. Requires the `debug` feature.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::collider::debug_check_collider_orientation;
+///
+/// debug_check_collider_orientation(&heightmap).expect("collider orientation");
+/// ```
+#[cfg(feature = "debug")]
+pub fn debug_check_collider_orientation(
+    heightmap: &HeightMap,
+) -> Result<(), ColliderOrientationError> {
+    verify_collider_orientation(heightmap, &build_heightfield_collider(heightmap))
+}
+
+/// Component that keeps an entity's heightfield [`Collider`] in sync with a
+/// heightmap, rebuilding it off the main thread.
+///
+/// Requires the `async` feature. Add [`spawn_collider_tasks`] and
+/// [`apply_collider_tasks`] to your `Update` schedule. Whenever the source is
+/// dirty — on creation and after [`mark_dirty`](Self::mark_dirty) — the first
+/// system clones the heightmap into a task on the
+/// [`AsyncComputeTaskPool`](bevy::tasks::AsyncComputeTaskPool) running
+/// [`build_heightfield_collider`]; the second inserts the finished collider
+/// on the entity, replacing the previous one.
+///
+/// The clone costs one copy of the height data per rebuild (256 KiB for a
+/// 256² map), paid on the main thread; the expensive heightfield
+/// construction is not. Marking the source dirty while a rebuild is in
+/// flight lets that rebuild finish and be inserted, then starts another from
+/// the latest heights, so a source dirtied every frame still gets colliders.
+#[cfg(feature = "async")]
+#[derive(Component)]
+pub struct AsyncColliderSource {
+    /// Heights the collider is built from. Call
+    /// [`mark_dirty`](Self::mark_dirty) after changing them.
+    pub heightmap: HeightMap,
+    dirty: bool,
+    task: Option<bevy::tasks::Task<Collider>>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncColliderSource {
+    /// Creates a source whose collider is built on the next
+    /// [`spawn_collider_tasks`] run.
+    pub fn new(heightmap: HeightMap) -> Self {
+        Self {
+            heightmap,
+            dirty: true,
+            task: None,
+        }
+    }
+
+    /// Marks the heights as changed so the collider is rebuilt.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Returns `true` while a rebuild is running.
+    pub fn is_building(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+/// Bevy system that starts a background collider build for every dirty
+/// [`AsyncColliderSource`] that is not already building. Requires the `async`
+/// feature.
+#[cfg(feature = "async")]
+pub fn spawn_collider_tasks(mut sources: Query<&mut AsyncColliderSource>) {
+    let pool = bevy::tasks::AsyncComputeTaskPool::get();
+    for mut source in &mut sources {
+        if !source.dirty || source.task.is_some() {
+            continue;
+        }
+        let heightmap = source.heightmap.clone();
+        source.task = Some(pool.spawn(async move { build_heightfield_collider(&heightmap) }));
+        source.dirty = false;
+    }
+}
 
-    Collider::heightfield(heights, scale)
+/// Bevy system that inserts finished colliders from
+/// [`spawn_collider_tasks`] on their entities. Requires the `async` feature.
+#[cfg(feature = "async")]
+pub fn apply_collider_tasks(
+    mut commands: Commands,
+    mut sources: Query<(Entity, &mut AsyncColliderSource)>,
+) {
+    for (entity, mut source) in &mut sources {
+        let Some(task) = source.task.as_mut() else {
+            continue;
+        };
+        if let Some(collider) = bevy::tasks::futures::check_ready(task) {
+            source.task = None;
+            commands.entity(entity).insert(collider);
+        }
+    }
 }
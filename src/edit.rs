@@ -0,0 +1,279 @@
+//! Debounced rebuilds for interactive terrain editing.
+//!
+//! Dragging a sculpt brush edits the terrain every frame, but rebuilding the
+//! mesh, collider, and splat texture that often wastes most of the work.
+//! Edits record their dirty rectangles in a [`TerrainEditQueue`];
+//! [`flush_terrain_edits`] unions them and sends a single [`TerrainRebuild`]
+//! once the queue's interval has passed or it holds enough edits, and
+//! [`apply_terrain_rebuilds`] carries each rebuild out on the crate's own
+//! terrain types. [`diff_regions`] finds the dirty rectangles between two
+//! versions of a heightmap, e.g. after a network delta.
+
+use bevy::prelude::*;
+use symbios_ground::HeightMap;
+
+#[cfg(feature = "async")]
+use crate::collider::AsyncColliderSource;
+use crate::live::TerrainLive;
+use crate::splat::GroundMaterialSettings;
+
+/// Message asking for the terrain inside `rect` to be rebuilt.
+///
+/// Sent by [`flush_terrain_edits`]; `rect` is the union of every dirty
+/// rectangle queued since the previous rebuild, in heightmap vertices.
+/// [`apply_terrain_rebuilds`] consumes it; read it in your own systems to
+/// rebuild anything else derived from the heights.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerrainRebuild {
+    /// Vertices to rebuild, `min` inclusive and `max` exclusive.
+    pub rect: URect,
+    /// Whether the splat weight map may have changed along with the
+    /// heights, so the splat texture is re-uploaded too. `false` for
+    /// height-only changes such as
+    /// [`modulate_terrain_heights`](crate::modulate_terrain_heights).
+    pub splat: bool,
+}
+
+/// Resource accumulating dirty rectangles between terrain rebuilds.
+///
+/// Call [`push`](Self::push) for every edit and add [`flush_terrain_edits`]
+/// followed by [`apply_terrain_rebuilds`] to your `Update` schedule. The
+/// queue flushes `interval` seconds after its first pending edit, or as soon
+/// as it holds [`max_edits`](Self::with_max_edits) edits, whichever comes
+/// first.
+///
+/// # Example
+///
+/// ```ignore
+/// app.add_message::<TerrainRebuild>()
+///     .insert_resource(TerrainEditQueue::new(0.25))
+///     .add_systems(Update, (flush_terrain_edits, apply_terrain_rebuilds).chain());
+///
+/// fn sculpt(mut terrain: Single<&mut TerrainLive>, mut queue: ResMut<TerrainEditQueue>) {
+///     terrain.heightmap_mut().set(10, 12, 4.0);
+///     queue.push(URect::new(10, 12, 11, 13));
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct TerrainEditQueue {
+    interval: f32,
+    max_edits: usize,
+    pending: Option<URect>,
+    edits: usize,
+    waited: f32,
+}
+
+impl TerrainEditQueue {
+    /// Creates an empty queue that flushes `interval` seconds after its
+    /// first pending edit. Clamped to non-negative; `0` flushes on the next
+    /// [`flush_terrain_edits`] run.
+    pub fn new(interval: f32) -> Self {
+        Self {
+            interval: interval.max(0.0),
+            max_edits: usize::MAX,
+            pending: None,
+            edits: 0,
+            waited: 0.0,
+        }
+    }
+
+    /// Also flushes as soon as `count` edits are pending, without waiting
+    /// for the interval. Clamped to at least 1.
+    pub fn with_max_edits(mut self, count: usize) -> Self {
+        self.max_edits = count.max(1);
+        self
+    }
+
+    /// Queues an edit of the vertices in `rect` (`max` exclusive).
+    pub fn push(&mut self, rect: URect) {
+        self.pending = Some(match self.pending {
+            Some(pending) => pending.union(rect),
+            None => rect,
+        });
+        self.edits += 1;
+    }
+
+    /// Union of the pending dirty rectangles, if any.
+    pub fn pending(&self) -> Option<URect> {
+        self.pending
+    }
+
+    /// Number of edits queued since the last flush.
+    pub fn len(&self) -> usize {
+        self.edits
+    }
+
+    /// Returns `true` when no edit is pending.
+    pub fn is_empty(&self) -> bool {
+        self.edits == 0
+    }
+}
+
+/// Bevy system that sends one [`TerrainRebuild`] per batch of queued edits.
+///
+/// While edits are pending it accumulates the frame time; once that reaches
+/// the queue's interval, or the edit count reaches its maximum, it sends the
+/// union rectangle and empties the queue. Register the message with
+/// `app.add_message::<TerrainRebuild>()`.
+pub fn flush_terrain_edits(
+    time: Res<Time>,
+    mut queue: ResMut<TerrainEditQueue>,
+    mut rebuilds: MessageWriter<TerrainRebuild>,
+) {
+    let Some(rect) = queue.pending else {
+        return;
+    };
+    queue.waited += time.delta_secs();
+    if queue.waited >= queue.interval || queue.edits >= queue.max_edits {
+        rebuilds.write(TerrainRebuild { rect, splat: true });
+        queue.pending = None;
+        queue.edits = 0;
+        queue.waited = 0.0;
+    }
+}
+
+/// Bevy system that carries out every [`TerrainRebuild`] on the crate's
+/// terrain types.
+///
+/// For each rebuild it re-meshes `rect` of every [`TerrainLive`] from its
+/// current heights with [`TerrainLive::refresh`]. If the rebuild has
+/// [`splat`](TerrainRebuild::splat) set, it also marks the
+/// [`GroundMaterialSettings`] dirty, if present, so [`sync_splat_texture`]
+/// re-uploads the weight map. With the `async` feature, it also marks every
+/// [`AsyncColliderSource`] dirty; a source on the same entity as a
+/// [`TerrainLive`] first has `rect` of its heightmap copied from the live
+/// heights. Several rebuilds in one run are unioned into one. Run it after
+/// [`flush_terrain_edits`].
+///
+/// [`sync_splat_texture`]: crate::sync_splat_texture
+pub fn apply_terrain_rebuilds(
+    mut rebuilds: MessageReader<TerrainRebuild>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    #[cfg(not(feature = "async"))] mut terrains: Query<&mut TerrainLive>,
+    #[cfg(feature = "async")] mut terrains: Query<(
+        &mut TerrainLive,
+        Option<&mut AsyncColliderSource>,
+    )>,
+    #[cfg(feature = "async")] mut colliders: Query<&mut AsyncColliderSource, Without<TerrainLive>>,
+    splat: Option<ResMut<GroundMaterialSettings>>,
+) {
+    let Some(TerrainRebuild {
+        rect,
+        splat: weights,
+    }) = rebuilds.read().copied().reduce(|a, b| TerrainRebuild {
+        rect: a.rect.union(b.rect),
+        splat: a.splat || b.splat,
+    })
+    else {
+        return;
+    };
+
+    #[cfg(not(feature = "async"))]
+    for mut live in &mut terrains {
+        live.refresh(&mut meshes, rect);
+    }
+    #[cfg(feature = "async")]
+    {
+        for (mut live, source) in &mut terrains {
+            live.refresh(&mut meshes, rect);
+            if let Some(mut source) = source {
+                let heights = live.heightmap();
+                for z in rect.min.y as usize..rect.max.y as usize {
+                    for x in rect.min.x as usize..rect.max.x as usize {
+                        source.heightmap.set(x, z, heights.get(x, z));
+                    }
+                }
+                source.mark_dirty();
+            }
+        }
+        for mut source in &mut colliders {
+            source.mark_dirty();
+        }
+    }
+
+    if weights && let Some(mut splat) = splat {
+        splat.mark_dirty();
+    }
+}
+
+/// Returns bounding rectangles of the vertices that differ between two
+/// versions of a heightmap.
+///
+/// A vertex has changed when its heights differ by more than `epsilon` (or
+/// exactly one of them is NaN). Changed vertices touching each other,
+/// diagonals included, form one region; regions whose rectangles overlap or
+/// touch are then merged until none do, so scattered edits yield a few
+/// rectangles rather than one per vertex. Rectangles are in heightmap
+/// vertices with `max` exclusive, like [`TerrainRebuild::rect`], sorted by
+/// their `min` corner (`z` first). Returns an empty list when nothing
+/// changed.
+///
+/// # Panics
+///
+/// Panics if the heightmaps have different dimensions.
+pub fn diff_regions(old: &HeightMap, new: &HeightMap, epsilon: f32) -> Vec<URect> {
+    let (w, h) = (old.width(), old.height());
+    assert_eq!(
+        (w, h),
+        (new.width(), new.height()),
+        "heightmaps must have the same dimensions to diff"
+    );
+    let mut changed: Vec<bool> = old
+        .data()
+        .iter()
+        .zip(new.data())
+        .map(|(&a, &b)| (a - b).abs() > epsilon || a.is_nan() != b.is_nan())
+        .collect();
+
+    // Flood-fill each 8-connected region of changed vertices into its bounds.
+    let mut rects: Vec<URect> = Vec::new();
+    let mut stack = Vec::new();
+    for start in 0..w * h {
+        if !changed[start] {
+            continue;
+        }
+        changed[start] = false;
+        stack.push(start);
+        let cell = |x: usize, z: usize| URect::new(x as u32, z as u32, x as u32 + 1, z as u32 + 1);
+        let mut rect = cell(start % w, start / w);
+        while let Some(i) = stack.pop() {
+            let (x, z) = (i % w, i / w);
+            rect = rect.union(cell(x, z));
+            for nz in z.saturating_sub(1)..=(z + 1).min(h - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(w - 1) {
+                    let n = nz * w + nx;
+                    if changed[n] {
+                        changed[n] = false;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+        rects.push(rect);
+    }
+
+    // Merge rectangles that overlap or share an edge or corner.
+    let touches = |a: &URect, b: &URect| {
+        a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+    };
+    let mut merged = true;
+    while merged {
+        merged = false;
+        let mut i = 0;
+        while i < rects.len() {
+            let mut j = i + 1;
+            while j < rects.len() {
+                if touches(&rects[i], &rects[j]) {
+                    let other = rects.swap_remove(j);
+                    rects[i] = rects[i].union(other);
+                    merged = true;
+                } else {
+                    j += 1;
+                }
+            }
+            i += 1;
+        }
+    }
+    rects.sort_by_key(|r| (r.min.y, r.min.x));
+    rects
+}
@@ -0,0 +1,72 @@
+//! Heightmap export for external terrain tools.
+//!
+//! [`save_heightmap_png`] writes a [`HeightMap`] as a 16-bit grayscale PNG,
+//! the interchange format World Machine, Gaea, and most engines import.
+//!
+//! Requires the `image-export` feature.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use png::{BitDepth, ColorType, Encoder};
+use symbios_ground::HeightMap;
+
+/// Saves `heightmap` to `path` as a 16-bit grayscale PNG.
+///
+/// See [`write_heightmap_png`] for how heights map to pixel values.
+///
+/// # Example
+///
+/// ```ignore
+/// save_heightmap_png(&heightmap, "island.png", Some((0.0, 100.0)))?;
+/// ```
+pub fn save_heightmap_png(
+    heightmap: &HeightMap,
+    path: impl AsRef<Path>,
+    range: Option<(f32, f32)>,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write_heightmap_png(heightmap, &mut file, range)?;
+    file.flush()
+}
+
+/// Writes `heightmap` as a 16-bit grayscale PNG to `writer`.
+///
+/// One pixel per vertex, row `z` of the image holding row `z` of the map.
+/// Heights are mapped linearly from `range` — `(low, high)` in world units —
+/// onto `0..=65535`, clamping anything outside it. With `None` the map's own
+/// minimum and maximum are used, so the full 16-bit precision is spent on the
+/// heights present; a perfectly flat map then writes all zeros. Record the
+/// range to restore world heights on import.
+pub fn write_heightmap_png(
+    heightmap: &HeightMap,
+    writer: impl Write,
+    range: Option<(f32, f32)>,
+) -> io::Result<()> {
+    let (low, high) = range.unwrap_or_else(|| {
+        heightmap
+            .data()
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| {
+                (lo.min(h), hi.max(h))
+            })
+    });
+    let span = high - low;
+    let pixels: Vec<u8> = heightmap
+        .data()
+        .iter()
+        .flat_map(|&h| {
+            let t = if span > 0.0 { (h - low) / span } else { 0.0 };
+            ((t.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16).to_be_bytes()
+        })
+        .collect();
+
+    let mut encoder = Encoder::new(writer, heightmap.width() as u32, heightmap.height() as u32);
+    encoder.set_color(ColorType::Grayscale);
+    encoder.set_depth(BitDepth::Sixteen);
+    let mut png = encoder.write_header()?;
+    png.write_image_data(&pixels)?;
+    png.finish()?;
+    Ok(())
+}
@@ -9,6 +9,27 @@
 //!   topology, smooth normals, and tiling UV coordinates via [`HeightMapMeshBuilder`].
 //! - **Splat textures**: Convert a `WeightMap` to a Bevy [`Image`] (RGBA8 GPU texture)
 //!   for use with terrain shaders via [`splat`].
+//! - **Surface queries**: Look up the exact rendered triangle under a world
+//!   point via [`query`].
+//! - **Terrain analysis**: Derive per-cell data such as D8 flow directions
+//!   for rivers via [`analysis`].
+//! - **Navmesh input**: Produce a coarse, hole-aware walkable triangle soup
+//!   for navmesh bakers via [`navmesh`].
+//! - **Heightmap operations**: Transform heightmaps before meshing (e.g.
+//!   mirroring into symmetric islands) via [`ops`].
+//! - **Previews**: Rasterize a small hillshaded top-down image of the terrain
+//!   for impostors or minimaps via [`preview`].
+//! - **Checksums**: Hash baked meshes and heightmaps into stable 64-bit
+//!   values for build caches via [`checksum`].
+//! - **Edit batching**: Debounce rapid edits into one rebuild of their union
+//!   rectangle via [`edit`].
+//! - **Live sculpting**: Patch heights of a spawned mesh in place, updating
+//!   only the affected positions and normals, or animate them with a
+//!   function of time, via [`live`].
+//! - **LOD switching**: Swap terrain meshes by camera distance, with
+//!   hysteresis, via [`lod`].
+//! - **One-call setup**: Spawn a fully configured PBR terrain entity from a
+//!   heightmap and weight map via [`spawn_terrain`].
 //! - **Physics colliders** (optional, `physics` feature): Generate an Avian3D
 //!   `Collider::heightfield` from a `HeightMap` via [`collider`].
 //!
@@ -16,6 +37,19 @@
 //!
 //! - `physics`: Enables [`collider`] and [`collider::build_heightfield_collider`]
 //!   for Avian3D integration.
+//! - `async` (implies `physics`): Enables
+//!   [`collider::AsyncColliderSource`] for rebuilding the heightfield
+//!   collider on the async compute pool.
+//! - `rayon`: Enables [`HeightMapMeshBuilder::build_chunked_parallel`] for
+//!   multithreaded chunk generation.
+//! - `metrics`: Enables [`HeightMapMeshBuilder::with_metrics_callback`] for
+//!   per-phase build timings.
+//! - `tiled`: Enables [`tiled`], a compressed on-disk tile format for
+//!   streaming heightmaps too large to hold in memory.
+//! - `image-export`: Enables [`export`], saving heightmaps as 16-bit
+//!   grayscale PNGs for external terrain tools.
+//! - `debug`: Enables development-time verifiers such as
+//!   `collider::debug_check_collider_orientation` (with `physics`).
 //!
 //! # Example
 //!
@@ -44,14 +78,82 @@
 //! }
 //! ```
 
+pub mod analysis;
+pub mod checksum;
+pub mod edit;
+pub mod live;
+pub mod lod;
 pub mod mesher;
+pub mod navmesh;
+pub mod ops;
+pub mod preview;
+pub mod query;
+pub mod spawn;
 pub mod splat;
 
 #[cfg(feature = "physics")]
 pub mod collider;
+#[cfg(feature = "image-export")]
+pub mod export;
+#[cfg(feature = "tiled")]
+pub mod tiled;
 
-pub use mesher::{HeightMapMeshBuilder, NormalMethod};
-pub use splat::{GroundMaterialSettings, SplatTexture, splat_to_image, sync_splat_texture};
+pub use analysis::{
+    ASPECT_FLAT, D8_OFFSETS, FLOW_PIT, aspect_map, bake_hillshade, deposition_potential,
+    distance_to_water, find_pits, flow_directions, gradient_field, slope_mask,
+};
+pub use checksum::{heightmap_checksum, mesh_checksum};
+pub use edit::{
+    TerrainEditQueue, TerrainRebuild, apply_terrain_rebuilds, diff_regions, flush_terrain_edits,
+};
+pub use live::{TerrainHeightModulator, TerrainLive, modulate_terrain_heights};
+pub use lod::{TerrainLod, update_terrain_lod};
+#[cfg(feature = "metrics")]
+pub use mesher::BuildMetrics;
+pub use mesher::{
+    ATTRIBUTE_BARYCENTRIC, ATTRIBUTE_HEIGHT, ATTRIBUTE_NORMAL_OCT16, BoundingSphere, ChunkNode,
+    ChunkTree, CustomAttributeValue, Edge, HeightMapMeshBuilder, MeshSizeEstimate, NanPolicy,
+    NormalEncoding, NormalField, NormalMethod, NormalMethodSelector, QuadDiagonal,
+    SharedIndexBuffer, TangentError, Topology, UvMode, decode_octahedral, encode_octahedral,
+    estimate_mesh_bytes, recompute_tangents, shared_index_buffer, update_normals, weld_and_clean,
+    weld_seam,
+};
+pub use navmesh::{NavmeshOptions, build_navmesh_geometry};
+pub use ops::{MirrorMode, clamp_max_slope, despike, downsample, mirror_heightmap, resample};
+pub use preview::{
+    PREVIEW_LAYER_COLORS, bake_ao_image, buildable_mask_image, buildable_mask_image_feathered,
+    composite_weight_colors, edge_fade_image, height_palette_image, render_terrain_preview,
+};
+pub use query::{
+    cross_section, pick_cell, sample_height_bilinear, sample_heights_bilinear,
+    sample_normal_bilinear, sample_normals_bilinear, triangle_at, triangle_at_with_diagonal,
+};
+pub use spawn::{
+    fit_to_box, spawn_terrain, terrain_alignment, terrain_standard_material, world_bounds,
+    world_extent,
+};
+pub use splat::{
+    GroundMaterialSettings, SplatCrossfade, SplatError, SplatImageOptions, SplatTexture, SplatTint,
+    WeightInterpretation, WeightMap8, WeightMapLayout, dominant_layer, pack_layers_to_image,
+    sample_weights_bilinear, splat_chunked, splat_to_image, splat_to_image_with_layout,
+    splat_to_image_with_options, splat_to_images_split, splat_write_into, sync_splat_texture,
+    update_splat_crossfade,
+};
 
 #[cfg(feature = "physics")]
-pub use collider::build_heightfield_collider;
+pub use collider::{
+    ColliderOrientationError, HEIGHTFIELD_COLUMN_AXIS, HEIGHTFIELD_DIAGONAL, HEIGHTFIELD_ROW_AXIS,
+    build_capped_trimesh_collider, build_heightfield_collider,
+    build_heightfield_collider_conservative, build_heightfield_collider_with_diagonal,
+    build_hull_collider, build_matched, build_trimesh_collider_with_holes, heightfield_diagonal,
+    verify_collider_orientation,
+};
+
+#[cfg(feature = "image-export")]
+pub use export::{save_heightmap_png, write_heightmap_png};
+
+#[cfg(feature = "tiled")]
+pub use tiled::{TiledHeightMap, TiledHeightMapError, write_tiled_heightmap};
+
+#[cfg(feature = "async")]
+pub use collider::{AsyncColliderSource, apply_collider_tasks, spawn_collider_tasks};
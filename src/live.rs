@@ -0,0 +1,349 @@
+//! In-place updates of a spawned terrain mesh for live sculpting.
+//!
+//! [`TerrainLive`] keeps the authoritative [`HeightMap`] next to the handle
+//! of the mesh built from it. [`TerrainLive::patch_heights`] writes new
+//! heights for a rectangle, moves the matching vertices, and recomputes only
+//! the normals the edit can affect, instead of rebuilding the whole mesh.
+//! [`TerrainHeightModulator`] drives those patches from a function of time,
+//! for tides, breathing terrain, or scripted geology.
+
+use std::sync::Arc;
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use symbios_ground::HeightMap;
+
+use crate::edit::TerrainRebuild;
+use crate::mesher::{GridRect, HeightMapMeshBuilder};
+
+/// A live-editable terrain mesh and the heightmap it was built from.
+///
+/// The mesh must have one vertex per heightmap vertex in row-major order, as
+/// [`HeightMapMeshBuilder::build`] produces with its default index options —
+/// no flat merging, holes, per-cell UVs, welding, subdivisions, or height
+/// quantization. `builder` should be the one the mesh was built with: patches
+/// run through its height preprocessing (NaN policy, geomorph, radial taper)
+/// and its normal encoding, so patched positions and normals match a fresh
+/// build.
+///
+/// # Example
+///
+/// ```ignore
+/// let mesh = builder.build(&heightmap);
+/// let live = TerrainLive::new(meshes.add(mesh), heightmap, builder);
+/// // Raise a 3×3 patch.
+/// live.patch_heights(&mut meshes, URect::new(10, 10, 13, 13), &[5.0; 9]);
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct TerrainLive {
+    /// Handle of the mesh kept in sync with the heightmap.
+    pub mesh: Handle<Mesh>,
+    heightmap: HeightMap,
+    prepared: HeightMap,
+    builder: HeightMapMeshBuilder,
+}
+
+impl TerrainLive {
+    /// Wraps the mesh at `mesh`, built from `heightmap` with `builder`.
+    pub fn new(mesh: Handle<Mesh>, heightmap: HeightMap, builder: HeightMapMeshBuilder) -> Self {
+        let prepared = builder.prepare(&heightmap).into_owned();
+        Self {
+            mesh,
+            heightmap,
+            prepared,
+            builder,
+        }
+    }
+
+    /// The current heights, including every patch applied so far.
+    pub fn heightmap(&self) -> &HeightMap {
+        &self.heightmap
+    }
+
+    /// Mutable access to the heights, for edits meshed later by
+    /// [`refresh`](Self::refresh) — e.g. from
+    /// [`apply_terrain_rebuilds`](crate::apply_terrain_rebuilds) once a
+    /// [`TerrainEditQueue`](crate::TerrainEditQueue) flushes.
+    pub fn heightmap_mut(&mut self) -> &mut HeightMap {
+        &mut self.heightmap
+    }
+
+    /// Replaces the heights of the vertices in `rect` and updates the mesh.
+    ///
+    /// `rect` is a half-open vertex range — `min` inclusive, `max` exclusive
+    /// — and `new_heights` holds its heights row by row. The heightmap is
+    /// updated and the builder's preprocessing re-run for `rect` (plus a
+    /// one-vertex border with geomorphing), whose results become the Y of
+    /// the matching mesh positions. Normals are then recomputed for that
+    /// area plus a one-vertex border (wider with normal smoothing), since
+    /// the triangles around the edit change shape too.
+    /// Taking the mesh mutably marks the asset changed, so Bevy re-uploads
+    /// it. Returns `false`, changing only the heightmap, if the mesh asset is
+    /// not loaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rect` is empty or exceeds the heightmap, `new_heights` does
+    /// not have one entry per vertex of `rect`, or the mesh does not have one
+    /// vertex per heightmap vertex.
+    pub fn patch_heights(
+        &mut self,
+        meshes: &mut Assets<Mesh>,
+        rect: URect,
+        new_heights: &[f32],
+    ) -> bool {
+        let changed = self.grid_rect(rect);
+        assert_eq!(
+            new_heights.len(),
+            changed.width() * changed.height(),
+            "new_heights must have one entry per vertex of the patch"
+        );
+
+        for (z, row) in (changed.z0..=changed.z1).zip(new_heights.chunks_exact(changed.width())) {
+            for (x, &height) in (changed.x0..=changed.x1).zip(row) {
+                self.heightmap.set(x, z, height);
+            }
+        }
+        self.refresh(meshes, rect)
+    }
+
+    /// Brings the mesh up to date with the current heights of the vertices
+    /// in `rect`, after editing them through
+    /// [`heightmap_mut`](Self::heightmap_mut).
+    ///
+    /// `rect` is half-open like in [`patch_heights`](Self::patch_heights),
+    /// which edits and refreshes in one call. When no vertex in `rect` ends
+    /// up at a different height, the mesh is left untouched, so it is not
+    /// re-uploaded. Returns `false`, changing nothing, if the mesh asset is
+    /// not loaded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rect` is empty or exceeds the heightmap, or the mesh does
+    /// not have one vertex per heightmap vertex.
+    pub fn refresh(&mut self, meshes: &mut Assets<Mesh>, rect: URect) -> bool {
+        let (w, h) = (self.heightmap.width(), self.heightmap.height());
+        let changed = self.grid_rect(rect);
+        if !meshes.contains(&self.mesh) {
+            return false;
+        }
+        let Some(changed) =
+            self.builder
+                .prepare_patch(&self.heightmap, &mut self.prepared, changed)
+        else {
+            return true;
+        };
+
+        let Some(mesh) = meshes.get_mut(&self.mesh) else {
+            return false;
+        };
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            assert_eq!(
+                positions.len(),
+                w * h,
+                "TerrainLive needs one mesh vertex per heightmap vertex"
+            );
+            for z in changed.z0..=changed.z1 {
+                for x in changed.x0..=changed.x1 {
+                    positions[z * w + x][1] = self.prepared.get(x, z);
+                }
+            }
+        }
+        self.builder
+            .patch_grid_normals(mesh, &self.prepared, changed);
+        true
+    }
+
+    /// Converts the half-open `rect` into an inclusive grid rectangle,
+    /// checking it against the heightmap.
+    fn grid_rect(&self, rect: URect) -> GridRect {
+        let (w, h) = (self.heightmap.width(), self.heightmap.height());
+        assert!(
+            rect.min.x < rect.max.x && rect.min.y < rect.max.y,
+            "patch rectangle must not be empty"
+        );
+        assert!(
+            rect.max.x as usize <= w && rect.max.y as usize <= h,
+            "patch rectangle exceeds the {w}×{h} heightmap"
+        );
+        GridRect {
+            x0: rect.min.x as usize,
+            z0: rect.min.y as usize,
+            x1: rect.max.x as usize - 1,
+            z1: rect.max.y as usize - 1,
+        }
+    }
+}
+
+/// Computes a modulated height from mesh-local `(x, z)`, the base height,
+/// and the elapsed time in seconds.
+type HeightFunction = dyn Fn(f32, f32, f32, f32) -> f32 + Send + Sync;
+
+/// Resource animating every [`TerrainLive`] terrain with a height function.
+///
+/// [`modulate_terrain_heights`] calls the function as
+/// `f(x, z, base_height, time)` — `x`/`z` in mesh-local world units
+/// (`index · scale`), `time` the elapsed seconds of `Time` — and patches
+/// the result into the mesh. Base heights are captured from each terrain's
+/// heightmap the first time the system sees it, so the modulation never
+/// compounds; call [`recapture`](Self::recapture) after sculpting the base.
+///
+/// To keep it affordable, [`with_interval`](Self::with_interval) throttles
+/// how often heights are recomputed and [`with_stride`](Self::with_stride)
+/// evaluates the function on a coarser grid, interpolating its offset from
+/// the base in between.
+///
+/// # Example
+///
+/// ```ignore
+/// app.add_message::<TerrainRebuild>()
+///     .insert_resource(
+///         TerrainHeightModulator::new(|_x, _z, h, t| h + 0.5 * (t * 0.3).sin())
+///             .with_interval(1.0 / 20.0)
+///             .with_stride(4),
+///     )
+///     .add_systems(Update, modulate_terrain_heights);
+/// ```
+#[derive(Resource, Clone)]
+pub struct TerrainHeightModulator {
+    function: Arc<HeightFunction>,
+    interval: f32,
+    stride: usize,
+    waited: f32,
+    bases: HashMap<Entity, HeightMap>,
+}
+
+impl std::fmt::Debug for TerrainHeightModulator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TerrainHeightModulator")
+            .field("interval", &self.interval)
+            .field("stride", &self.stride)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TerrainHeightModulator {
+    /// Creates a modulator that applies `function` every frame at full
+    /// resolution.
+    pub fn new(function: impl Fn(f32, f32, f32, f32) -> f32 + Send + Sync + 'static) -> Self {
+        Self {
+            function: Arc::new(function),
+            interval: 0.0,
+            stride: 1,
+            waited: 0.0,
+            bases: HashMap::new(),
+        }
+    }
+
+    /// Recomputes heights at most once per `seconds`. Clamped to
+    /// non-negative; `0` (the default) updates every frame.
+    pub fn with_interval(mut self, seconds: f32) -> Self {
+        self.interval = seconds.max(0.0);
+        self
+    }
+
+    /// Evaluates the function only at every `stride`-th vertex along each
+    /// axis (and the last row and column), bilinearly interpolating the
+    /// offset from the base height in between. Clamped to at least 1, the
+    /// default, which evaluates every vertex.
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride.max(1);
+        self
+    }
+
+    /// Forgets the captured base heights, so the next update takes each
+    /// terrain's current heights as its new base.
+    pub fn recapture(&mut self) {
+        self.bases.clear();
+    }
+
+    /// Modulated heights of `base` at `time`, row by row.
+    fn heights(&self, base: &HeightMap, time: f32) -> Vec<f32> {
+        let (w, h, s) = (base.width(), base.height(), base.scale());
+        let samples = |n: usize| -> Vec<usize> {
+            let mut at: Vec<usize> = (0..n).step_by(self.stride).collect();
+            if at.last() != Some(&(n - 1)) {
+                at.push(n - 1);
+            }
+            at
+        };
+        let (xs, zs) = (samples(w), samples(h));
+        let offsets: Vec<f32> = zs
+            .iter()
+            .flat_map(|&z| xs.iter().map(move |&x| (x, z)))
+            .map(|(x, z)| {
+                let height = base.get(x, z);
+                (self.function)(x as f32 * s, z as f32 * s, height, time) - height
+            })
+            .collect();
+
+        // Segment of `samples` containing `i`, and the position inside it.
+        let locate = |samples: &[usize], i: usize| -> (usize, f32) {
+            let k = (i / self.stride).min(samples.len().saturating_sub(2));
+            let (a, b) = (samples[k], samples[(k + 1).min(samples.len() - 1)]);
+            let t = if b > a {
+                (i - a) as f32 / (b - a) as f32
+            } else {
+                0.0
+            };
+            (k, t)
+        };
+        let cols = xs.len();
+        let offset = |k: usize, j: usize| offsets[j.min(zs.len() - 1) * cols + k.min(cols - 1)];
+        (0..h)
+            .flat_map(|z| (0..w).map(move |x| (x, z)))
+            .map(|(x, z)| {
+                let (k, tx) = locate(&xs, x);
+                let (j, tz) = locate(&zs, z);
+                let top = offset(k, j) * (1.0 - tx) + offset(k + 1, j) * tx;
+                let bottom = offset(k, j + 1) * (1.0 - tx) + offset(k + 1, j + 1) * tx;
+                base.get(x, z) + top * (1.0 - tz) + bottom * tz
+            })
+            .collect()
+    }
+}
+
+/// Bevy system applying the [`TerrainHeightModulator`] to every
+/// [`TerrainLive`] terrain.
+///
+/// Once the modulator's interval has passed, recomputes each terrain's
+/// heights from its captured base, patches them into the mesh with
+/// [`TerrainLive::patch_heights`], and sends a [`TerrainRebuild`] covering
+/// the whole map so colliders can follow. The mesh is already current and
+/// the splat weights are untouched, so
+/// [`apply_terrain_rebuilds`](crate::apply_terrain_rebuilds) only updates
+/// colliders; one still building finishes before the next starts. Register
+/// the message with
+/// `app.add_message::<TerrainRebuild>()`. Does nothing without the resource.
+pub fn modulate_terrain_heights(
+    time: Res<Time>,
+    modulator: Option<ResMut<TerrainHeightModulator>>,
+    mut terrains: Query<(Entity, &mut TerrainLive)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut rebuilds: MessageWriter<TerrainRebuild>,
+) {
+    let Some(mut modulator) = modulator else {
+        return;
+    };
+    modulator.waited += time.delta_secs();
+    if modulator.waited < modulator.interval {
+        return;
+    }
+    modulator.waited = 0.0;
+
+    let elapsed = time.elapsed_secs();
+    for (entity, mut live) in &mut terrains {
+        if !modulator.bases.contains_key(&entity) {
+            modulator.bases.insert(entity, live.heightmap.clone());
+        }
+        let modulator = &*modulator;
+        let base = &modulator.bases[&entity];
+        let heights = modulator.heights(base, elapsed);
+        let rect = URect::new(0, 0, base.width() as u32, base.height() as u32);
+        live.patch_heights(&mut meshes, rect, &heights);
+        rebuilds.write(TerrainRebuild { rect, splat: false });
+    }
+}
@@ -0,0 +1,139 @@
+//! Distance-based terrain LOD switching.
+//!
+//! Attach [`TerrainLod`] to a terrain (or chunk) entity that already has a
+//! [`Mesh3d`], and add [`update_terrain_lod`] to your `Update` schedule. The
+//! system swaps the entity's mesh between the LOD levels as cameras approach
+//! or recede, with a hysteresis band so an object resting on a threshold does
+//! not flicker between levels.
+
+use bevy::camera::primitives::Aabb;
+use bevy::prelude::*;
+
+/// LOD meshes of one terrain entity and the distances at which they switch.
+///
+/// `meshes` runs from finest to coarsest. `distances[i]` is the camera
+/// distance beyond which level `i + 1` replaces level `i`, so it needs one
+/// fewer entry than `meshes` and must be ascending. The meshes are typically
+/// built from progressively downsampled heightmaps, optionally with
+/// [`HeightMapMeshBuilder::with_geomorph`](crate::HeightMapMeshBuilder::with_geomorph)
+/// to soften each switch.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::TerrainLod;
+///
+/// commands.spawn((
+///     Mesh3d(lod0.clone()),
+///     MeshMaterial3d(material),
+///     TerrainLod::new(vec![lod0, lod1, lod2], vec![200.0, 600.0]),
+/// ));
+/// ```
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct TerrainLod {
+    /// Mesh handles, finest first.
+    pub meshes: Vec<Handle<Mesh>>,
+    /// Switch distances, ascending; `distances[i]` separates level `i` from
+    /// level `i + 1`.
+    pub distances: Vec<f32>,
+    /// Width of the hysteresis band as a fraction of each switch distance.
+    ///
+    /// A level only coarsens once the camera is past
+    /// `distance · (1 + hysteresis)` and only refines again inside
+    /// `distance · (1 − hysteresis)`. Defaults to `0.05`.
+    pub hysteresis: f32,
+}
+
+impl TerrainLod {
+    /// Creates an LOD set with the default hysteresis of 5%.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `meshes` is empty or `distances` does not have exactly one
+    /// fewer entry than `meshes`.
+    pub fn new(meshes: Vec<Handle<Mesh>>, distances: Vec<f32>) -> Self {
+        assert!(!meshes.is_empty(), "TerrainLod needs at least one mesh");
+        assert_eq!(
+            distances.len(),
+            meshes.len() - 1,
+            "TerrainLod needs one switch distance between each pair of meshes"
+        );
+        Self {
+            meshes,
+            distances,
+            hysteresis: 0.05,
+        }
+    }
+
+    /// Sets the hysteresis band as a fraction of each switch distance.
+    /// Clamped to `[0, 1)`.
+    pub fn with_hysteresis(mut self, fraction: f32) -> Self {
+        self.hysteresis = fraction.clamp(0.0, 0.999);
+        self
+    }
+
+    /// Returns the level to show at `distance` when `current` is shown now.
+    ///
+    /// Moves to a coarser level only past the outer edge of the hysteresis
+    /// band and to a finer one only inside its inner edge; anywhere between,
+    /// `current` is kept.
+    pub fn select_level(&self, current: usize, distance: f32) -> usize {
+        let past = |factor: f32| {
+            self.distances
+                .iter()
+                .filter(|&&d| distance > d * factor)
+                .count()
+        };
+        let coarsest_required = past(1.0 + self.hysteresis);
+        let coarsest_allowed = past(1.0 - self.hysteresis);
+        current.clamp(coarsest_required, coarsest_allowed)
+    }
+}
+
+/// Bevy system that swaps each [`TerrainLod`] entity's [`Mesh3d`] by camera
+/// distance.
+///
+/// Distance is measured from the nearest active 3D camera to the entity's
+/// world-space [`Aabb`] (the closest point on its bounds), or to its origin
+/// when it has no `Aabb` yet. With several cameras, such as split screen, the
+/// nearest one decides, so every view gets at least the detail it needs. The
+/// current level is read back from the entity's `Mesh3d`, and the mesh is
+/// only written when the level changes.
+pub fn update_terrain_lod(
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    mut terrains: Query<(&TerrainLod, &mut Mesh3d, &GlobalTransform, Option<&Aabb>)>,
+) {
+    let eyes: Vec<Vec3> = cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .map(|(_, transform)| transform.translation())
+        .collect();
+    if eyes.is_empty() {
+        return;
+    }
+
+    for (lod, mut mesh, transform, aabb) in &mut terrains {
+        let distance = eyes
+            .iter()
+            .map(|&eye| distance_to_bounds(eye, transform, aabb))
+            .fold(f32::INFINITY, f32::min);
+        let current = lod.meshes.iter().position(|m| *m == mesh.0).unwrap_or(0);
+        let level = lod
+            .select_level(current, distance)
+            .min(lod.meshes.len() - 1);
+        if lod.meshes[level] != mesh.0 {
+            mesh.0 = lod.meshes[level].clone();
+        }
+    }
+}
+
+/// World-space distance from `eye` to the entity's bounds (or origin).
+fn distance_to_bounds(eye: Vec3, transform: &GlobalTransform, aabb: Option<&Aabb>) -> f32 {
+    let Some(aabb) = aabb else {
+        return eye.distance(transform.translation());
+    };
+    let affine = transform.affine();
+    let local = affine.inverse().transform_point3(eye);
+    let closest = local.clamp(Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+    eye.distance(affine.transform_point3(closest))
+}
@@ -6,10 +6,20 @@
 //!   or Sobel filter applied directly to the heightmap)
 //! - Tiling UV coordinates (world-space scaled by `uv_tile_size`)
 
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
 use bevy::asset::RenderAssetUsages;
-use bevy::mesh::{Indices, PrimitiveTopology};
+use bevy::camera::primitives::{Aabb, MeshAabb};
+use bevy::math::DVec3;
+use bevy::mesh::{
+    Indices, MeshVertexAttribute, PrimitiveTopology, VertexAttributeValues, VertexFormat,
+};
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
-use symbios_ground::HeightMap;
+use symbios_ground::{HeightMap, WeightMap};
 
 /// Selects the algorithm used to compute per-vertex normals in [`HeightMapMeshBuilder`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -33,6 +43,349 @@ pub enum NormalMethod {
     Sobel,
 }
 
+/// Storage format of the vertex normals emitted by [`HeightMapMeshBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalEncoding {
+    /// Unit vectors in [`Mesh::ATTRIBUTE_NORMAL`] (default).
+    #[default]
+    Float32x3,
+
+    /// Octahedral-encoded normals in [`ATTRIBUTE_NORMAL_OCT16`], 4 bytes per
+    /// vertex instead of 12.
+    ///
+    /// [`Mesh::ATTRIBUTE_NORMAL`] is removed, so the mesh needs a material
+    /// whose vertex shader decodes the attribute (see
+    /// [`decode_octahedral`]). Accurate to well under a degree.
+    Oct16,
+}
+
+/// Octahedral-encoded vertex normal written by [`NormalEncoding::Oct16`].
+///
+/// Bind it at shader location of your choice and decode it with the WGSL
+/// equivalent of [`decode_octahedral`]:
+///
+/// ```wgsl
+/// fn decode_octahedral(e: vec2<f32>) -> vec3<f32> {
+///     var n = vec3(e.x, 1.0 - abs(e.x) - abs(e.y), e.y);
+///     if n.y < 0.0 {
+///         n = vec3((1.0 - abs(n.z)) * sign(n.x), n.y, (1.0 - abs(n.x)) * sign(n.z));
+///     }
+///     return normalize(n);
+/// }
+/// ```
+pub const ATTRIBUTE_NORMAL_OCT16: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_NormalOct16", 1_460_823_517, VertexFormat::Snorm16x2);
+
+/// Per-vertex barycentric coordinate written by
+/// [`HeightMapMeshBuilder::with_barycentric`].
+///
+/// The three vertices of every triangle carry `(1,0,0)`, `(0,1,0)`, and
+/// `(0,0,1)`, so after interpolation the smallest component is the distance
+/// to the nearest edge — enough for a fragment-shader wireframe:
+///
+/// ```wgsl
+/// let d = fwidth(in.barycentric);
+/// let edge = smoothstep(vec3(0.0), d * 1.5, in.barycentric);
+/// let wire = 1.0 - min(edge.x, min(edge.y, edge.z));
+/// ```
+pub const ATTRIBUTE_BARYCENTRIC: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_Barycentric", 1_460_823_518, VertexFormat::Float32x3);
+
+/// Per-vertex grid height written by
+/// [`HeightMapMeshBuilder::with_height_attribute`].
+pub const ATTRIBUTE_HEIGHT: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_Height", 1_460_823_519, VertexFormat::Float32);
+
+/// Encodes a unit normal into the octahedral `Snorm16x2` form of
+/// [`ATTRIBUTE_NORMAL_OCT16`].
+///
+/// The sphere is projected onto the octahedron `|x| + |y| + |z| = 1` and
+/// unfolded onto the XZ square, with the lower (`-Y`) hemisphere folded over
+/// the corners, so upward-facing terrain normals use the centre of the range.
+pub fn encode_octahedral(normal: Vec3) -> [i16; 2] {
+    let n = normal / (normal.x.abs() + normal.y.abs() + normal.z.abs()).max(f32::EPSILON);
+    let mut p = Vec2::new(n.x, n.z);
+    if n.y < 0.0 {
+        let sign = Vec2::new(
+            if p.x >= 0.0 { 1.0 } else { -1.0 },
+            if p.y >= 0.0 { 1.0 } else { -1.0 },
+        );
+        p = (Vec2::ONE - Vec2::new(p.y.abs(), p.x.abs())) * sign;
+    }
+    let snorm = |v: f32| (v.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+    [snorm(p.x), snorm(p.y)]
+}
+
+/// Decodes an [`ATTRIBUTE_NORMAL_OCT16`] value back into a unit normal.
+pub fn decode_octahedral(encoded: [i16; 2]) -> Vec3 {
+    let e = Vec2::new(encoded[0] as f32, encoded[1] as f32) / i16::MAX as f32;
+    let e = e.clamp(Vec2::NEG_ONE, Vec2::ONE);
+    let mut n = Vec3::new(e.x, 1.0 - e.x.abs() - e.y.abs(), e.y);
+    if n.y < 0.0 {
+        let sign = |v: f32| if v >= 0.0 { 1.0 } else { -1.0 };
+        n = Vec3::new(
+            (1.0 - n.z.abs()) * sign(n.x),
+            n.y,
+            (1.0 - n.x.abs()) * sign(n.z),
+        );
+    }
+    n.normalize()
+}
+
+/// Value types a [`HeightMapMeshBuilder::with_custom_attribute`] closure may return.
+///
+/// Implemented for `[f32; 1]` through `[f32; 4]`, mapping to the
+/// `Float32` … `Float32x4` vertex formats.
+pub trait CustomAttributeValue: Copy + Send + Sync + 'static {
+    /// Vertex format the attribute must be declared with.
+    const FORMAT: VertexFormat;
+
+    /// Packs the per-vertex values into a mesh attribute buffer.
+    fn into_values(values: Vec<Self>) -> VertexAttributeValues;
+}
+
+impl CustomAttributeValue for [f32; 1] {
+    const FORMAT: VertexFormat = VertexFormat::Float32;
+
+    fn into_values(values: Vec<Self>) -> VertexAttributeValues {
+        VertexAttributeValues::Float32(values.into_iter().map(|[v]| v).collect())
+    }
+}
+
+impl CustomAttributeValue for [f32; 2] {
+    const FORMAT: VertexFormat = VertexFormat::Float32x2;
+
+    fn into_values(values: Vec<Self>) -> VertexAttributeValues {
+        VertexAttributeValues::Float32x2(values)
+    }
+}
+
+impl CustomAttributeValue for [f32; 3] {
+    const FORMAT: VertexFormat = VertexFormat::Float32x3;
+
+    fn into_values(values: Vec<Self>) -> VertexAttributeValues {
+        VertexAttributeValues::Float32x3(values)
+    }
+}
+
+impl CustomAttributeValue for [f32; 4] {
+    const FORMAT: VertexFormat = VertexFormat::Float32x4;
+
+    fn into_values(values: Vec<Self>) -> VertexAttributeValues {
+        VertexAttributeValues::Float32x4(values)
+    }
+}
+
+/// Produces a custom attribute's buffer for the vertices of a rectangle.
+type AttributeGenerator = dyn Fn(&HeightMap, GridRect) -> VertexAttributeValues + Send + Sync;
+
+/// A user-defined vertex attribute registered on the builder.
+#[derive(Clone)]
+struct CustomAttribute {
+    attribute: MeshVertexAttribute,
+    generate: Arc<AttributeGenerator>,
+}
+
+impl std::fmt::Debug for CustomAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomAttribute")
+            .field("attribute", &self.attribute)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A grid of authored normals, one per heightmap vertex.
+///
+/// Laid out like a `HeightMap`: `data[z * width + x]`. Normals use the
+/// terrain's `+Y`-up convention, so `Vec3::Y` means "no detail". Used by
+/// [`HeightMapMeshBuilder::with_detail_normals`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalField {
+    /// Normals in row-major order.
+    pub data: Vec<Vec3>,
+    /// Vertices along X.
+    pub width: usize,
+    /// Vertices along Z.
+    pub height: usize,
+}
+
+impl NormalField {
+    /// Creates a `width × height` field of flat (`+Y`) normals.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            data: vec![Vec3::Y; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+/// Timings and counts for one mesh produced by [`HeightMapMeshBuilder`].
+///
+/// Delivered to the callback set with
+/// [`with_metrics_callback`](HeightMapMeshBuilder::with_metrics_callback).
+/// Requires the `metrics` feature.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BuildMetrics {
+    /// Time spent generating positions and UVs.
+    pub vertex_pass: Duration,
+    /// Time spent computing normals, including any smoothing.
+    pub normal_pass: Duration,
+    /// Time spent generating indices and attribute buffers, including flat
+    /// merging, hole removal, compaction, and welding.
+    pub index_pass: Duration,
+    /// Vertices in the finished mesh.
+    pub vertex_count: usize,
+    /// Non-degenerate triangles in the finished mesh.
+    pub triangle_count: usize,
+}
+
+/// Callback registered with [`HeightMapMeshBuilder::with_metrics_callback`].
+#[cfg(feature = "metrics")]
+#[derive(Clone)]
+struct MetricsCallback(Arc<dyn Fn(BuildMetrics) + Send + Sync>);
+
+#[cfg(feature = "metrics")]
+impl std::fmt::Debug for MetricsCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetricsCallback").finish_non_exhaustive()
+    }
+}
+
+/// Stopwatch for the build phases; reads no clock without the `metrics`
+/// feature.
+struct PhaseTimer {
+    #[cfg(feature = "metrics")]
+    last: std::time::Instant,
+}
+
+impl PhaseTimer {
+    fn start() -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            last: std::time::Instant::now(),
+        }
+    }
+
+    /// Returns the time since the previous lap (or the start).
+    fn lap(&mut self) -> Duration {
+        #[cfg(feature = "metrics")]
+        {
+            let now = std::time::Instant::now();
+            let elapsed = now - self.last;
+            self.last = now;
+            elapsed
+        }
+        #[cfg(not(feature = "metrics"))]
+        Duration::ZERO
+    }
+}
+
+/// Primitive topology emitted by [`HeightMapMeshBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    /// `TriangleList`: six indices per quad (default).
+    #[default]
+    List,
+
+    /// `TriangleStrip`: one strip per row of quads, joined by degenerate
+    /// triangles. Roughly a third of the list's index count.
+    Strip,
+}
+
+/// UV layout emitted by [`HeightMapMeshBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UvMode {
+    /// World-space UVs shared across cells (default). See
+    /// [`with_uv_tile_size`](HeightMapMeshBuilder::with_uv_tile_size).
+    #[default]
+    Continuous,
+
+    /// Every cell spans the full `0..1` UV square, for texture atlases where
+    /// each cell samples a whole sub-tile. Cells no longer share vertices.
+    PerCell,
+}
+
+/// Picks a [`NormalMethod`] per chunk coordinate for
+/// [`HeightMapMeshBuilder::build_chunked_with_selector`].
+pub type NormalMethodSelector<'a> = dyn Fn(IVec2) -> NormalMethod + 'a;
+
+/// How [`HeightMapMeshBuilder`] treats non-finite heights.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NanPolicy {
+    /// Use heights as-is (default). NaN and infinities reach the mesh.
+    #[default]
+    Passthrough,
+
+    /// Clamp every height into `[min, max]`.
+    ///
+    /// Infinities clamp to the nearest bound and NaN becomes `min`. Finite
+    /// heights outside the range are clamped as well.
+    Clamp(f32, f32),
+
+    /// Replace NaN and infinite heights with the given value.
+    ReplaceWith(f32),
+}
+
+impl NanPolicy {
+    /// Sanitizes a single height.
+    fn apply(self, h: f32) -> f32 {
+        match self {
+            NanPolicy::Passthrough => h,
+            NanPolicy::Clamp(min, _) if h.is_nan() => min,
+            NanPolicy::Clamp(min, max) => h.max(min).min(max),
+            NanPolicy::ReplaceWith(value) if !h.is_finite() => value,
+            NanPolicy::ReplaceWith(_) => h,
+        }
+    }
+}
+
+/// Selects which diagonal splits each grid quad into two triangles.
+///
+/// Each quad `(x, z) → (x+1, z+1)` has four corners, named as seen from above
+/// with +X to the right and +Z downward:
+///
+/// ```text
+///   tl──tr        tl──tr
+///   │  ╱│         │╲  │
+///   │ ╱ │         │ ╲ │
+///   bl──br        bl──br
+///  TopRightBottomLeft   TopLeftBottomRight
+/// ```
+///
+/// The diagonal matters whenever the four corners are not coplanar: the two
+/// splits produce different surfaces inside the cell. Avian's heightfield
+/// collider splits every cell along the `tr–bl` diagonal, so the default here
+/// matches it and the rendered surface agrees with the physics surface. See
+/// `collider::HEIGHTFIELD_DIAGONAL` (requires the `physics` feature).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuadDiagonal {
+    /// Split along the `tr–bl` diagonal (default; matches Avian's heightfield).
+    ///
+    /// Triangles: `(tl, bl, tr)` and `(tr, bl, br)`.
+    #[default]
+    TopRightBottomLeft,
+
+    /// Split along the `tl–br` diagonal (Parry's "zigzag" subdivision).
+    ///
+    /// Triangles: `(tl, bl, br)` and `(tl, br, tr)`.
+    TopLeftBottomRight,
+}
+
+impl QuadDiagonal {
+    /// Splits a quad into two CCW triangles (normal pointing +Y for flat terrain).
+    ///
+    /// Works for any corner representation — vertex indices, positions, or
+    /// grid coordinates.
+    pub fn split<T: Copy>(self, tl: T, tr: T, bl: T, br: T) -> [[T; 3]; 2] {
+        match self {
+            QuadDiagonal::TopRightBottomLeft => [[tl, bl, tr], [tr, bl, br]],
+            QuadDiagonal::TopLeftBottomRight => [[tl, bl, br], [tl, br, tr]],
+        }
+    }
+}
+
 /// Converts a [`HeightMap`] into a Bevy [`Mesh`].
 ///
 /// The mesh covers world space `[0, world_width] × [0, world_depth]` in the XZ
@@ -50,6 +403,12 @@ pub enum NormalMethod {
 ///
 /// Setting `uv_tile_size = scale` tiles the texture once per grid cell.
 /// Setting `uv_tile_size = world_width` stretches the texture over the whole mesh.
+/// [`with_uv_transform`](HeightMapMeshBuilder::with_uv_transform) additionally rotates, scales,
+/// or offsets the result.
+///
+/// UVs are always stored as `Float32x2`. Bevy's [`VertexAttributeValues`] has
+/// no half-float variant, so a `Float16x2` UV channel cannot be produced
+/// through [`Mesh::insert_attribute`].
 ///
 /// # Example
 ///
@@ -62,23 +421,90 @@ pub enum NormalMethod {
 ///     .with_uv_tile_size(4.0)
 ///     .build(&heightmap);
 /// ```
+#[derive(Debug, Clone)]
 pub struct HeightMapMeshBuilder {
     uv_tile_size: f32,
+    uv_transform: (Mat2, Vec2),
     normal_method: NormalMethod,
+    normal_encoding: NormalEncoding,
+    diagonal: QuadDiagonal,
+    normal_smoothing: u32,
+    normal_flatten: f32,
+    flat_merge: Option<f32>,
+    high_precision_normals: bool,
+    weld_and_clean: Option<bool>,
+    sharp_angle: Option<f32>,
+    cache_optimization: bool,
+    height_bands: Option<Arc<[f32]>>,
+    detail_normals: Option<(Arc<NormalField>, f32)>,
+    nan_policy: NanPolicy,
+    geomorph: f32,
+    subdivisions: u32,
+    topology: Topology,
+    uv_mode: UvMode,
+    overlay_uv: bool,
+    barycentric: bool,
+    double_sided: bool,
+    height_attribute: bool,
+    normal_border: usize,
+    vertex_splat: Option<Arc<WeightMap>>,
+    custom_attributes: Vec<CustomAttribute>,
+    hole_mask: Option<Arc<[bool]>>,
+    height_floor: Option<f32>,
+    height_floor_snap: bool,
+    radial_mask: Option<(f32, f32)>,
+    chunk_frame: Option<Vec2>,
+    height_quantization: Option<f32>,
+    shared_indices: Option<SharedIndexBuffer>,
+    #[cfg(feature = "metrics")]
+    metrics_callback: Option<MetricsCallback>,
 }
 
 impl Default for HeightMapMeshBuilder {
     fn default() -> Self {
         Self {
             uv_tile_size: 1.0,
+            uv_transform: (Mat2::IDENTITY, Vec2::ZERO),
             normal_method: NormalMethod::default(),
+            normal_encoding: NormalEncoding::default(),
+            diagonal: QuadDiagonal::default(),
+            normal_smoothing: 0,
+            normal_flatten: 0.0,
+            flat_merge: None,
+            high_precision_normals: false,
+            weld_and_clean: None,
+            sharp_angle: None,
+            cache_optimization: false,
+            height_bands: None,
+            detail_normals: None,
+            nan_policy: NanPolicy::default(),
+            geomorph: 0.0,
+            subdivisions: 0,
+            topology: Topology::default(),
+            uv_mode: UvMode::default(),
+            overlay_uv: false,
+            barycentric: false,
+            double_sided: false,
+            height_attribute: false,
+            normal_border: 0,
+            vertex_splat: None,
+            custom_attributes: Vec::new(),
+            hole_mask: None,
+            height_floor: None,
+            height_floor_snap: false,
+            radial_mask: None,
+            chunk_frame: None,
+            height_quantization: None,
+            shared_indices: None,
+            #[cfg(feature = "metrics")]
+            metrics_callback: None,
         }
     }
 }
 
 impl HeightMapMeshBuilder {
     /// Creates a new builder with default settings (`uv_tile_size = 1.0`,
-    /// `normal_method = AreaWeighted`).
+    /// `normal_method = AreaWeighted`, `diagonal = TopRightBottomLeft`).
     pub fn new() -> Self {
         Self::default()
     }
@@ -93,6 +519,116 @@ impl HeightMapMeshBuilder {
         self
     }
 
+    /// Applies a 2×2 rotation/scale `matrix` and an `offset` to every UV.
+    ///
+    /// UVs become `matrix · (xz / uv_tile_size) + offset`, so the tile size
+    /// still sets the base frequency while the matrix rotates or stretches the
+    /// pattern across the surface. The default identity matrix and zero
+    /// offset leave UVs unchanged.
+    ///
+    /// ```ignore
+    /// // Rotate the detail texture 30° and stretch it 2× along U.
+    /// let m = Mat2::from_scale_angle(Vec2::new(2.0, 1.0), 30f32.to_radians());
+    /// let builder = HeightMapMeshBuilder::new().with_uv_transform(m, Vec2::ZERO);
+    /// ```
+    pub fn with_uv_transform(mut self, matrix: Mat2, offset: Vec2) -> Self {
+        self.uv_transform = (matrix, offset);
+        self
+    }
+
+    /// Sets how UVs are laid out across the grid.
+    ///
+    /// [`UvMode::PerCell`] gives the corners of every cell the UVs
+    /// `(0,0)`, `(1,0)`, `(0,1)`, `(1,1)` (top-left, top-right, bottom-left,
+    /// bottom-right) so each cell samples a whole atlas sub-tile. Neighbouring
+    /// cells need different UVs at their shared corners, so this forces
+    /// vertex duplication — like flat shading, every triangle gets its own
+    /// three vertices, keeping the smooth normals of the grid point it came
+    /// from. The UV tile size is ignored; the
+    /// [`uv_transform`](Self::with_uv_transform) still applies, so a scale and
+    /// offset can select the sub-tile. Per-cell UVs apply to
+    /// [`Topology::List`] only; flat merging is skipped, and
+    /// [`with_weld_and_clean`](Self::with_weld_and_clean) only drops
+    /// degenerate triangles, since welding would undo the split.
+    pub fn with_uv_mode(mut self, mode: UvMode) -> Self {
+        self.uv_mode = mode;
+        self
+    }
+
+    /// Also writes [`Mesh::ATTRIBUTE_UV_1`] with coordinates normalized over
+    /// the whole heightmap.
+    ///
+    /// Vertex `(x, z)` gets `[x/(w-1), z/(h-1)]`, so UV_1 spans exactly
+    /// `0..1` across the full map — chunks and regions included — for a
+    /// per-terrain overlay such as a painted map or mask, while UV_0 keeps
+    /// the tiled world-space coordinates for detail textures. Not affected by
+    /// [`with_uv_transform`](Self::with_uv_transform) or
+    /// [`UvMode::PerCell`]. Defaults to `false`.
+    pub fn with_overlay_uv(mut self, enabled: bool) -> Self {
+        self.overlay_uv = enabled;
+        self
+    }
+
+    /// Also writes [`ATTRIBUTE_BARYCENTRIC`] for wireframe rendering in the
+    /// fragment shader.
+    ///
+    /// Each triangle's vertices get the basis vectors `(1,0,0)`, `(0,1,0)`,
+    /// and `(0,0,1)`. Shared vertices cannot hold a different coordinate per
+    /// triangle, so this de-indexes the mesh like flat shading: every triangle
+    /// gets its own three vertices, keeping the smooth normals of the grid
+    /// point it came from. Applied after welding and sharp-edge splitting,
+    /// which would otherwise merge the copies again. [`Topology::List`] only.
+    /// Defaults to `false`.
+    pub fn with_barycentric(mut self, enabled: bool) -> Self {
+        self.barycentric = enabled;
+        self
+    }
+
+    /// Writes the splat weights of `weight_map` into
+    /// [`Mesh::ATTRIBUTE_COLOR`], so a vertex-blended material can mix the
+    /// four layers without sampling a texture — cheap enough for low-end and
+    /// mobile targets.
+    ///
+    /// Each vertex takes the pixel at its grid position, normalized to
+    /// `0..1` per channel (R, G, B, A = layers 0–3). With
+    /// [`with_subdivisions`](Self::with_subdivisions), inserted vertices
+    /// interpolate their neighbours' weights bilinearly.
+    ///
+    /// # Panics
+    ///
+    /// [`build`](Self::build) and the other builders panic if the weight map
+    /// does not have one pixel per heightmap vertex.
+    pub fn with_vertex_splat(mut self, weight_map: &WeightMap) -> Self {
+        self.vertex_splat = Some(Arc::new(weight_map.clone()));
+        self
+    }
+
+    /// Also writes [`ATTRIBUTE_HEIGHT`], each vertex's sampled grid height.
+    ///
+    /// The value equals the position's Y as built, but stays put if a shader
+    /// or later pass displaces positions, so height-based blending keeps
+    /// reading the terrain data. It is the height after the builder's
+    /// preprocessing (NaN policy, geomorph, subdivisions, radial taper) and
+    /// before [`with_height_floor_snap`](Self::with_height_floor_snap).
+    /// Defaults to `false`.
+    pub fn with_height_attribute(mut self, enabled: bool) -> Self {
+        self.height_attribute = enabled;
+        self
+    }
+
+    /// Appends a back face for every triangle, so the terrain renders from
+    /// below too — e.g. under a glass floor — without disabling culling.
+    ///
+    /// The vertex buffers are duplicated with the normals negated, and the
+    /// second half of the index buffer repeats every triangle with flipped
+    /// winding over the copies, so both faces share positions and UVs but
+    /// light correctly. Doubles vertex and index counts. [`Topology::List`]
+    /// only. Defaults to `false`.
+    pub fn with_double_sided(mut self, enabled: bool) -> Self {
+        self.double_sided = enabled;
+        self
+    }
+
     /// Selects the algorithm used to compute per-vertex normals.
     ///
     /// See [`NormalMethod`] for a description of each variant.
@@ -101,144 +637,3339 @@ impl HeightMapMeshBuilder {
         self
     }
 
-    /// Builds the mesh from the given heightmap, consuming the builder.
-    ///
-    /// Produces a `TriangleList` mesh with positions, normals, and UV_0.
+    /// Selects how vertex normals are stored in the mesh.
     ///
-    /// # Panics
+    /// [`NormalEncoding::Oct16`] replaces [`Mesh::ATTRIBUTE_NORMAL`] with the
+    /// packed [`ATTRIBUTE_NORMAL_OCT16`] after every other pass, cutting
+    /// normal memory by two thirds for bandwidth-limited targets. Helpers that
+    /// read float normals, such as [`weld_seam`] and [`update_normals`], do
+    /// not apply to packed meshes. Defaults to [`NormalEncoding::Float32x3`].
+    pub fn with_normal_encoding(mut self, encoding: NormalEncoding) -> Self {
+        self.normal_encoding = encoding;
+        self
+    }
+
+    /// Treats the outer `rings` vertex rings of the heightmap as normal
+    /// context only.
     ///
-    /// Panics if the heightmap dimensions are less than 2×2, as at least one
-    /// quad is required to produce valid triangle geometry.
-    pub fn build(&self, heightmap: &HeightMap) -> Mesh {
-        assert!(
-            heightmap.width() >= 2 && heightmap.height() >= 2,
-            "HeightMap must be at least 2×2 to generate a mesh (got {}×{})",
-            heightmap.width(),
-            heightmap.height()
-        );
+    /// [`build`](Self::build) then meshes just the interior, as
+    /// [`build_region`](Self::build_region) would, so every emitted normal is
+    /// computed from real neighbours instead of the clamped edge — meant for
+    /// chunk heightmaps carrying a stitching border. Positions stay in the
+    /// heightmap's world space, so the mesh starts at `rings · scale` in X
+    /// and Z. With [`with_subdivisions`](Self::with_subdivisions) the rings
+    /// count source vertices. Defaults to `0`.
+    pub fn with_normal_border(mut self, rings: usize) -> Self {
+        self.normal_border = rings;
+        self
+    }
 
-        let w = heightmap.width();
-        let h = heightmap.height();
-        let s = heightmap.scale();
+    /// Selects the diagonal used to split each grid quad into triangles.
+    ///
+    /// The default matches Avian's heightfield collider. Only change it if you
+    /// also build the collider with the same diagonal, otherwise the rendered
+    /// and physics surfaces disagree inside each cell.
+    pub fn with_diagonal(mut self, diagonal: QuadDiagonal) -> Self {
+        self.diagonal = diagonal;
+        self
+    }
 
-        let vertex_count = w * h;
-        let mut positions: Vec<[f32; 3]> = Vec::with_capacity(vertex_count);
-        let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(vertex_count);
+    /// Blurs the computed normal field with a box filter of the given radius.
+    ///
+    /// After the base normals are computed with the selected
+    /// [`NormalMethod`], a separable `(2·radius + 1)`-wide box blur is applied
+    /// across the vertex grid and the result is re-normalized. This suppresses
+    /// high-frequency normal noise on rough generator output without changing
+    /// the geometry. Samples outside the map are skipped, not clamped.
+    ///
+    /// A radius of `0` (the default) disables smoothing.
+    pub fn with_normal_smoothing(mut self, radius: u32) -> Self {
+        self.normal_smoothing = radius;
+        self
+    }
 
-        for z in 0..h {
-            for x in 0..w {
-                let world_x = x as f32 * s;
-                let world_z = z as f32 * s;
-                let world_y = heightmap.get(x, z);
+    /// Lerps every computed normal toward `+Y` by `factor`, then
+    /// re-normalizes, for flatter, more readable lighting in top-down views.
+    ///
+    /// Applied last — after smoothing and detail normals — and the same way
+    /// for every [`NormalMethod`] and in
+    /// [`update_normals`](Self::update_normals). `0.0` (the default) leaves
+    /// normals unchanged and `1.0` makes them all point straight up. Clamped
+    /// to `[0, 1]`. Normals re-derived by
+    /// [`with_sharp_angle`](Self::with_sharp_angle) splits are not flattened.
+    pub fn with_normal_flatten(mut self, factor: f32) -> Self {
+        self.normal_flatten = factor.clamp(0.0, 1.0);
+        self
+    }
 
-                positions.push([world_x, world_y, world_z]);
-                uvs.push([world_x / self.uv_tile_size, world_z / self.uv_tile_size]);
-            }
-        }
+    /// Merges perfectly flat regions into larger polygons to save triangles.
+    ///
+    /// Rectangles of cells whose vertex heights all lie within `epsilon` of
+    /// each other are emitted as a single triangle fan around an interior
+    /// vertex instead of two triangles per cell. A fan over a `w`×`h` block
+    /// has `2·(w+h)` triangles against `2·w·h` for its cells, so a block is
+    /// merged only when that saves triangles: at least 2×3 cells in either
+    /// orientation. The fan keeps every vertex on the rectangle's boundary,
+    /// so edges shared with neighbouring (merged or unmerged) cells stay
+    /// watertight with no T-junctions.
+    /// Vertices left unreferenced inside merged regions are removed from the
+    /// mesh, so the vertex buffer is no longer a complete grid.
+    ///
+    /// Rectangles are found greedily in row-major order. Normals are still
+    /// computed from the full-resolution grid. This is a cheap alternative to
+    /// full adaptive meshing for levels dominated by flat plains.
+    pub fn with_flat_merge(mut self, epsilon: f32) -> Self {
+        self.flat_merge = Some(epsilon.max(0.0));
+        self
+    }
 
-        // Build CCW triangle indices (normal pointing +Y when terrain is flat).
-        // Each quad (x, z) → (x+1, z+1) emits two triangles:
-        //   tl──tr
-        //   │╲  │     Triangle 1: tl, bl, tr
-        //   │ ╲ │     Triangle 2: tr, bl, br
-        //   bl──br
-        let quad_count = (w - 1) * (h - 1);
-        let mut indices: Vec<u32> = Vec::with_capacity(quad_count * 6);
+    /// Accumulates area-weighted normals in double precision.
+    ///
+    /// Vertex positions and face cross products are evaluated as `DVec3`, and
+    /// only the final normalized result is downcast to `f32`. On very large
+    /// maps or fractional grid scales, `f32` rounding of positions far from
+    /// the origin skews each face normal slightly, showing up as faint banding
+    /// in lighting; the `f64` path removes that error at roughly twice the
+    /// cost of the normal pass.
+    ///
+    /// Only affects [`NormalMethod::AreaWeighted`]. Disabled by default.
+    pub fn with_high_precision_normals(mut self, enabled: bool) -> Self {
+        self.high_precision_normals = enabled;
+        self
+    }
 
-        for z in 0..(h - 1) {
-            for x in 0..(w - 1) {
-                let tl = (z * w + x) as u32;
-                let tr = (z * w + x + 1) as u32;
-                let bl = ((z + 1) * w + x) as u32;
-                let br = ((z + 1) * w + x + 1) as u32;
+    /// Drops zero-area triangles from the finished mesh.
+    ///
+    /// Generators that emit extreme data (e.g. a vanishingly small grid
+    /// scale) can produce triangles whose face cross product is effectively
+    /// zero. These cost GPU work and can break
+    /// trimesh collider construction. When `weld_vertices` is `true`,
+    /// vertices with bit-identical positions are merged first. See
+    /// [`weld_and_clean`]. Disabled by default.
+    pub fn with_weld_and_clean(mut self, weld_vertices: bool) -> Self {
+        self.weld_and_clean = Some(weld_vertices);
+        self
+    }
 
-                // Triangle 1 — CCW: cross(bl-tl, tr-tl) = +Y for flat terrain
-                indices.push(tl);
-                indices.push(bl);
-                indices.push(tr);
+    /// Blends an authored [`NormalField`] into the computed normals.
+    ///
+    /// Each vertex's normal is combined with the field's normal at the same
+    /// point of the map using reoriented normal mapping, which tilts the detail
+    /// around the terrain normal rather than averaging the two — large baked
+    /// features such as dunes keep their shape on slopes. `strength` scales
+    /// the detail from `0.0` (unchanged normals) to `1.0` (full detail; on
+    /// flat terrain this is the detail normal itself). Applied after
+    /// smoothing, in every build and in
+    /// [`update_normals`](Self::update_normals).
+    ///
+    /// The field covers the whole map and is sampled bilinearly in
+    /// normalized map space, so it lines up with subdivided, downsampled,
+    /// and LOD grids as well as with the heightmap's own. A field with the
+    /// heightmap's dimensions is sampled exactly at the vertices. Building
+    /// panics if the field is empty.
+    pub fn with_detail_normals(mut self, field: &NormalField, strength: f32) -> Self {
+        self.detail_normals = Some((Arc::new(field.clone()), strength.clamp(0.0, 1.0)));
+        self
+    }
 
-                // Triangle 2 — CCW: cross(bl-tr, br-tr) = +Y for flat terrain
-                indices.push(tr);
-                indices.push(bl);
-                indices.push(br);
-            }
-        }
+    /// Keeps creases sharper than `degrees` instead of smoothing over them.
+    ///
+    /// Where two triangles sharing an edge meet at a dihedral angle above the
+    /// threshold — cliff tops, canyon rims — each vertex on that edge is split
+    /// so every side of the crease gets its own copy, with a normal averaged
+    /// (area-weighted) over that side's triangles only. Vertices whose faces
+    /// all meet below the threshold stay shared and keep the normal from
+    /// [`with_normal_method`](Self::with_normal_method). Applies to
+    /// [`Topology::List`] only. Disabled by default.
+    pub fn with_sharp_angle(mut self, degrees: f32) -> Self {
+        self.sharp_angle = Some(degrees.to_radians());
+        self
+    }
 
-        let normals: Vec<[f32; 3]> = match self.normal_method {
-            NormalMethod::AreaWeighted => {
-                // Accumulate unnormalized face normals (cross products) at each
-                // vertex. The cross-product magnitude equals twice the triangle
-                // area, so larger triangles contribute proportionally more
-                // (area weighting). Reflects the actual rendered geometry.
-                let mut acc: Vec<Vec3> = vec![Vec3::ZERO; vertex_count];
-                for tri in indices.chunks_exact(3) {
-                    let [i0, i1, i2] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
-                    let p0 = Vec3::from(positions[i0]);
-                    let p1 = Vec3::from(positions[i1]);
-                    let p2 = Vec3::from(positions[i2]);
-                    let face_normal = (p1 - p0).cross(p2 - p0);
-                    acc[i0] += face_normal;
-                    acc[i1] += face_normal;
-                    acc[i2] += face_normal;
-                }
-                acc.iter()
-                    .map(|n| {
-                        let len = n.length();
-                        if len > f32::EPSILON {
-                            (*n / len).into()
-                        } else {
-                            [0.0, 1.0, 0.0]
-                        }
-                    })
-                    .collect()
-            }
-            NormalMethod::Sobel => compute_normals_sobel(heightmap),
-        };
+    /// Reorders the triangles of the index buffer for the GPU's
+    /// post-transform vertex cache.
+    ///
+    /// Uses Forsyth's linear-speed optimizer, which greedily emits the
+    /// triangle whose vertices are most likely still cached, so wide maps no
+    /// longer re-transform a whole row of vertices per row of quads. Only the
+    /// order of triangles changes; vertex buffers and winding are untouched.
+    /// Runs after every other index pass. Ignored by [`Topology::Strip`].
+    /// Defaults to `false`.
+    pub fn with_cache_optimization(mut self, enabled: bool) -> Self {
+        self.cache_optimization = enabled;
+        self
+    }
 
-        let mut mesh = Mesh::new(
-            PrimitiveTopology::TriangleList,
-            RenderAssetUsages::default(),
-        );
-        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-        mesh.insert_indices(Indices::U32(indices));
-        mesh
+    /// Groups triangles into contiguous index ranges by height band.
+    ///
+    /// `bounds` are the band boundaries, sorted here: band `0` holds triangles
+    /// whose average vertex height is below `bounds[0]`, band `i` those in
+    /// `[bounds[i-1], bounds[i])`, and band `bounds.len()` everything above.
+    /// The index buffer is stably partitioned band by band after every other
+    /// index pass, so [`build_banded`](Self::build_banded) can report each
+    /// band's range for drawing or hiding it on its own. An empty slice
+    /// disables banding, as does the default. Ignored by [`Topology::Strip`].
+    pub fn with_height_bands(mut self, bounds: &[f32]) -> Self {
+        let mut bounds = bounds.to_vec();
+        bounds.sort_by(f32::total_cmp);
+        self.height_bands = (!bounds.is_empty()).then(|| bounds.into());
+        self
     }
-}
 
-/// Computes per-vertex normals using a 3×3 Sobel filter over the heightmap.
-///
-/// For each grid vertex `(xi, zi)`, the 3×3 neighborhood of heights is sampled
-/// (edge vertices clamp to the nearest valid index). The Sobel X kernel
-/// `[[-1,0,1],[-2,0,2],[-1,0,1]]` and Sobel Z kernel
-/// `[[-1,-2,-1],[0,0,0],[1,2,1]]` produce weighted height gradients `gx` and
-/// `gz`. The surface normal follows from the cross product of the two tangent
-/// vectors:
-///
-/// ```text
-/// normal ∝ (-gx, 8·scale, -gz)
-/// ```
-///
-/// where `scale` is the world-space grid spacing. The factor `8·scale` arises
-/// because the Sobel kernels approximate the derivative as `dh/dx ≈ gx/(8s)`,
-/// so the unnormalized normal `(-dh/dx, 1, -dh/dz)` scaled by `8s` becomes
-/// `(-gx, 8s, -gz)`.
-fn compute_normals_sobel(heightmap: &HeightMap) -> Vec<[f32; 3]> {
-    let w = heightmap.width();
-    let h = heightmap.height();
-    let s = heightmap.scale();
+    /// Sets how non-finite heights (NaN, ±infinity) are sanitized on read.
+    ///
+    /// A single NaN propagates into the positions and normals of every
+    /// neighbouring vertex; sanitizing heights first keeps one bad cell from
+    /// corrupting the mesh. See [`NanPolicy`]. Defaults to
+    /// [`NanPolicy::Passthrough`].
+    pub fn with_nan_policy(mut self, policy: NanPolicy) -> Self {
+        self.nan_policy = policy;
+        self
+    }
 
-    let sample = |xi: usize, zi: usize, dx: i32, dz: i32| -> f32 {
-        let nx = (xi as i32 + dx).clamp(0, w as i32 - 1) as usize;
-        let nz = (zi as i32 + dz).clamp(0, h as i32 - 1) as usize;
-        heightmap.get(nx, nz)
-    };
+    /// Morphs odd rows and columns toward the next-coarser LOD.
+    ///
+    /// Each vertex with an odd `x` or `z` index has its height lerped by
+    /// `factor` toward the height the coarse (every-other-vertex) surface has
+    /// at that point: the midpoint of its two even neighbours along the odd
+    /// axis, or — when both indices are odd — the midpoint of the two coarse
+    /// corners joined by the quad diagonal. `0.0` keeps full detail and `1.0`
+    /// reproduces the coarse surface exactly, so animating the factor hides
+    /// the pop when switching LODs. Vertices lacking a neighbour on the far
+    /// edge are left unchanged. Clamped to `[0, 1]`; defaults to `0.0`.
+    pub fn with_geomorph(mut self, factor: f32) -> Self {
+        self.geomorph = factor.clamp(0.0, 1.0);
+        self
+    }
 
-    let mut normals = Vec::with_capacity(w * h);
-    for zi in 0..h {
-        for xi in 0..w {
-            // Sobel X kernel: horizontal gradient (dh/dx direction)
+    /// Tessellates the mesh `n` times finer than the heightmap.
+    ///
+    /// Inserts `n` vertices between each pair of neighbouring grid vertices,
+    /// with heights sampled bilinearly from the source grid, so a `w×h` map
+    /// meshes as `((w-1)(n+1)+1) × ((h-1)(n+1)+1)` vertices over the same
+    /// extent. Normals, UVs and every later pass run on the denser grid,
+    /// which gives smoother silhouettes or a finer base for GPU displacement.
+    /// The hole mask still addresses source cells; chunk sizes for the
+    /// chunked builds count dense cells. Defaults to `0`.
+    pub fn with_subdivisions(mut self, n: u32) -> Self {
+        self.subdivisions = n;
+        self
+    }
+
+    /// Selects the primitive topology of the generated mesh.
+    ///
+    /// [`Topology::Strip`] emits one `TriangleStrip` per row of quads, joined
+    /// by degenerate triangles, describing the same surface (and winding) as
+    /// the default list. Vertices and normals are unchanged. Strips always
+    /// cover the full grid, so [`with_flat_merge`](Self::with_flat_merge) and
+    /// [`with_weld_and_clean`](Self::with_weld_and_clean) are ignored.
+    /// Defaults to [`Topology::List`].
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Cuts holes in the mesh where `mask` is `true`.
+    ///
+    /// `mask` holds one entry per grid **cell** of the full heightmap, in
+    /// row-major order: cell `(x, z)` (the quad between vertices `(x, z)` and
+    /// `(x+1, z+1)`) is at `mask[z * (width-1) + x]`. Both triangles of a
+    /// masked cell are omitted — e.g. for cave or tunnel openings — and
+    /// vertices no longer referenced by any triangle are removed. Masked cells
+    /// never join a flat-merged rectangle.
+    ///
+    /// Heightfield colliders cannot have holes; use
+    /// `collider::build_trimesh_collider_with_holes` (requires the `physics`
+    /// feature) with the same mask so physics matches the visuals. Ignored by
+    /// [`Topology::Strip`].
+    ///
+    /// # Panics
+    ///
+    /// [`build`](Self::build) and the chunked builders panic if the mask
+    /// length is not `(width-1)·(height-1)`.
+    pub fn with_hole_mask(mut self, mask: impl Into<Arc<[bool]>>) -> Self {
+        self.hole_mask = Some(mask.into());
+        self
+    }
+
+    /// Omits every cell whose four corners all lie below `floor`.
+    ///
+    /// Meant for islands whose seabed is covered by a water plane: culled
+    /// cells behave exactly like [`with_hole_mask`](Self::with_hole_mask)
+    /// holes (and combine with a mask), so unreferenced vertices are removed
+    /// while the remaining cells still share their vertices and the shoreline
+    /// stays crack-free. Cells straddling the floor are kept; see
+    /// [`with_height_floor_snap`](Self::with_height_floor_snap) to clamp
+    /// their submerged corners. Ignored by [`Topology::Strip`].
+    pub fn with_height_floor(mut self, floor: f32) -> Self {
+        self.height_floor = Some(floor);
+        self
+    }
+
+    /// Raises the vertices left below the
+    /// [`with_height_floor`](Self::with_height_floor) floor up to it, so the
+    /// kept shoreline cells end exactly at the water plane instead of
+    /// dipping under it. Only positions move; normals keep the slope of the
+    /// original heights. Has no effect without a floor. Defaults to `false`.
+    pub fn with_height_floor_snap(mut self, snap: bool) -> Self {
+        self.height_floor_snap = snap;
+        self
+    }
+
+    /// Meshes only a circular footprint centred on the heightmap, for round
+    /// islands.
+    ///
+    /// `radius` and `falloff` are world units measured from the centre of the
+    /// map in the XZ plane. Cells with a corner farther than
+    /// `radius + falloff` are culled like [`with_hole_mask`](Self::with_hole_mask)
+    /// holes, so the mesh boundary follows the circle from the inside. With a
+    /// positive `falloff`, heights in the band between `radius` and
+    /// `radius + falloff` are scaled toward zero with a smoothstep, tapering
+    /// the island into the water; normals follow the tapered heights. Ignored
+    /// by [`Topology::Strip`] apart from the taper.
+    pub fn with_radial_mask(mut self, radius: f32, falloff: f32) -> Self {
+        self.radial_mask = Some((radius.max(0.0), falloff.max(0.0)));
+        self
+    }
+
+    /// Rounds every height to the nearest multiple of `step` before meshing,
+    /// for stepped, blocky terraces.
+    ///
+    /// Applied last in preprocessing, after subdivision and the radial taper,
+    /// so every output height is an exact multiple of `step`. Cells spanning
+    /// several levels are then split along their midlines: each corner keeps
+    /// a flat region at its own height, and vertical riser quads join
+    /// neighbouring regions on different levels, so terraces meet in crisp,
+    /// axis-aligned steps. Tops are shaded with `+Y` normals and risers with
+    /// their horizontal face normal. The split adds vertices, so the mesh no
+    /// longer has one vertex per grid point; it is ignored by
+    /// [`Topology::Strip`]. A `step` of `0.0` or less disables it, as does
+    /// the default.
+    pub fn with_height_quantization(mut self, step: f32) -> Self {
+        self.height_quantization = (step > 0.0).then_some(step);
+        self
+    }
+
+    /// Places positions relative to `chunk_origin` while keeping UVs global.
+    ///
+    /// Every position is shifted by `(-chunk_origin.x, 0, -chunk_origin.y)`
+    /// in `f64` before it is downcast, so a chunk built around its own centre
+    /// keeps full `f32` precision far from the map origin — e.g. with
+    /// [`build_region`](Self::build_region) per chunk under a floating
+    /// origin. UVs (and overlay UVs) are still derived from the unshifted
+    /// map coordinates, so textures stay continuous across chunks. Spawn
+    /// each chunk at its origin. Combines with the offset of
+    /// [`build_f64`](Self::build_f64).
+    pub fn with_chunk_frame(mut self, chunk_origin: Vec2) -> Self {
+        self.chunk_frame = Some(chunk_origin);
+        self
+    }
+
+    /// Reuses a prebuilt index buffer instead of generating one per build.
+    ///
+    /// The buffer from [`shared_index_buffer`] records the grid size and
+    /// diagonal it was generated for. It is used whenever those match the
+    /// grid being meshed — the whole map for [`build`](Self::build), each
+    /// chunk for the chunked builds — and this builder's diagonal, and
+    /// ignored otherwise, so a smaller last row of chunks still gets correct
+    /// indices. Hole masks are applied on top of it; flat merging and
+    /// [`Topology::Strip`] generate their own indices and ignore it.
+    pub fn with_shared_indices(mut self, indices: SharedIndexBuffer) -> Self {
+        self.shared_indices = Some(indices);
+        self
+    }
+
+    /// Calls `callback` with the [`BuildMetrics`] of every mesh this builder
+    /// produces: once per [`build`](Self::build), and once per chunk for the
+    /// chunked builds. Requires the `metrics` feature, so the clock is never
+    /// read in builds that do not profile.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_callback(
+        mut self,
+        callback: impl Fn(BuildMetrics) + Send + Sync + 'static,
+    ) -> Self {
+        self.metrics_callback = Some(MetricsCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Adds a custom per-vertex attribute computed by `generate`.
+    ///
+    /// `generate` is called for every emitted vertex with its grid `(x, z)`
+    /// index and its height, and returns the attribute value: an `[f32; N]`
+    /// for `N` in `1..=4`. The values are inserted under `attribute`, whose
+    /// format must be the matching `Float32`, `Float32x2`, `Float32x3`, or
+    /// `Float32x4`. Use it for shader inputs the crate cannot know about,
+    /// such as a biome id or a precomputed lighting term. May be called
+    /// several times for different attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attribute.format` does not match the closure's arity.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// const ATTRIBUTE_BIOME: MeshVertexAttribute =
+    ///     MeshVertexAttribute::new("Biome", 988_540_917, VertexFormat::Float32);
+    ///
+    /// let mesh = HeightMapMeshBuilder::new()
+    ///     .with_custom_attribute(ATTRIBUTE_BIOME, move |x, z, _| [biomes.get(x, z) as f32])
+    ///     .build(&heightmap);
+    /// ```
+    pub fn with_custom_attribute<V: CustomAttributeValue>(
+        mut self,
+        attribute: MeshVertexAttribute,
+        generate: impl Fn(usize, usize, f32) -> V + Send + Sync + 'static,
+    ) -> Self {
+        assert_eq!(
+            attribute.format,
+            V::FORMAT,
+            "custom attribute {} has format {:?}, but the closure returns {:?} values",
+            attribute.name,
+            attribute.format,
+            V::FORMAT
+        );
+        self.custom_attributes.push(CustomAttribute {
+            attribute,
+            generate: Arc::new(move |heightmap, rect| {
+                let values = (rect.z0..=rect.z1)
+                    .flat_map(|z| (rect.x0..=rect.x1).map(move |x| (x, z)))
+                    .map(|(x, z)| generate(x, z, heightmap.get(x, z)))
+                    .collect();
+                V::into_values(values)
+            }),
+        });
+        self
+    }
+
+    /// Builds the mesh from the given heightmap, consuming the builder.
+    ///
+    /// Produces a `TriangleList` mesh with positions, normals, and UV_0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heightmap dimensions are less than 2×2, as at least one
+    /// quad is required to produce valid triangle geometry, or if a
+    /// [`with_normal_border`](Self::with_normal_border) leaves less than 2×2
+    /// interior vertices.
+    pub fn build(&self, heightmap: &HeightMap) -> Mesh {
+        assert_min_size(heightmap);
+        if self.normal_border > 0 {
+            let border = self.normal_border;
+            return self.build_region(
+                heightmap,
+                border,
+                border,
+                heightmap.width().saturating_sub(2 * border),
+                heightmap.height().saturating_sub(2 * border),
+            );
+        }
+        let heightmap = self.prepare(heightmap);
+        self.build_rect(&heightmap, GridRect::full(&heightmap))
+    }
+
+    /// Like [`build`](Self::build), but also returns the index range of each
+    /// [`with_height_bands`](Self::with_height_bands) band.
+    ///
+    /// Each entry is `(band, range)` with `range` in indices (three per
+    /// triangle). Empty bands are left out, so the ranges are ascending by
+    /// band and partition the whole index buffer. Without height bands — or
+    /// with [`Topology::Strip`] — the single entry `(0, 0..len)` covers the
+    /// whole buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`build`](Self::build).
+    pub fn build_banded(&self, heightmap: &HeightMap) -> (Mesh, Vec<(usize, Range<usize>)>) {
+        let mesh = self.build(heightmap);
+        let len = mesh.indices().map_or(0, |indices| indices.len());
+        let ranges = match &self.height_bands {
+            Some(bounds) if self.topology == Topology::List => {
+                let bands = triangle_height_bands(&mesh, bounds);
+                let mut ranges: Vec<(usize, Range<usize>)> = Vec::new();
+                for (t, band) in bands.into_iter().enumerate() {
+                    match ranges.last_mut() {
+                        Some((last, range)) if *last == band => range.end = 3 * t + 3,
+                        _ => ranges.push((band, 3 * t..3 * t + 3)),
+                    }
+                }
+                ranges
+            }
+            _ => vec![(0, 0..len)],
+        };
+        (mesh, ranges)
+    }
+
+    /// Builds the terrain as two submeshes: gentle ground and steep cliffs.
+    ///
+    /// Triangles whose face normal is more than `cliff_angle_degrees` from
+    /// vertical (`+Y`, or `-Y` for [`with_double_sided`](Self::with_double_sided)
+    /// back faces) go to the second mesh, the rest to the first, so each can
+    /// get its own material — e.g. a tri-planar one for cliff faces. Both are
+    /// cut from one [`build`](Self::build) and keep only the vertices they
+    /// reference, so vertices on the edges between them carry identical
+    /// positions, normals and attributes in both. Always uses
+    /// [`Topology::List`]. Either mesh may be empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`build`](Self::build).
+    pub fn build_with_cliffs(
+        &self,
+        heightmap: &HeightMap,
+        cliff_angle_degrees: f32,
+    ) -> (Mesh, Mesh) {
+        let builder = Self {
+            topology: Topology::List,
+            ..self.clone()
+        };
+        let mesh = builder.build(heightmap);
+        let cos_limit = cliff_angle_degrees.to_radians().cos();
+        let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions,
+            _ => unreachable!("terrain meshes have Float32x3 positions"),
+        };
+        let indices: Vec<u32> = mesh
+            .indices()
+            .map(|indices| indices.iter().map(|i| i as u32).collect())
+            .unwrap_or_default();
+
+        let (mut ground, mut cliff) = (Vec::new(), Vec::new());
+        for t in indices.chunks_exact(3) {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| Vec3::from(positions[i as usize]));
+            let normal = (b - a).cross(c - a);
+            let steep = normal.y.abs() < cos_limit * normal.length();
+            if steep { &mut cliff } else { &mut ground }.extend_from_slice(t);
+        }
+
+        let submesh = |indices: Vec<u32>| {
+            let mut part = mesh.clone();
+            part.insert_indices(Indices::U32(indices));
+            compact_vertices(&mut part);
+            part
+        };
+        (submesh(ground), submesh(cliff))
+    }
+
+    /// Builds the finest uniform LOD of `heightmap` with at most
+    /// `max_triangles` triangles.
+    ///
+    /// Picks the smallest LOD step — keeping every `step`-th vertex, as in
+    /// [`downsample`](crate::ops::downsample) — whose full grid of
+    /// `k·(w'-1)·(h'-1)` triangles fits the budget, starting from the
+    /// closed-form bound `sqrt(k·(w-1)·(h-1)/max_triangles)` rather than
+    /// scanning every step, and builds only that level. `k` is the number of
+    /// triangles per cell: `2·(n+1)²` with
+    /// [`with_subdivisions(n)`](Self::with_subdivisions), doubled by
+    /// [`with_double_sided`](Self::with_double_sided). Passes whose output
+    /// cannot be predicted from the grid, such as the risers of
+    /// [`with_height_quantization`](Self::with_height_quantization), are
+    /// handled by counting the built mesh and coarsening until it fits.
+    /// Returns the mesh and the step used. The mesh is in the same world
+    /// space as [`build`](Self::build); with a step that does not divide the
+    /// cell count, the trailing rows/columns are dropped. If even a single
+    /// cell exceeds the budget, the coarsest possible step is used.
+    ///
+    /// A full-resolution hole mask, vertex splat, or detail normal field is
+    /// strided along with the heights; a coarse cell is a hole if any fine
+    /// cell it covers is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heightmap dimensions are less than 2×2.
+    pub fn build_within_budget(
+        &self,
+        heightmap: &HeightMap,
+        max_triangles: usize,
+    ) -> (Mesh, usize) {
+        assert_min_size(heightmap);
+        let (cols, rows) = (heightmap.width() - 1, heightmap.height() - 1);
+        let coarsest = cols.min(rows);
+        let dense = self.subdivisions as usize + 1;
+        let sides = if self.double_sided && self.topology != Topology::Strip {
+            2
+        } else {
+            1
+        };
+        let per_cell = 2 * dense * dense * sides;
+        let fits = |step: usize| per_cell * (cols / step) * (rows / step) <= max_triangles;
+        // `k·(cols/s)·(rows/s) ≤ max` holds for any `s ≥ sqrt(k·cols·rows/max)`;
+        // flooring the cell counts can let a few smaller steps fit as well, so
+        // refine from the bound in both directions.
+        let bound = (per_cell as f64 * cols as f64 * rows as f64 / max_triangles as f64).sqrt();
+        let mut step = (bound.ceil() as usize).clamp(1, coarsest);
+        while step < coarsest && !fits(step) {
+            step += 1;
+        }
+        while step > 1 && fits(step - 1) {
+            step -= 1;
+        }
+        loop {
+            let lod = crate::ops::downsample(heightmap, step);
+            let mesh = self.for_lod(heightmap, step).build(&lod);
+            if step >= coarsest || triangle_count(&mesh) <= max_triangles {
+                return (mesh, step);
+            }
+            step += 1;
+        }
+    }
+
+    /// Builds one mesh per LOD step in `steps`, in the same order, for
+    /// populating a [`TerrainLod`](crate::TerrainLod).
+    ///
+    /// Step `n` keeps every `n`-th vertex, as in
+    /// [`downsample`](crate::ops::downsample) and
+    /// [`build_within_budget`](Self::build_within_budget), and `1` is the
+    /// full-resolution [`build`](Self::build). All levels share the world
+    /// space of `build`. A step repeated in `steps` is built once and cloned.
+    /// Full-resolution per-cell and per-vertex state is strided per level, as
+    /// in [`build_within_budget`](Self::build_within_budget).
+    ///
+    /// ```ignore
+    /// let handles = builder
+    ///     .build_lod_chain(&heightmap, &[1, 2, 4])
+    ///     .into_iter()
+    ///     .map(|mesh| meshes.add(mesh))
+    ///     .collect();
+    /// let lod = TerrainLod::new(handles, vec![200.0, 600.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heightmap dimensions are less than 2×2 or a step is zero.
+    pub fn build_lod_chain(&self, heightmap: &HeightMap, steps: &[usize]) -> Vec<Mesh> {
+        assert_min_size(heightmap);
+        let mut built: Vec<(usize, Mesh)> = Vec::new();
+        steps
+            .iter()
+            .map(|&step| {
+                if let Some((_, mesh)) = built.iter().find(|(s, _)| *s == step) {
+                    return mesh.clone();
+                }
+                let mesh = match step {
+                    1 => self.build(heightmap),
+                    _ => self
+                        .for_lod(heightmap, step)
+                        .build(&crate::ops::downsample(heightmap, step)),
+                };
+                built.push((step, mesh.clone()));
+                mesh
+            })
+            .collect()
+    }
+
+    /// Builds a `LineList` mesh of the triangulation's edges, for debug
+    /// overlays without a wireframe render pipeline.
+    ///
+    /// The triangulation is the one [`build`](Self::build) produces with
+    /// [`Topology::List`] — including flat merging, holes, and welding — and
+    /// the vertex buffers are identical. Every edge appears exactly once,
+    /// even when shared by two triangles, in order of first appearance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heightmap dimensions are less than 2×2.
+    pub fn build_wireframe(&self, heightmap: &HeightMap) -> Mesh {
+        let triangles = self.clone().with_topology(Topology::List).build(heightmap);
+
+        let mut seen = bevy::platform::collections::HashSet::new();
+        let mut lines = Vec::new();
+        let indices: Vec<u32> = triangles
+            .indices()
+            .map(|i| i.iter().map(|i| i as u32).collect())
+            .unwrap_or_default();
+        for tri in indices.chunks_exact(3) {
+            for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+                if seen.insert((a.min(b), a.max(b))) {
+                    lines.extend_from_slice(&[a, b]);
+                }
+            }
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default());
+        for (attribute, values) in triangles.attributes() {
+            mesh.insert_attribute(*attribute, values.clone());
+        }
+        mesh.insert_indices(Indices::U32(lines));
+        mesh
+    }
+
+    /// Builds the mesh with positions relative to a floating origin.
+    ///
+    /// Each vertex is placed at `placement + (x·scale, height, z·scale)` and
+    /// then shifted by `-origin`, all in `f64`; only the final relative
+    /// position is downcast to `f32`. For terrain at world coordinates in the
+    /// millions, where `f32` has sub-meter spacing, this keeps vertices
+    /// precise as long as `origin` (typically the camera's floating origin)
+    /// is nearby. Spawn the mesh at the origin of the shifted frame.
+    ///
+    /// Normals and UVs are computed exactly as in [`build`](Self::build), in
+    /// the heightmap's local space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heightmap dimensions are less than 2×2.
+    pub fn build_f64(&self, heightmap: &HeightMap, placement: DVec3, origin: DVec3) -> Mesh {
+        assert_min_size(heightmap);
+        let heightmap = self.prepare(heightmap);
+        self.build_rect_at(
+            &heightmap,
+            GridRect::full(&heightmap),
+            Some(placement - origin),
+        )
+    }
+
+    /// Wraps the heightmap onto a sphere of `radius` for small-planet scenes.
+    ///
+    /// The sphere's centre lies `radius` below the middle of the map, at
+    /// `(world_width / 2, −radius, world_depth / 2)`, so the map's centre
+    /// touches the sphere's top at height zero. Each vertex keeps its
+    /// planar distance and direction from the map centre as an arc along the
+    /// surface (an azimuthal equidistant projection) and is raised by its
+    /// height along the sphere's outward normal there. Normals are
+    /// recomputed from the curved triangles, area-weighted, so lighting
+    /// follows the curvature. UVs are the planar ones of
+    /// [`build`](Self::build); index-level options such as holes, strips, or
+    /// flat merging do not apply.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heightmap dimensions are less than 2×2 or `radius` is
+    /// not positive.
+    pub fn build_spherical(&self, heightmap: &HeightMap, radius: f32) -> Mesh {
+        assert_min_size(heightmap);
+        assert!(
+            radius > 0.0,
+            "sphere radius must be positive (got {radius})"
+        );
+        let heightmap = self.prepare(heightmap);
+        let w = heightmap.width();
+        let h = heightmap.height();
+        let s = heightmap.scale();
+        let (uv_matrix, uv_offset) = self.uv_transform;
+        let middle = Vec2::new((w - 1) as f32, (h - 1) as f32) * s * 0.5;
+        let center = Vec3::new(middle.x, -radius, middle.y);
+
+        let mut positions = Vec::with_capacity(w * h);
+        let mut uvs = Vec::with_capacity(w * h);
+        for z in 0..h {
+            for x in 0..w {
+                let planar = Vec2::new(x as f32, z as f32) * s;
+                let offset = planar - middle;
+                let angle = offset.length() / radius;
+                let dir = offset.normalize_or_zero();
+                let up = Vec3::new(angle.sin() * dir.x, angle.cos(), angle.sin() * dir.y);
+                positions.push((center + up * (radius + heightmap.get(x, z))).to_array());
+                uvs.push((uv_matrix * (planar / self.uv_tile_size) + uv_offset).to_array());
+            }
+        }
+
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_indices(Indices::U32(grid_indices(w, h, self.diagonal)));
+        mesh.compute_area_weighted_normals();
+        self.encode_normals(&mut mesh);
+        mesh
+    }
+
+    /// Meshes a centred `inner_size × inner_size`-cell playable area at full
+    /// detail and the rest of the map around it at a coarser step, as one
+    /// watertight mesh — e.g. a detailed track inside a cheap backdrop ring.
+    ///
+    /// The ring keeps every `ring_lod_step`-th vertex, as in
+    /// [`downsample`](crate::ops::downsample), and the inner area is centred
+    /// on that coarse grid so its border lies on coarse grid lines. Ring cells
+    /// touching the inner area fan out to every full-detail vertex along the
+    /// shared edge, so the seam has no T-junctions. Positions, normals, and
+    /// UVs are those of a full [`build`](Self::build) at the same vertices;
+    /// index-level options such as holes, flat merging, or strips do not
+    /// apply. As in `downsample`, trailing rows/columns that do not fit the
+    /// coarse grid are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the heightmap dimensions are less than 2×2, `ring_lod_step`
+    /// is zero, `inner_size` is zero or not a multiple of `ring_lod_step`, or
+    /// the inner area does not fit inside the map.
+    pub fn build_ring(
+        &self,
+        heightmap: &HeightMap,
+        inner_size: usize,
+        ring_lod_step: usize,
+    ) -> Mesh {
+        assert_min_size(heightmap);
+        let k = ring_lod_step;
+        assert!(k > 0, "ring LOD step must be at least 1");
+        assert!(
+            inner_size > 0 && inner_size.is_multiple_of(k),
+            "inner size {inner_size} must be a positive multiple of the ring LOD step {k}"
+        );
+        let heightmap = self.prepare(heightmap);
+        let cells_x = (heightmap.width() - 1) / k;
+        let cells_z = (heightmap.height() - 1) / k;
+        let inner = inner_size / k;
+        assert!(
+            inner <= cells_x && inner <= cells_z,
+            "inner area of {inner_size} cells does not fit the map"
+        );
+        let (cx0, cz0) = ((cells_x - inner) / 2, (cells_z - inner) / 2);
+        let (x0, z0) = (cx0 * k, cz0 * k);
+        let in_inner = |cx: usize, cz: usize| {
+            (cx0..cx0 + inner).contains(&cx) && (cz0..cz0 + inner).contains(&cz)
+        };
+
+        let mut slots = bevy::platform::collections::HashMap::new();
+        let mut grid: Vec<(usize, usize)> = Vec::new();
+        let mut vertex = |x: usize, z: usize| {
+            *slots.entry((x, z)).or_insert_with(|| {
+                grid.push((x, z));
+                grid.len() as u32 - 1
+            })
+        };
+        let mut indices = Vec::new();
+
+        for z in z0..z0 + inner_size {
+            for x in x0..x0 + inner_size {
+                let [tl, tr, bl, br] =
+                    [(x, z), (x + 1, z), (x, z + 1), (x + 1, z + 1)].map(|(x, z)| vertex(x, z));
+                indices.extend(self.diagonal.split(tl, tr, bl, br).into_iter().flatten());
+            }
+        }
+
+        for cz in 0..cells_z {
+            for cx in 0..cells_x {
+                if in_inner(cx, cz) {
+                    continue;
+                }
+                let (x, z) = (cx * k, cz * k);
+                // Corners in winding order: tl, bl, br, tr. A ring cell borders
+                // the inner area along at most one edge; `seam` is the index of
+                // that edge's first corner.
+                let corners = [(x, z), (x, z + k), (x + k, z + k), (x + k, z)];
+                let seam = if in_inner(cx + 1, cz) {
+                    Some(2)
+                } else if cx > 0 && in_inner(cx - 1, cz) {
+                    Some(0)
+                } else if in_inner(cx, cz + 1) {
+                    Some(1)
+                } else if cz > 0 && in_inner(cx, cz - 1) {
+                    Some(3)
+                } else {
+                    None
+                };
+                let Some(seam) = seam else {
+                    let [tl, bl, br, tr] = corners.map(|(x, z)| vertex(x, z));
+                    indices.extend(self.diagonal.split(tl, tr, bl, br).into_iter().flatten());
+                    continue;
+                };
+
+                // Fan from the corner before the seam over the cell outline
+                // with every full-detail vertex of the seam edge inserted.
+                let (start, end) = (corners[seam], corners[(seam + 1) % 4]);
+                let mut outline = vec![vertex(start.0, start.1)];
+                for i in 1..k {
+                    let along = |a: usize, b: usize| match b.cmp(&a) {
+                        std::cmp::Ordering::Greater => a + i,
+                        std::cmp::Ordering::Less => a - i,
+                        std::cmp::Ordering::Equal => a,
+                    };
+                    outline.push(vertex(along(start.0, end.0), along(start.1, end.1)));
+                }
+                for j in 1..=3 {
+                    let (x, z) = corners[(seam + j) % 4];
+                    outline.push(vertex(x, z));
+                }
+                let apex = outline.pop().unwrap();
+                for pair in outline.windows(2) {
+                    indices.extend([apex, pair[0], pair[1]]);
+                }
+            }
+        }
+
+        let s = heightmap.scale();
+        let (uv_matrix, uv_offset) = self.uv_transform;
+        let field = self.compute_normals(&heightmap, GridRect::full(&heightmap));
+        let w = heightmap.width();
+        let positions: Vec<[f32; 3]> = grid
+            .iter()
+            .map(|&(x, z)| [x as f32 * s, heightmap.get(x, z), z as f32 * s])
+            .collect();
+        let uvs: Vec<[f32; 2]> = positions
+            .iter()
+            .map(|p| (uv_matrix * (Vec2::new(p[0], p[2]) / self.uv_tile_size) + uv_offset).into())
+            .collect();
+        let normals: Vec<[f32; 3]> = grid.iter().map(|&(x, z)| field[z * w + x]).collect();
+
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_indices(Indices::U32(indices));
+        self.encode_normals(&mut mesh);
+        mesh
+    }
+
+    /// Builds only the `w×h`-vertex sub-grid of `heightmap` whose first vertex
+    /// is `(x0, z0)`.
+    ///
+    /// Positions and UVs are in the same world space as [`build`](Self::build),
+    /// so the patch lines up with the full map and its texture. Normals on
+    /// the region border are computed from the heights just outside it where
+    /// the map has them, so the patch is lit exactly like the same vertices
+    /// of a full build. With [`with_subdivisions`](Self::with_subdivisions)
+    /// the region is still given in source vertices and meshed densely.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `w` or `h` is less than 2, or the region does not fit
+    /// inside the heightmap.
+    pub fn build_region(
+        &self,
+        heightmap: &HeightMap,
+        x0: usize,
+        z0: usize,
+        w: usize,
+        h: usize,
+    ) -> Mesh {
+        assert!(
+            w >= 2 && h >= 2,
+            "region must be at least 2×2 to generate a mesh (got {w}×{h})"
+        );
+        assert!(
+            x0 + w <= heightmap.width() && z0 + h <= heightmap.height(),
+            "region {w}×{h} at ({x0}, {z0}) exceeds the {}×{} heightmap",
+            heightmap.width(),
+            heightmap.height()
+        );
+        let heightmap = self.prepare(heightmap);
+        let step = self.subdivisions as usize + 1;
+        let rect = GridRect {
+            x0: x0 * step,
+            z0: z0 * step,
+            x1: (x0 + w - 1) * step,
+            z1: (z0 + h - 1) * step,
+        };
+        self.build_rect(&heightmap, rect)
+    }
+
+    /// Splits the heightmap into square chunks and builds one mesh per chunk.
+    ///
+    /// `chunk_size` is the number of grid cells along each side of a chunk.
+    /// Chunk `(cx, cz)` covers grid vertices
+    /// `[cx·chunk_size, (cx+1)·chunk_size] × [cz·chunk_size, (cz+1)·chunk_size]`
+    /// (clamped to the map), so neighbouring chunks share their boundary
+    /// vertices — a one-vertex overlap that keeps the seams crack-free. The
+    /// last row/column of chunks may be smaller when the cell count is not a
+    /// multiple of `chunk_size`.
+    ///
+    /// Positions and UVs are in the same world space as [`build`](Self::build),
+    /// so chunks align without per-chunk transforms. Normals at chunk borders
+    /// are computed from the neighbouring heights outside the chunk, so
+    /// lighting is continuous across seams.
+    ///
+    /// Chunks are returned in row-major order (`cz` outer, `cx` inner).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero or the heightmap is smaller than 2×2.
+    pub fn build_chunked(&self, heightmap: &HeightMap, chunk_size: usize) -> Vec<(IVec2, Mesh)> {
+        self.build_chunked_with_overlap(heightmap, chunk_size, 0)
+    }
+
+    /// Like [`build_chunked`](Self::build_chunked), with each chunk grown by
+    /// `overlap` extra rings of geometry beyond its shared boundary.
+    ///
+    /// Chunk `(cx, cz)` covers the same vertices as in `build_chunked`, plus
+    /// `overlap` more rows and columns on every side (clamped to the map), so
+    /// skirt blending or wide post-process filters have neighbouring
+    /// geometry to work with. Border normals already see the heights outside
+    /// the chunk, so no overlap is needed for lighting. The extra rings are
+    /// identical to the neighbours' geometry: when rendering chunks side by
+    /// side, draw only each chunk's interior (e.g. clip or discard outside
+    /// its `chunk_size` footprint) to avoid double-drawn, z-fighting
+    /// borders. `overlap = 0` is exactly `build_chunked`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero or the heightmap is smaller than 2×2.
+    pub fn build_chunked_with_overlap(
+        &self,
+        heightmap: &HeightMap,
+        chunk_size: usize,
+        overlap: usize,
+    ) -> Vec<(IVec2, Mesh)> {
+        assert_min_size(heightmap);
+        let heightmap = self.prepare(heightmap);
+        chunk_layout(heightmap.width(), heightmap.height(), chunk_size)
+            .into_iter()
+            .map(|(coord, rect)| {
+                let rect = rect.grow(overlap, &heightmap);
+                (coord, self.build_rect(&heightmap, rect))
+            })
+            .collect()
+    }
+
+    /// Like [`build_chunked`](Self::build_chunked), but builds the chunks in
+    /// parallel on the rayon thread pool. Requires the `rayon` feature.
+    ///
+    /// Every chunk is independent, so the output is identical to
+    /// `build_chunked` — including its row-major chunk order — regardless of
+    /// thread count or scheduling.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero or the heightmap is smaller than 2×2.
+    #[cfg(feature = "rayon")]
+    pub fn build_chunked_parallel(
+        &self,
+        heightmap: &HeightMap,
+        chunk_size: usize,
+    ) -> Vec<(IVec2, Mesh)> {
+        use rayon::prelude::*;
+
+        assert_min_size(heightmap);
+        let heightmap = self.prepare(heightmap);
+        chunk_layout(heightmap.width(), heightmap.height(), chunk_size)
+            .into_par_iter()
+            .map(|(coord, rect)| (coord, self.build_rect(&heightmap, rect)))
+            .collect()
+    }
+
+    /// Like [`build_chunked`](Self::build_chunked), but also returns each
+    /// chunk's [`BoundingSphere`].
+    ///
+    /// The sphere encloses the chunk's vertex extents (its XZ footprint and
+    /// the min/max height inside it) and is computed in the same pass as the
+    /// mesh, for renderers that cull by spheres rather than AABBs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero or the heightmap is smaller than 2×2.
+    pub fn build_chunked_with_bounds(
+        &self,
+        heightmap: &HeightMap,
+        chunk_size: usize,
+    ) -> Vec<(IVec2, Mesh, BoundingSphere)> {
+        assert_min_size(heightmap);
+        let heightmap = self.prepare(heightmap);
+        chunk_layout(heightmap.width(), heightmap.height(), chunk_size)
+            .into_iter()
+            .map(|(coord, rect)| {
+                let mesh = self.build_rect(&heightmap, rect);
+                (coord, mesh, BoundingSphere::from_rect(&heightmap, rect))
+            })
+            .collect()
+    }
+
+    /// Like [`build_chunked`](Self::build_chunked), but arranges the chunks
+    /// in a [`ChunkTree`] for hierarchical frustum culling.
+    ///
+    /// Leaves hold one chunk of `leaf_chunk_size` cells with its mesh and
+    /// AABB. Internal nodes split their chunk range in half along X and Z (a
+    /// quadtree; ranges of odd size give uneven halves, and one-chunk-wide
+    /// ranges only split along the other axis) and carry the union AABB of
+    /// their children, so a culling pass can reject a whole subtree with one
+    /// test.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `leaf_chunk_size` is zero or the heightmap is smaller than
+    /// 2×2.
+    pub fn build_chunked_tree(&self, heightmap: &HeightMap, leaf_chunk_size: usize) -> ChunkTree {
+        let mut leaves: Vec<Option<ChunkNode>> = self
+            .build_chunked(heightmap, leaf_chunk_size)
+            .into_iter()
+            .map(|(coord, mesh)| {
+                let aabb = mesh.compute_aabb().unwrap_or_default();
+                Some(ChunkNode::Leaf { coord, mesh, aabb })
+            })
+            .collect();
+        let last = match &leaves.last() {
+            Some(Some(ChunkNode::Leaf { coord, .. })) => *coord,
+            _ => unreachable!("a heightmap of at least 2×2 has a chunk"),
+        };
+        let columns = last.x as usize + 1;
+        ChunkTree {
+            root: ChunkNode::subdivide(
+                &mut leaves,
+                columns,
+                (0, columns),
+                (0, last.y as usize + 1),
+            ),
+        }
+    }
+
+    /// Like [`build_chunked`](Self::build_chunked), but picks the
+    /// [`NormalMethod`] for each chunk with `selector`.
+    ///
+    /// `selector` is called once per chunk with its coordinate, and its result
+    /// replaces the builder's own normal method for that chunk — e.g.
+    /// `AreaWeighted` near the player and the cheaper `Sobel` far away. All
+    /// other settings are shared.
+    ///
+    /// Neighbouring chunks built with different methods will not shade
+    /// identically along their shared border.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero or the heightmap is smaller than 2×2.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let chunks = HeightMapMeshBuilder::new().build_chunked_with_selector(&heightmap, 32, &|c| {
+    ///     if c.length_squared() <= 4 { NormalMethod::AreaWeighted } else { NormalMethod::Sobel }
+    /// });
+    /// ```
+    pub fn build_chunked_with_selector(
+        &self,
+        heightmap: &HeightMap,
+        chunk_size: usize,
+        selector: &NormalMethodSelector<'_>,
+    ) -> Vec<(IVec2, Mesh)> {
+        assert_min_size(heightmap);
+        let heightmap = self.prepare(heightmap);
+        let mut chunk_builder = self.clone();
+        chunk_layout(heightmap.width(), heightmap.height(), chunk_size)
+            .into_iter()
+            .map(|(coord, rect)| {
+                chunk_builder.normal_method = selector(coord);
+                (coord, chunk_builder.build_rect(&heightmap, rect))
+            })
+            .collect()
+    }
+
+    /// Recomputes the normals a height change inside `changed` can affect,
+    /// writing them into the normals of `mesh`, laid out one per heightmap
+    /// vertex in row-major order and stored in the configured
+    /// [`NormalEncoding`].
+    ///
+    /// A vertex's normal depends on its direct neighbours, plus the smoothing
+    /// radius when smoothing is enabled, so `changed` is grown by that much.
+    /// `heightmap` must already be [prepared](Self::prepare).
+    pub(crate) fn patch_grid_normals(
+        &self,
+        mesh: &mut Mesh,
+        heightmap: &HeightMap,
+        changed: GridRect,
+    ) {
+        let rect = changed.grow(1 + self.normal_smoothing as usize, heightmap);
+        let field = self.compute_normals(heightmap, rect);
+        let w = heightmap.width();
+        let rows = (rect.z0..=rect.z1).zip(field.chunks_exact(rect.width()));
+        match mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(normals)) => {
+                for (z, row) in rows {
+                    normals[z * w + rect.x0..=z * w + rect.x1].copy_from_slice(row);
+                }
+            }
+            _ => {
+                if let Some(VertexAttributeValues::Snorm16x2(packed)) =
+                    mesh.attribute_mut(ATTRIBUTE_NORMAL_OCT16)
+                {
+                    for (z, row) in rows {
+                        for (x, &n) in (rect.x0..=rect.x1).zip(row) {
+                            packed[z * w + x] = encode_octahedral(Vec3::from(n));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Recomputes only the normals of an existing mesh from `heightmap`.
+    ///
+    /// Positions, UVs, and indices are left untouched, which makes this the
+    /// cheap path after a localized sculpt whose vertex heights were already
+    /// patched. Each vertex is mapped back to its grid point from its XZ
+    /// position, so the mesh may come from [`build`](Self::build),
+    /// [`build_chunked`](Self::build_chunked), or a flat-merged build; normals
+    /// are computed with this builder's settings exactly as a fresh build
+    /// would, including neighbours outside a chunk. Subdivided meshes map onto
+    /// the dense grid, [`with_chunk_frame`](Self::with_chunk_frame) positions
+    /// are shifted back to map space first, and the result is stored in the
+    /// configured [`NormalEncoding`], replacing the previous normals.
+    /// Terraces from [`with_height_quantization`](Self::with_height_quantization)
+    /// change shape with the heights, so rebuild those instead.
+    ///
+    /// Meshes without `Float32x3` positions are left unchanged.
+    pub fn update_normals(&self, mesh: &mut Mesh, heightmap: &HeightMap) {
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return;
+        };
+        if positions.is_empty() {
+            return;
+        }
+
+        let heightmap = self.prepare(heightmap);
+        let s = heightmap.scale();
+        let origin = self.chunk_frame.unwrap_or(Vec2::ZERO);
+        let grid: Vec<(usize, usize)> = positions
+            .iter()
+            .map(|p| {
+                let x =
+                    (((p[0] + origin.x) / s).round().max(0.0) as usize).min(heightmap.width() - 1);
+                let z =
+                    (((p[2] + origin.y) / s).round().max(0.0) as usize).min(heightmap.height() - 1);
+                (x, z)
+            })
+            .collect();
+        let rect = GridRect {
+            x0: grid.iter().map(|g| g.0).min().unwrap_or(0),
+            z0: grid.iter().map(|g| g.1).min().unwrap_or(0),
+            x1: grid.iter().map(|g| g.0).max().unwrap_or(0),
+            z1: grid.iter().map(|g| g.1).max().unwrap_or(0),
+        };
+
+        let field = self.compute_normals(&heightmap, rect);
+        let rw = rect.width();
+        let normals: Vec<[f32; 3]> = grid
+            .iter()
+            .map(|&(x, z)| field[(z - rect.z0) * rw + (x - rect.x0)])
+            .collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        self.encode_normals(mesh);
+    }
+
+    /// Builds the mesh for the vertices inside `rect`, in full-map world space.
+    fn build_rect(&self, heightmap: &HeightMap, rect: GridRect) -> Mesh {
+        self.build_rect_at(heightmap, rect, None)
+    }
+
+    /// Like [`build_rect`](Self::build_rect), optionally offsetting positions
+    /// by `shift` in `f64` before they are downcast.
+    fn build_rect_at(&self, heightmap: &HeightMap, rect: GridRect, shift: Option<DVec3>) -> Mesh {
+        let shift = match self.chunk_frame {
+            Some(origin) => {
+                let frame = DVec3::new(-origin.x as f64, 0.0, -origin.y as f64);
+                Some(shift.unwrap_or(DVec3::ZERO) + frame)
+            }
+            None => shift,
+        };
+        let w = rect.width();
+        let h = rect.height();
+        let s = heightmap.scale();
+        let (uv_matrix, uv_offset) = self.uv_transform;
+
+        let vertex_count = w * h;
+        let mut positions: Vec<[f32; 3]> = Vec::with_capacity(vertex_count);
+        let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(vertex_count);
+
+        let mut timer = PhaseTimer::start();
+        for z in rect.z0..=rect.z1 {
+            for x in rect.x0..=rect.x1 {
+                let world_x = x as f32 * s;
+                let world_z = z as f32 * s;
+                let mut world_y = heightmap.get(x, z);
+                if self.height_floor_snap
+                    && let Some(floor) = self.height_floor
+                {
+                    world_y = world_y.max(floor);
+                }
+
+                positions.push(match shift {
+                    Some(shift) => {
+                        let local =
+                            DVec3::new(x as f64 * s as f64, world_y as f64, z as f64 * s as f64);
+                        (shift + local).as_vec3().into()
+                    }
+                    None => [world_x, world_y, world_z],
+                });
+                let uv = uv_matrix * (Vec2::new(world_x, world_z) / self.uv_tile_size) + uv_offset;
+                uvs.push(uv.into());
+            }
+        }
+
+        let vertex_pass = timer.lap();
+        let normals = self.compute_normals(heightmap, rect);
+        let normal_pass = timer.lap();
+
+        if self.topology == Topology::Strip {
+            let mut mesh = Mesh::new(
+                PrimitiveTopology::TriangleStrip,
+                RenderAssetUsages::default(),
+            );
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+            self.insert_extra_attributes(&mut mesh, heightmap, rect);
+            mesh.insert_indices(Indices::U32(strip_indices(w, h, self.diagonal)));
+            self.encode_normals(&mut mesh);
+            self.report_metrics(&mesh, [vertex_pass, normal_pass, timer.lap()]);
+            return mesh;
+        }
+
+        let mask_holes = self.hole_mask.as_deref().map(|mask| {
+            let step = self.subdivisions as usize + 1;
+            let cells_x = (heightmap.width() - 1) / step;
+            assert_eq!(
+                mask.len(),
+                cells_x * ((heightmap.height() - 1) / step),
+                "hole mask must have one entry per grid cell"
+            );
+            let cell = move |x: usize, z: usize| mask[z / step * cells_x + x / step];
+            (rect.z0..rect.z1)
+                .flat_map(|z| (rect.x0..rect.x1).map(move |x| cell(x, z)))
+                .collect::<Vec<bool>>()
+        });
+        let corners = |x: usize, z: usize| [(x, z), (x + 1, z), (x, z + 1), (x + 1, z + 1)];
+        let culled = |x: usize, z: usize| {
+            let submerged = self.height_floor.is_some_and(|floor| {
+                corners(x, z)
+                    .into_iter()
+                    .all(|(cx, cz)| heightmap.get(cx, cz) < floor)
+            });
+            let outside = self.radial_mask.is_some_and(|(radius, falloff)| {
+                corners(x, z)
+                    .into_iter()
+                    .any(|(cx, cz)| radial_distance(heightmap, cx, cz) > radius + falloff)
+            });
+            submerged || outside
+        };
+        let holes = if self.height_floor.is_some() || self.radial_mask.is_some() {
+            let cells = (rect.z0..rect.z1).flat_map(|z| (rect.x0..rect.x1).map(move |x| (x, z)));
+            Some(match mask_holes {
+                Some(mask) => mask
+                    .iter()
+                    .zip(cells)
+                    .map(|(&m, (x, z))| m || culled(x, z))
+                    .collect(),
+                None => cells.map(|(x, z)| culled(x, z)).collect(),
+            })
+        } else {
+            mask_holes
+        };
+        let per_cell_uvs = self.uv_mode == UvMode::PerCell;
+        let flat_merge = self.flat_merge.filter(|_| !per_cell_uvs);
+        let indices = match flat_merge {
+            Some(epsilon) => {
+                flat_merged_indices(heightmap, rect, self.diagonal, epsilon, holes.as_deref())
+            }
+            None => {
+                let indices = match &self.shared_indices {
+                    Some(shared) if shared.matches(w, h, self.diagonal) => shared.indices.to_vec(),
+                    _ => grid_indices(w, h, self.diagonal),
+                };
+                match &holes {
+                    // Six indices per cell, in row-major cell order.
+                    Some(holes) => indices
+                        .chunks_exact(6)
+                        .zip(holes)
+                        .filter(|(_, hole)| !**hole)
+                        .flat_map(|(cell, _)| cell.iter().copied())
+                        .collect(),
+                    None => indices,
+                }
+            }
+        };
+
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        self.insert_extra_attributes(&mut mesh, heightmap, rect);
+        if per_cell_uvs {
+            split_per_cell_uvs(&mut mesh, &indices, w, uv_matrix, uv_offset);
+        } else {
+            mesh.insert_indices(Indices::U32(indices));
+            if flat_merge.is_some() || holes.is_some() {
+                compact_vertices(&mut mesh);
+            }
+        }
+        if let Some(weld_vertices) = self.weld_and_clean {
+            weld_and_clean(&mut mesh, weld_vertices && !per_cell_uvs);
+        }
+        if self.height_quantization.is_some() {
+            split_terrace_risers(&mut mesh);
+        }
+        if let Some(sharp_angle) = self.sharp_angle {
+            split_sharp_edges(&mut mesh, sharp_angle);
+        }
+        if self.barycentric {
+            insert_barycentric(&mut mesh);
+        }
+        if self.double_sided {
+            append_back_faces(&mut mesh);
+        }
+        if self.cache_optimization
+            && let Some(indices) = mesh.indices()
+        {
+            let indices: Vec<u32> = indices.iter().map(|i| i as u32).collect();
+            let optimized = optimize_vertex_cache(&indices, mesh.count_vertices());
+            mesh.insert_indices(Indices::U32(optimized));
+        }
+        if let Some(bounds) = &self.height_bands {
+            sort_by_height_band(&mut mesh, bounds);
+        }
+        self.encode_normals(&mut mesh);
+        self.report_metrics(&mesh, [vertex_pass, normal_pass, timer.lap()]);
+        mesh
+    }
+
+    /// Swaps the float normals for the configured [`NormalEncoding`].
+    fn encode_normals(&self, mesh: &mut Mesh) {
+        if self.normal_encoding == NormalEncoding::Oct16
+            && let Some(VertexAttributeValues::Float32x3(normals)) =
+                mesh.remove_attribute(Mesh::ATTRIBUTE_NORMAL)
+        {
+            let packed = normals
+                .into_iter()
+                .map(|n| encode_octahedral(Vec3::from(n)))
+                .collect();
+            mesh.insert_attribute(
+                ATTRIBUTE_NORMAL_OCT16,
+                VertexAttributeValues::Snorm16x2(packed),
+            );
+        }
+    }
+
+    /// Passes the phase durations `[vertex, normal, index]` and the counts of
+    /// a finished mesh to the metrics callback, if one is set.
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn report_metrics(&self, mesh: &Mesh, phases: [Duration; 3]) {
+        #[cfg(feature = "metrics")]
+        if let Some(callback) = &self.metrics_callback {
+            (callback.0)(BuildMetrics {
+                vertex_pass: phases[0],
+                normal_pass: phases[1],
+                index_pass: phases[2],
+                vertex_count: mesh.count_vertices(),
+                triangle_count: triangle_count(mesh),
+            });
+        }
+    }
+}
+
+impl HeightMapMeshBuilder {
+    /// The diagonal every cell of the mesh is split along.
+    #[cfg(feature = "physics")]
+    pub(crate) fn diagonal(&self) -> QuadDiagonal {
+        self.diagonal
+    }
+
+    /// This builder with its per-vertex and per-cell state strided to the
+    /// grid [`downsample`](crate::ops::downsample)`(heightmap, step)` yields.
+    ///
+    /// The hole mask keeps a coarse cell as a hole if any fine cell inside it
+    /// is one, so openings stay open; the vertex splat and a detail field of
+    /// the heightmap's size keep every `step`-th sample. State whose size does
+    /// not match the heightmap is left as is, so building still reports it.
+    pub(crate) fn for_lod(&self, heightmap: &HeightMap, step: usize) -> Self {
+        let mut lod = self.clone();
+        if step <= 1 {
+            return lod;
+        }
+        let (w, h) = (heightmap.width(), heightmap.height());
+        let (cw, ch) = ((w - 1) / step + 1, (h - 1) / step + 1);
+        let strided = |x: usize, z: usize, width: usize| z * step * width + x * step;
+
+        if let Some(mask) = &self.hole_mask
+            && mask.len() == (w - 1) * (h - 1)
+        {
+            let coarse: Vec<bool> = (0..ch - 1)
+                .flat_map(|cz| (0..cw - 1).map(move |cx| (cx, cz)))
+                .map(|(cx, cz)| {
+                    (0..step).any(|dz| {
+                        (0..step).any(|dx| mask[(cz * step + dz) * (w - 1) + cx * step + dx])
+                    })
+                })
+                .collect();
+            lod.hole_mask = Some(coarse.into());
+        }
+        if let Some(splat) = &self.vertex_splat
+            && (splat.width, splat.height) == (w, h)
+        {
+            let mut coarse = WeightMap::new(cw, ch);
+            coarse.data = (0..ch)
+                .flat_map(|z| (0..cw).map(move |x| splat.data[strided(x, z, w)]))
+                .collect();
+            lod.vertex_splat = Some(Arc::new(coarse));
+        }
+        if let Some((field, strength)) = &self.detail_normals
+            && (field.width, field.height) == (w, h)
+        {
+            let coarse = NormalField {
+                data: (0..ch)
+                    .flat_map(|z| (0..cw).map(move |x| field.data[strided(x, z, w)]))
+                    .collect(),
+                width: cw,
+                height: ch,
+            };
+            lod.detail_normals = Some((Arc::new(coarse), *strength));
+        }
+        lod
+    }
+
+    /// Applies the builder's height preprocessing, borrowing the input when
+    /// there is nothing to change.
+    pub(crate) fn prepare<'a>(&self, heightmap: &'a HeightMap) -> Cow<'a, HeightMap> {
+        let mut heightmap = Cow::Borrowed(heightmap);
+        if self.nan_policy != NanPolicy::Passthrough {
+            for h in heightmap.to_mut().data_mut() {
+                *h = self.nan_policy.apply(*h);
+            }
+        }
+        if self.geomorph > 0.0 {
+            let morphed = geomorph(&heightmap, self.diagonal, self.geomorph);
+            heightmap = Cow::Owned(morphed);
+        }
+        if self.subdivisions > 0 {
+            let step = self.subdivisions as usize + 1;
+            let dense = crate::ops::resample(
+                &heightmap,
+                (heightmap.width() - 1) * step + 1,
+                (heightmap.height() - 1) * step + 1,
+            );
+            heightmap = Cow::Owned(dense);
+        }
+        if self.radial_mask.is_some_and(|m| m.1 > 0.0) || self.height_quantization.is_some() {
+            let heightmap = heightmap.to_mut();
+            for z in 0..heightmap.height() {
+                for x in 0..heightmap.width() {
+                    let height = self.shape_height(heightmap, x, z, heightmap.get(x, z));
+                    heightmap.set(x, z, height);
+                }
+            }
+        }
+        heightmap
+    }
+
+    /// Applies the radial taper and height quantization — the last, purely
+    /// per-vertex steps of [`prepare`](Self::prepare) — to `height` at
+    /// `(x, z)` of `heightmap`.
+    fn shape_height(&self, heightmap: &HeightMap, x: usize, z: usize, mut height: f32) -> f32 {
+        if let Some((radius, falloff)) = self.radial_mask.filter(|m| m.1 > 0.0) {
+            let t = ((radial_distance(heightmap, x, z) - radius) / falloff).clamp(0.0, 1.0);
+            height *= 1.0 - t * t * (3.0 - 2.0 * t);
+        }
+        if let Some(step) = self.height_quantization {
+            height = (height / step).round() * step;
+        }
+        height
+    }
+
+    /// Re-runs [`prepare`](Self::prepare) for the vertices a raw height
+    /// change inside `changed` can affect, writing them into `prepared`, the
+    /// earlier prepared copy of `heightmap`. Returns the rectangle of
+    /// vertices rewritten — `changed`, grown by one vertex with geomorphing,
+    /// which reads the neighbours of odd vertices — or `None` when every
+    /// prepared height in it came out bit-identical.
+    ///
+    /// Subdivisions change the grid size and are not supported.
+    pub(crate) fn prepare_patch(
+        &self,
+        heightmap: &HeightMap,
+        prepared: &mut HeightMap,
+        changed: GridRect,
+    ) -> Option<GridRect> {
+        debug_assert_eq!(self.subdivisions, 0, "subdivided grids cannot be patched");
+        let rect = changed.grow(usize::from(self.geomorph > 0.0), heightmap);
+        let (w, h) = (heightmap.width(), heightmap.height());
+        let raw = |x: usize, z: usize| self.nan_policy.apply(heightmap.get(x, z));
+        let mut any = false;
+        for z in rect.z0..=rect.z1 {
+            for x in rect.x0..=rect.x1 {
+                let mut height = raw(x, z);
+                if self.geomorph > 0.0
+                    && let Some([(ax, az), (bx, bz)]) = geomorph_pair(w, h, x, z, self.diagonal)
+                {
+                    let coarse = 0.5 * (raw(ax, az) + raw(bx, bz));
+                    height += (coarse - height) * self.geomorph;
+                }
+                let height = self.shape_height(heightmap, x, z, height);
+                if height.to_bits() != prepared.get(x, z).to_bits() {
+                    prepared.set(x, z, height);
+                    any = true;
+                }
+            }
+        }
+        any.then_some(rect)
+    }
+
+    /// Inserts the optional overlay UVs, height attribute, and vertex splat
+    /// weights, and evaluates every custom attribute over `rect` into `mesh`.
+    fn insert_extra_attributes(&self, mesh: &mut Mesh, heightmap: &HeightMap, rect: GridRect) {
+        if self.overlay_uv {
+            let inv = Vec2::ONE
+                / Vec2::new(
+                    (heightmap.width() - 1) as f32,
+                    (heightmap.height() - 1) as f32,
+                );
+            let overlay: Vec<[f32; 2]> = (rect.z0..=rect.z1)
+                .flat_map(|z| {
+                    (rect.x0..=rect.x1).map(move |x| (Vec2::new(x as f32, z as f32) * inv).into())
+                })
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, overlay);
+        }
+        if self.height_attribute {
+            let heights: Vec<f32> = (rect.z0..=rect.z1)
+                .flat_map(|z| (rect.x0..=rect.x1).map(move |x| heightmap.get(x, z)))
+                .collect();
+            mesh.insert_attribute(ATTRIBUTE_HEIGHT, heights);
+        }
+        if let Some(weight_map) = &self.vertex_splat {
+            let step = self.subdivisions as usize + 1;
+            let source = (
+                (heightmap.width() - 1) / step + 1,
+                (heightmap.height() - 1) / step + 1,
+            );
+            assert_eq!(
+                (weight_map.width, weight_map.height),
+                source,
+                "vertex splat weight map must have one pixel per heightmap vertex"
+            );
+            let s = heightmap.scale();
+            let colors: Vec<[f32; 4]> = (rect.z0..=rect.z1)
+                .flat_map(|z| {
+                    (rect.x0..=rect.x1).map(move |x| {
+                        crate::splat::sample_weights_bilinear(
+                            weight_map,
+                            x as f32 * s,
+                            z as f32 * s,
+                            s * step as f32,
+                        )
+                    })
+                })
+                .collect();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+        for custom in &self.custom_attributes {
+            mesh.insert_attribute(custom.attribute, (custom.generate)(heightmap, rect));
+        }
+    }
+
+    /// Computes the final per-vertex normals for the vertices inside `rect`.
+    ///
+    /// When smoothing is enabled, base normals are computed over `rect` grown
+    /// by the smoothing radius so the blur sees the same neighbourhood it
+    /// would in a full-map build.
+    fn compute_normals(&self, heightmap: &HeightMap, rect: GridRect) -> Vec<[f32; 3]> {
+        let radius = self.normal_smoothing as usize;
+        let outer = rect.grow(radius, heightmap);
+
+        let mut normals = match self.normal_method {
+            NormalMethod::AreaWeighted if self.high_precision_normals => {
+                compute_normals_area_weighted_f64(heightmap, outer, self.diagonal)
+            }
+            NormalMethod::AreaWeighted => {
+                compute_normals_area_weighted(heightmap, outer, self.diagonal)
+            }
+            NormalMethod::Sobel => compute_normals_sobel(heightmap, outer),
+        };
+        if radius > 0 {
+            box_blur_normals(&mut normals, outer.width(), outer.height(), radius);
+            normals = crop(&normals, outer, rect);
+        }
+        if let Some((detail, strength)) = &self.detail_normals {
+            blend_detail_normals(&mut normals, detail, *strength, heightmap, rect);
+        }
+        if self.normal_flatten > 0.0 {
+            for normal in &mut normals {
+                *normal = normalize_or_up(Vec3::from(*normal).lerp(Vec3::Y, self.normal_flatten));
+            }
+        }
+        normals
+    }
+}
+
+/// Blends `detail` into the normals of `rect` with reoriented normal mapping.
+///
+/// The detail normal is first lerped from `+Y` by `strength`, then rotated
+/// so that its `+Y` frame follows the base normal:
+/// `t = base + Y`, `u = detail · (−1, 1, −1)`, `result = t·(t·u)/t.y − u`.
+fn blend_detail_normals(
+    normals: &mut [[f32; 3]],
+    detail: &NormalField,
+    strength: f32,
+    heightmap: &HeightMap,
+    rect: GridRect,
+) {
+    assert!(
+        detail.width > 0 && detail.data.len() == detail.width * detail.height,
+        "detail normal field must be non-empty with width·height normals"
+    );
+    // Position of map vertex `i` of `n` along a field axis of `f` normals.
+    let to_field = |i: usize, n: usize, f: usize| {
+        if f == n {
+            i as f32
+        } else {
+            i as f32 / (n - 1) as f32 * (f - 1) as f32
+        }
+    };
+    let at = |x: usize, z: usize| detail.data[z * detail.width + x].normalize_or(Vec3::Y);
+    let sample = |fx: f32, fz: f32| {
+        let (x0, z0) = (fx.floor() as usize, fz.floor() as usize);
+        let (x1, z1) = (
+            (x0 + 1).min(detail.width - 1),
+            (z0 + 1).min(detail.height - 1),
+        );
+        let (tx, tz) = (fx - x0 as f32, fz - z0 as f32);
+        let top = at(x0, z0) * (1.0 - tx) + at(x1, z0) * tx;
+        let bottom = at(x0, z1) * (1.0 - tx) + at(x1, z1) * tx;
+        (top * (1.0 - tz) + bottom * tz).normalize_or(Vec3::Y)
+    };
+
+    let grid = (rect.z0..=rect.z1).flat_map(|z| (rect.x0..=rect.x1).map(move |x| (x, z)));
+    for (normal, (x, z)) in normals.iter_mut().zip(grid) {
+        let fx = to_field(x, heightmap.width(), detail.width);
+        let fz = to_field(z, heightmap.height(), detail.height);
+        let d = Vec3::Y.lerp(sample(fx, fz), strength).normalize_or(Vec3::Y);
+        let t = Vec3::from(*normal) + Vec3::Y;
+        let u = d * Vec3::new(-1.0, 1.0, -1.0);
+        *normal = normalize_or_up(t * t.dot(u) / t.y - u);
+    }
+}
+
+/// Applies a separable box blur of `radius` to a `w × h` normal field in place,
+/// re-normalizing the result. Out-of-range samples are skipped.
+fn box_blur_normals(normals: &mut [[f32; 3]], w: usize, h: usize, radius: usize) {
+    let mut tmp = vec![Vec3::ZERO; w * h];
+    for z in 0..h {
+        for x in 0..w {
+            let lo = x.saturating_sub(radius);
+            let hi = (x + radius).min(w - 1);
+            tmp[z * w + x] = (lo..=hi).map(|xi| Vec3::from(normals[z * w + xi])).sum();
+        }
+    }
+    for z in 0..h {
+        let lo = z.saturating_sub(radius);
+        let hi = (z + radius).min(h - 1);
+        for x in 0..w {
+            let sum: Vec3 = (lo..=hi).map(|zi| tmp[zi * w + x]).sum();
+            normals[z * w + x] = normalize_or_up(sum);
+        }
+    }
+}
+
+/// Extracts the values for `inner` from a row-major buffer laid out over `outer`.
+fn crop<T: Copy>(values: &[T], outer: GridRect, inner: GridRect) -> Vec<T> {
+    let ow = outer.width();
+    (inner.z0..=inner.z1)
+        .flat_map(|z| (inner.x0..=inner.x1).map(move |x| (x, z)))
+        .map(|(x, z)| values[(z - outer.z0) * ow + (x - outer.x0)])
+        .collect()
+}
+
+/// A sphere enclosing a terrain chunk, in world space.
+///
+/// Returned by [`HeightMapMeshBuilder::build_chunked_with_bounds`]. The center
+/// is the midpoint of the chunk's axis-aligned extents and the radius is half
+/// their diagonal, so every vertex of the chunk lies inside the sphere.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    /// Center of the sphere.
+    pub center: Vec3,
+    /// Radius of the sphere.
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    /// Encloses the vertices of `rect`, using the same positions as the mesh.
+    fn from_rect(heightmap: &HeightMap, rect: GridRect) -> Self {
+        let s = heightmap.scale();
+        let (mut lo, mut hi) = (f32::INFINITY, f32::NEG_INFINITY);
+        for z in rect.z0..=rect.z1 {
+            for x in rect.x0..=rect.x1 {
+                let y = heightmap.get(x, z);
+                lo = lo.min(y);
+                hi = hi.max(y);
+            }
+        }
+        let min = Vec3::new(rect.x0 as f32 * s, lo, rect.z0 as f32 * s);
+        let max = Vec3::new(rect.x1 as f32 * s, hi, rect.z1 as f32 * s);
+        Self {
+            center: (min + max) * 0.5,
+            radius: (max - min).length() * 0.5,
+        }
+    }
+}
+
+/// Quadtree of terrain chunks returned by
+/// [`HeightMapMeshBuilder::build_chunked_tree`].
+#[derive(Debug, Clone)]
+pub struct ChunkTree {
+    /// Node covering every chunk.
+    pub root: ChunkNode,
+}
+
+impl ChunkTree {
+    /// Iterates over the leaves as `(coord, mesh, aabb)`, in depth-first
+    /// order.
+    pub fn leaves(&self) -> impl Iterator<Item = (IVec2, &Mesh, Aabb)> {
+        let mut stack = vec![&self.root];
+        std::iter::from_fn(move || {
+            while let Some(node) = stack.pop() {
+                match node {
+                    ChunkNode::Leaf { coord, mesh, aabb } => return Some((*coord, mesh, *aabb)),
+                    ChunkNode::Internal { children, .. } => stack.extend(children.iter().rev()),
+                }
+            }
+            None
+        })
+    }
+}
+
+/// A node of a [`ChunkTree`].
+#[derive(Debug, Clone)]
+pub enum ChunkNode {
+    /// A single chunk, as produced by
+    /// [`HeightMapMeshBuilder::build_chunked`].
+    Leaf {
+        /// Chunk coordinate, as in `build_chunked`.
+        coord: IVec2,
+        /// The chunk's mesh, in full-map world space.
+        mesh: Mesh,
+        /// Bounds of the chunk's vertices.
+        aabb: Aabb,
+    },
+    /// A group of two to four child nodes.
+    Internal {
+        /// Union of the children's bounds.
+        aabb: Aabb,
+        /// Child nodes, in row-major order of the quadrants they cover.
+        children: Vec<ChunkNode>,
+    },
+}
+
+impl ChunkNode {
+    /// Bounds of every vertex under this node.
+    pub fn aabb(&self) -> Aabb {
+        match self {
+            Self::Leaf { aabb, .. } | Self::Internal { aabb, .. } => *aabb,
+        }
+    }
+
+    /// Builds the subtree over chunk columns `xs` and rows `zs` (half-open),
+    /// taking leaves out of the row-major `leaves`.
+    fn subdivide(
+        leaves: &mut [Option<ChunkNode>],
+        columns: usize,
+        xs: (usize, usize),
+        zs: (usize, usize),
+    ) -> Self {
+        if xs.1 - xs.0 == 1 && zs.1 - zs.0 == 1 {
+            return leaves[zs.0 * columns + xs.0]
+                .take()
+                .expect("each chunk is visited once");
+        }
+        let halves = |(lo, hi): (usize, usize)| {
+            let mid = lo + (hi - lo).div_ceil(2);
+            [(lo, mid), (mid, hi)]
+        };
+        let mut children = Vec::with_capacity(4);
+        for z in halves(zs) {
+            for x in halves(xs) {
+                if z.0 < z.1 && x.0 < x.1 {
+                    children.push(Self::subdivide(leaves, columns, x, z));
+                }
+            }
+        }
+        let (min, max) =
+            children
+                .iter()
+                .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), child| {
+                    let aabb = child.aabb();
+                    (min.min(aabb.min().into()), max.max(aabb.max().into()))
+                });
+        Self::Internal {
+            aabb: Aabb::from_min_max(min, max),
+            children,
+        }
+    }
+}
+
+/// Inclusive range of grid vertices `[x0, x1] × [z0, z1]` covered by a mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct GridRect {
+    pub x0: usize,
+    pub z0: usize,
+    pub x1: usize,
+    pub z1: usize,
+}
+
+impl GridRect {
+    /// The rectangle covering every vertex of `heightmap`.
+    pub fn full(heightmap: &HeightMap) -> Self {
+        Self {
+            x0: 0,
+            z0: 0,
+            x1: heightmap.width() - 1,
+            z1: heightmap.height() - 1,
+        }
+    }
+
+    /// Number of vertex columns.
+    pub fn width(&self) -> usize {
+        self.x1 - self.x0 + 1
+    }
+
+    /// Number of vertex rows.
+    pub fn height(&self) -> usize {
+        self.z1 - self.z0 + 1
+    }
+
+    /// Grows the rectangle by `n` vertices on every side, clamped to the map.
+    pub fn grow(&self, n: usize, heightmap: &HeightMap) -> Self {
+        Self {
+            x0: self.x0.saturating_sub(n),
+            z0: self.z0.saturating_sub(n),
+            x1: (self.x1 + n).min(heightmap.width() - 1),
+            z1: (self.z1 + n).min(heightmap.height() - 1),
+        }
+    }
+}
+
+fn assert_min_size(heightmap: &HeightMap) {
+    assert!(
+        heightmap.width() >= 2 && heightmap.height() >= 2,
+        "HeightMap must be at least 2×2 to generate a mesh (got {}×{})",
+        heightmap.width(),
+        heightmap.height()
+    );
+}
+
+/// Returns `heightmap` with odd rows/columns lerped toward the coarse surface.
+///
+/// See [`HeightMapMeshBuilder::with_geomorph`]. Coarse heights are read from
+/// the unmodified input, so the result does not depend on iteration order.
+fn geomorph(heightmap: &HeightMap, diagonal: QuadDiagonal, factor: f32) -> HeightMap {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let mut out = heightmap.clone();
+    for z in 0..h {
+        for x in 0..w {
+            let Some([(ax, az), (bx, bz)]) = geomorph_pair(w, h, x, z, diagonal) else {
+                continue;
+            };
+            let fine = heightmap.get(x, z);
+            let coarse = 0.5 * (heightmap.get(ax, az) + heightmap.get(bx, bz));
+            out.set(x, z, fine + (coarse - fine) * factor);
+        }
+    }
+    out
+}
+
+/// The two coarse vertices whose midpoint vertex `(x, z)` of a `w × h` grid
+/// morphs toward, or `None` for even vertices and odd ones on the far edge.
+fn geomorph_pair(
+    w: usize,
+    h: usize,
+    x: usize,
+    z: usize,
+    diagonal: QuadDiagonal,
+) -> Option<[(usize, usize); 2]> {
+    let (odd_x, odd_z) = (x % 2 == 1, z % 2 == 1);
+    if !(odd_x || odd_z) || (odd_x && x + 1 >= w) || (odd_z && z + 1 >= h) {
+        return None;
+    }
+    Some(match (odd_x, odd_z) {
+        (true, false) => [(x - 1, z), (x + 1, z)],
+        (false, true) => [(x, z - 1), (x, z + 1)],
+        _ => match diagonal {
+            QuadDiagonal::TopRightBottomLeft => [(x + 1, z - 1), (x - 1, z + 1)],
+            QuadDiagonal::TopLeftBottomRight => [(x - 1, z - 1), (x + 1, z + 1)],
+        },
+    })
+}
+
+/// Lays out the chunk grid used by [`HeightMapMeshBuilder::build_chunked`].
+///
+/// Returns each chunk's coordinate and inclusive vertex rectangle for a
+/// `w × h` vertex grid, in row-major order. Shared with the splat chunker so
+/// texture chunks line up with mesh chunks.
+pub(crate) fn chunk_layout(w: usize, h: usize, chunk_size: usize) -> Vec<(IVec2, GridRect)> {
+    assert!(chunk_size > 0, "chunk_size must be at least 1");
+    let chunks_x = (w - 1).div_ceil(chunk_size);
+    let chunks_z = (h - 1).div_ceil(chunk_size);
+
+    let mut chunks = Vec::with_capacity(chunks_x * chunks_z);
+    for cz in 0..chunks_z {
+        for cx in 0..chunks_x {
+            let x0 = cx * chunk_size;
+            let z0 = cz * chunk_size;
+            let rect = GridRect {
+                x0,
+                z0,
+                x1: (x0 + chunk_size).min(w - 1),
+                z1: (z0 + chunk_size).min(h - 1),
+            };
+            chunks.push((IVec2::new(cx as i32, cz as i32), rect));
+        }
+    }
+    chunks
+}
+
+/// A `TriangleList` index buffer together with the grid it was generated
+/// for, as returned by [`shared_index_buffer`].
+///
+/// Cloning is cheap: the indices are reference-counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedIndexBuffer {
+    width: usize,
+    height: usize,
+    diagonal: QuadDiagonal,
+    indices: Arc<[u32]>,
+}
+
+impl SharedIndexBuffer {
+    /// Vertex columns of the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Vertex rows of the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Diagonal each quad is split along.
+    pub fn diagonal(&self) -> QuadDiagonal {
+        self.diagonal
+    }
+
+    /// The indices, six per cell in row-major cell order.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    fn matches(&self, width: usize, height: usize, diagonal: QuadDiagonal) -> bool {
+        (self.width, self.height, self.diagonal) == (width, height, diagonal)
+    }
+}
+
+/// Returns the `TriangleList` index buffer of a `width × height` vertex grid.
+///
+/// This is exactly the buffer [`HeightMapMeshBuilder::build`] generates for
+/// a map of that size with the given `diagonal` (without flat merging or
+/// holes). Every patch of the same size shares it, so instanced or pooled
+/// terrain can upload it once and pass it to
+/// [`with_shared_indices`](HeightMapMeshBuilder::with_shared_indices) to skip
+/// regenerating it per build.
+///
+/// # Panics
+///
+/// Panics if `width` or `height` is less than 2.
+pub fn shared_index_buffer(
+    width: usize,
+    height: usize,
+    diagonal: QuadDiagonal,
+) -> SharedIndexBuffer {
+    assert!(
+        width >= 2 && height >= 2,
+        "grid must be at least 2×2 to produce triangles"
+    );
+    SharedIndexBuffer {
+        width,
+        height,
+        diagonal,
+        indices: grid_indices(width, height, diagonal).into(),
+    }
+}
+
+/// GPU buffer sizes of a mesh, as predicted by [`estimate_mesh_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeshSizeEstimate {
+    /// Number of vertices.
+    pub vertex_count: usize,
+    /// Number of indices.
+    pub index_count: usize,
+    /// Size of the interleaved vertex buffer in bytes.
+    pub vertex_bytes: usize,
+    /// Size of the index buffer in bytes.
+    pub index_bytes: usize,
+    /// `vertex_bytes + index_bytes`.
+    pub total: usize,
+}
+
+impl MeshSizeEstimate {
+    /// Adds the 16 bytes per vertex of `ATTRIBUTE_TANGENT`, as inserted by
+    /// [`recompute_tangents`] or Bevy's `generate_tangents`.
+    pub fn with_tangents(mut self) -> Self {
+        let tangents = self.vertex_count * VertexFormat::Float32x4.size() as usize;
+        self.vertex_bytes += tangents;
+        self.total += tangents;
+        self
+    }
+}
+
+/// Predicts the GPU buffer sizes of `builder.build(heightmap)` without
+/// building it.
+///
+/// Accounts for the topology, subdivisions, normal border, normal encoding,
+/// per-cell UVs, every optional attribute (overlay UVs, height, vertex splat,
+/// barycentrics, custom attributes) and double-sided duplication. Indices are
+/// always `u32`. The vertex size matches [`Mesh::get_vertex_size`], so
+/// `vertex_bytes` equals [`Mesh::get_vertex_buffer_size`].
+///
+/// The estimate assumes every cell is kept: data-dependent passes — holes,
+/// height floor, radial mask, flat merging, welding, sharp-edge splitting and
+/// terrace risers — are not accounted for. The first five only remove
+/// geometry, so without sharp edges or height quantization the estimate is an
+/// upper bound.
+///
+/// # Panics
+///
+/// Panics if the heightmap dimensions are less than 2×2.
+pub fn estimate_mesh_bytes(
+    heightmap: &HeightMap,
+    builder: &HeightMapMeshBuilder,
+) -> MeshSizeEstimate {
+    assert_min_size(heightmap);
+    let step = builder.subdivisions as usize + 1;
+    let border = 2 * builder.normal_border;
+    let w = (heightmap.width().saturating_sub(border + 1)) * step + 1;
+    let h = (heightmap.height().saturating_sub(border + 1)) * step + 1;
+    let cells = (w - 1) * (h - 1);
+
+    let normal = match builder.normal_encoding {
+        NormalEncoding::Float32x3 => VertexFormat::Float32x3,
+        NormalEncoding::Oct16 => VertexFormat::Snorm16x2,
+    };
+    let mut formats = vec![VertexFormat::Float32x3, normal, VertexFormat::Float32x2];
+    if builder.overlay_uv {
+        formats.push(VertexFormat::Float32x2);
+    }
+    if builder.height_attribute {
+        formats.push(VertexFormat::Float32);
+    }
+    if builder.vertex_splat.is_some() {
+        formats.push(VertexFormat::Float32x4);
+    }
+    formats.extend(builder.custom_attributes.iter().map(|c| c.attribute.format));
+
+    let (vertex_count, index_count) = if builder.topology == Topology::Strip {
+        let rows = h - 1;
+        let index_count = match builder.diagonal {
+            QuadDiagonal::TopRightBottomLeft => rows * 2 * w + (rows - 1) * 2,
+            QuadDiagonal::TopLeftBottomRight => rows * (2 * w + 1) + (rows - 1),
+        };
+        (w * h, index_count)
+    } else {
+        let index_count = cells * 6;
+        let mut vertex_count = w * h;
+        if builder.uv_mode == UvMode::PerCell || builder.barycentric {
+            vertex_count = index_count;
+        }
+        if builder.barycentric {
+            formats.push(VertexFormat::Float32x3);
+        }
+        if builder.double_sided {
+            (vertex_count * 2, index_count * 2)
+        } else {
+            (vertex_count, index_count)
+        }
+    };
+
+    let stride: u64 = formats.iter().map(|f| f.size()).sum();
+    let vertex_bytes = vertex_count * stride as usize;
+    let index_bytes = index_count * size_of::<u32>();
+    MeshSizeEstimate {
+        vertex_count,
+        index_count,
+        vertex_bytes,
+        index_bytes,
+        total: vertex_bytes + index_bytes,
+    }
+}
+
+/// Band of each triangle of an indexed `TriangleList` mesh by the average
+/// height of its vertices; see [`HeightMapMeshBuilder::with_height_bands`].
+fn triangle_height_bands(mesh: &Mesh, bounds: &[f32]) -> Vec<usize> {
+    let (Some(VertexAttributeValues::Float32x3(positions)), Some(indices)) =
+        (mesh.attribute(Mesh::ATTRIBUTE_POSITION), mesh.indices())
+    else {
+        return Vec::new();
+    };
+    let indices: Vec<usize> = indices.iter().collect();
+    indices
+        .chunks_exact(3)
+        .map(|t| {
+            let average = t.iter().map(|&i| positions[i][1]).sum::<f32>() / 3.0;
+            bounds.partition_point(|&b| b <= average)
+        })
+        .collect()
+}
+
+/// Stably reorders the triangles of `mesh` so each height band is
+/// contiguous, in ascending band order.
+fn sort_by_height_band(mesh: &mut Mesh, bounds: &[f32]) {
+    let bands = triangle_height_bands(mesh, bounds);
+    let Some(indices) = mesh.indices() else {
+        return;
+    };
+    let indices: Vec<u32> = indices.iter().map(|i| i as u32).collect();
+    let mut order: Vec<usize> = (0..bands.len()).collect();
+    order.sort_by_key(|&t| bands[t]);
+    let sorted = order
+        .into_iter()
+        .flat_map(|t| indices[3 * t..3 * t + 3].iter().copied())
+        .collect();
+    mesh.insert_indices(Indices::U32(sorted));
+}
+
+/// Builds CCW triangle indices for a `w × h` vertex grid.
+///
+/// Normals point +Y when the terrain is flat. Each quad (x, z) → (x+1, z+1)
+/// emits two triangles split along `diagonal` (see [`QuadDiagonal`]).
+fn grid_indices(w: usize, h: usize, diagonal: QuadDiagonal) -> Vec<u32> {
+    let quad_count = (w - 1) * (h - 1);
+    let mut indices: Vec<u32> = Vec::with_capacity(quad_count * 6);
+
+    for z in 0..(h - 1) {
+        for x in 0..(w - 1) {
+            let tl = (z * w + x) as u32;
+            let tr = (z * w + x + 1) as u32;
+            let bl = ((z + 1) * w + x) as u32;
+            let br = ((z + 1) * w + x + 1) as u32;
+
+            for tri in diagonal.split(tl, tr, bl, br) {
+                indices.extend_from_slice(&tri);
+            }
+        }
+    }
+    indices
+}
+
+/// Builds `TriangleStrip` indices for a `w × h` vertex grid.
+///
+/// Each row of quads is one strip alternating between the row and the row
+/// below; rows are joined by repeating the last vertex of one row and the
+/// first of the next, which yields zero-area triangles the GPU skips. The
+/// zig-zag order naturally splits along the `tr–bl` diagonal; for `tl–br` the
+/// rows run `bl, tl, br, tr, …` with a leading repeat so strip parity keeps
+/// every real triangle counter-clockwise. Each row starts at an even index,
+/// so the surface and winding match [`grid_indices`] exactly.
+fn strip_indices(w: usize, h: usize, diagonal: QuadDiagonal) -> Vec<u32> {
+    let mut indices: Vec<u32> = Vec::with_capacity((h - 1) * (2 * w + 3));
+    for z in 0..(h - 1) {
+        let top = |x: usize| (z * w + x) as u32;
+        let bottom = |x: usize| ((z + 1) * w + x) as u32;
+        let row_start = match diagonal {
+            QuadDiagonal::TopRightBottomLeft => top(0),
+            QuadDiagonal::TopLeftBottomRight => bottom(0),
+        };
+
+        if z > 0 {
+            // Degenerate join from the previous row's last vertex.
+            let last = *indices.last().expect("previous row emitted vertices");
+            indices.push(last);
+            if diagonal == QuadDiagonal::TopRightBottomLeft {
+                indices.push(row_start);
+            }
+        }
+        if diagonal == QuadDiagonal::TopLeftBottomRight {
+            indices.push(row_start);
+        }
+        for x in 0..w {
+            match diagonal {
+                QuadDiagonal::TopRightBottomLeft => indices.extend([top(x), bottom(x)]),
+                QuadDiagonal::TopLeftBottomRight => indices.extend([bottom(x), top(x)]),
+            }
+        }
+    }
+    indices
+}
+
+/// Builds indices for the vertices in `rect`, merging flat rectangles into fans.
+///
+/// See [`HeightMapMeshBuilder::with_flat_merge`]. Cells not covered by a
+/// merged rectangle (a fan that beats `2·w·h` triangles) are split along
+/// `diagonal` as usual.
+/// Cells flagged in `holes` (row-major over `rect`'s cells) emit nothing.
+fn flat_merged_indices(
+    heightmap: &HeightMap,
+    rect: GridRect,
+    diagonal: QuadDiagonal,
+    epsilon: f32,
+    holes: Option<&[bool]>,
+) -> Vec<u32> {
+    let w = rect.width();
+    let cells_x = w - 1;
+    let cells_z = rect.height() - 1;
+    let height = |x: usize, z: usize| heightmap.get(rect.x0 + x, rect.z0 + z);
+    let index = |x: usize, z: usize| (z * w + x) as u32;
+
+    // (min, max) height of the vertex segments along a column or a row.
+    let span = |points: &mut dyn Iterator<Item = (usize, usize)>| {
+        points.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), (x, z)| {
+            let y = height(x, z);
+            (lo.min(y), hi.max(y))
+        })
+    };
+    let column = |x: usize, z0: usize, z1: usize| span(&mut (z0..=z1).map(|z| (x, z)));
+    let row = |z: usize, x0: usize, x1: usize| span(&mut (x0..=x1).map(|x| (x, z)));
+    let union = |(lo, hi): (f32, f32), (a, b): (f32, f32)| (lo.min(a), hi.max(b));
+    let is_flat = |(lo, hi): (f32, f32)| hi - lo <= epsilon;
+
+    // Hole cells start out assigned, so they are neither merged nor emitted.
+    let mut assigned = match holes {
+        Some(holes) => holes.to_vec(),
+        None => vec![false; cells_x * cells_z],
+    };
+    let mut indices = Vec::with_capacity(cells_x * cells_z * 6);
+
+    for cz in 0..cells_z {
+        for cx in 0..cells_x {
+            if assigned[cz * cells_x + cx] {
+                continue;
+            }
+
+            // Grow right, then down, while all vertices stay within epsilon.
+            let mut range = union(column(cx, cz, cz + 1), column(cx + 1, cz, cz + 1));
+            let (mut x1, mut z1) = (cx + 1, cz + 1);
+            if is_flat(range) {
+                while x1 < cells_x
+                    && !assigned[cz * cells_x + x1]
+                    && is_flat(union(range, column(x1 + 1, cz, cz + 1)))
+                {
+                    range = union(range, column(x1 + 1, cz, cz + 1));
+                    x1 += 1;
+                }
+                while z1 < cells_z
+                    && (cx..x1).all(|x| !assigned[z1 * cells_x + x])
+                    && is_flat(union(range, row(z1 + 1, cx, x1)))
+                {
+                    range = union(range, row(z1 + 1, cx, x1));
+                    z1 += 1;
+                }
+            }
+
+            // A fan has 2·(span_x+span_z) triangles against 2·span_x·span_z
+            // for the regular cells, and its apex must be strictly inside.
+            let (span_x, span_z) = (x1 - cx, z1 - cz);
+            let saves = span_x >= 2 && span_z >= 2 && span_x + span_z < span_x * span_z;
+            if !saves {
+                assigned[cz * cells_x + cx] = true;
+                let quad = diagonal.split(
+                    index(cx, cz),
+                    index(cx + 1, cz),
+                    index(cx, cz + 1),
+                    index(cx + 1, cz + 1),
+                );
+                for tri in quad {
+                    indices.extend_from_slice(&tri);
+                }
+                continue;
+            }
+
+            for z in cz..z1 {
+                for x in cx..x1 {
+                    assigned[z * cells_x + x] = true;
+                }
+            }
+
+            // Boundary in CCW order (matching the winding of grid triangles):
+            // down the left edge, along the bottom, up the right, back along the top.
+            let mut boundary = Vec::with_capacity(2 * (span_x + span_z));
+            boundary.extend((cz..z1).map(|z| index(cx, z)));
+            boundary.extend((cx..x1).map(|x| index(x, z1)));
+            boundary.extend((cz + 1..=z1).rev().map(|z| index(x1, z)));
+            boundary.extend((cx + 1..=x1).rev().map(|x| index(x, cz)));
+
+            let apex = index(cx + span_x / 2, cz + span_z / 2);
+            for (i, &a) in boundary.iter().enumerate() {
+                let b = boundary[(i + 1) % boundary.len()];
+                indices.extend_from_slice(&[apex, a, b]);
+            }
+        }
+    }
+    indices
+}
+
+/// Recomputes only the `ATTRIBUTE_NORMAL` buffer of a terrain mesh.
+///
+/// Shorthand for [`HeightMapMeshBuilder::update_normals`] with default
+/// settings and the given `method`. Use the builder method instead when the
+/// mesh was built with a non-default diagonal, smoothing, or precision.
+pub fn update_normals(mesh: &mut Mesh, heightmap: &HeightMap, method: NormalMethod) {
+    HeightMapMeshBuilder::new()
+        .with_normal_method(method)
+        .update_normals(mesh, heightmap);
+}
+
+/// Error returned by [`recompute_tangents`] when a mesh lacks the data tangents
+/// are derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TangentError {
+    /// The named attribute is missing or is not stored as `Float32x3` /
+    /// `Float32x2`.
+    MissingAttribute(&'static str),
+    /// The mesh topology is not `TriangleList`.
+    UnsupportedTopology(PrimitiveTopology),
+}
+
+impl std::fmt::Display for TangentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingAttribute(name) => {
+                write!(
+                    f,
+                    "cannot compute tangents: mesh has no usable {name} attribute"
+                )
+            }
+            Self::UnsupportedTopology(topology) => write!(
+                f,
+                "cannot compute tangents: expected a TriangleList mesh, got {topology:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TangentError {}
+
+/// Derives per-vertex tangents from a mesh's positions, normals, and UV_0.
+///
+/// For each triangle, the tangent and bitangent are solved from the edge
+/// vectors and UV deltas and accumulated (unnormalized, so larger triangles
+/// weigh more) at its three vertices. Each vertex tangent is then
+/// Gram–Schmidt orthonormalized against the vertex normal. The bitangent is
+/// encoded as the handedness sign in `w`, as Bevy expects:
+/// `bitangent = w · normal × tangent`.
+///
+/// Inserts the result as `ATTRIBUTE_TANGENT`, replacing any existing tangents.
+/// Use this after editing an already-built mesh, e.g. with
+/// [`weld_and_clean`]. Vertices whose UVs are degenerate receive an arbitrary
+/// tangent perpendicular to their normal.
+///
+/// # Errors
+///
+/// Returns [`TangentError`] if positions, normals, or UV_0 are missing, or the
+/// mesh is not a `TriangleList`. The mesh is unchanged on error.
+pub fn recompute_tangents(mesh: &mut Mesh) -> Result<(), TangentError> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return Err(TangentError::UnsupportedTopology(mesh.primitive_topology()));
+    }
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return Err(TangentError::MissingAttribute("POSITION"));
+    };
+    let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        return Err(TangentError::MissingAttribute("NORMAL"));
+    };
+    let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) else {
+        return Err(TangentError::MissingAttribute("UV_0"));
+    };
+
+    let triangle_indices: Vec<usize> = match mesh.indices() {
+        Some(indices) => indices.iter().collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    let mut tangents = vec![Vec3::ZERO; positions.len()];
+    let mut bitangents = vec![Vec3::ZERO; positions.len()];
+    for tri in triangle_indices.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]];
+        let e1 = Vec3::from(positions[b]) - Vec3::from(positions[a]);
+        let e2 = Vec3::from(positions[c]) - Vec3::from(positions[a]);
+        let d1 = Vec2::from(uvs[b]) - Vec2::from(uvs[a]);
+        let d2 = Vec2::from(uvs[c]) - Vec2::from(uvs[a]);
+
+        let det = d1.x * d2.y - d2.x * d1.y;
+        if det.abs() <= f32::EPSILON {
+            continue;
+        }
+        let t = (e1 * d2.y - e2 * d1.y) / det;
+        let bt = (e2 * d1.x - e1 * d2.x) / det;
+        for &i in tri {
+            tangents[i] += t;
+            bitangents[i] += bt;
+        }
+    }
+
+    let result: Vec<[f32; 4]> = (0..positions.len())
+        .map(|i| {
+            let n = Vec3::from(normals[i]);
+            let t = (tangents[i] - n * n.dot(tangents[i]))
+                .try_normalize()
+                .unwrap_or_else(|| n.any_orthonormal_vector());
+            let w = if n.cross(t).dot(bitangents[i]) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            t.extend(w).into()
+        })
+        .collect();
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_TANGENT, result);
+    Ok(())
+}
+
+/// A side of a terrain mesh's XZ footprint, for [`weld_seam`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// The side with the smallest X.
+    MinX,
+    /// The side with the largest X.
+    MaxX,
+    /// The side with the smallest Z.
+    MinZ,
+    /// The side with the largest Z.
+    MaxZ,
+}
+
+impl Edge {
+    /// Position component across the edge, and along it.
+    fn axes(self) -> (usize, usize) {
+        match self {
+            Edge::MinX | Edge::MaxX => (0, 2),
+            Edge::MinZ | Edge::MaxZ => (2, 0),
+        }
+    }
+
+    /// Vertices on this edge of `positions`, with their offset along the edge
+    /// from its first vertex.
+    fn vertices(self, positions: &[[f32; 3]]) -> Vec<(usize, f32)> {
+        let (across, along) = self.axes();
+        let extreme = match self {
+            Edge::MinX | Edge::MinZ => positions
+                .iter()
+                .map(|p| p[across])
+                .fold(f32::INFINITY, f32::min),
+            Edge::MaxX | Edge::MaxZ => positions
+                .iter()
+                .map(|p| p[across])
+                .fold(f32::NEG_INFINITY, f32::max),
+        };
+        let tolerance = SEAM_TOLERANCE * extreme.abs().max(1.0);
+        let on_edge: Vec<usize> = (0..positions.len())
+            .filter(|&i| (positions[i][across] - extreme).abs() <= tolerance)
+            .collect();
+        let start = on_edge
+            .iter()
+            .map(|&i| positions[i][along])
+            .fold(f32::INFINITY, f32::min);
+        on_edge
+            .into_iter()
+            .map(|i| (i, positions[i][along] - start))
+            .collect()
+    }
+}
+
+/// Relative tolerance for matching seam vertices.
+const SEAM_TOLERANCE: f32 = 1e-4;
+
+/// Stitches the shared edge of two independently built neighbouring meshes.
+///
+/// Vertices on `edge_a` of `mesh_a` and `edge_b` of `mesh_b` are paired by
+/// their distance along the edge from its first vertex, so the meshes may
+/// live in separate local spaces (e.g. each built from its own heightmap
+/// and placed side by side with transforms). Every pair — and any duplicates
+/// at the same spot — gets the average of their heights and of their
+/// normals, so positions meet exactly and lighting is continuous across the
+/// join. X and Z are left untouched.
+///
+/// Returns the number of `mesh_a` vertices that found a partner. Meshes
+/// without `Float32x3` positions and normals are left unchanged.
+pub fn weld_seam(mesh_a: &mut Mesh, edge_a: Edge, mesh_b: &mut Mesh, edge_b: Edge) -> usize {
+    let (Some(positions_a), Some(positions_b)) = (seam_positions(mesh_a), seam_positions(mesh_b))
+    else {
+        return 0;
+    };
+    let (Some(normals_a), Some(normals_b)) = (
+        mesh_a
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .and_then(|n| n.as_float3()),
+        mesh_b
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .and_then(|n| n.as_float3()),
+    ) else {
+        return 0;
+    };
+    let side_a = edge_a.vertices(&positions_a);
+    let side_b = edge_b.vertices(&positions_b);
+    let length = side_a.iter().map(|v| v.1).fold(0.0, f32::max);
+    let tolerance = SEAM_TOLERANCE * length.max(1.0);
+
+    // Averaged (height, normal) per matched spot, applied afterwards.
+    let mut updates_a = Vec::new();
+    let mut updates_b = Vec::new();
+    for &(_, offset) in &side_a {
+        let near = |side: &[(usize, f32)]| -> Vec<usize> {
+            side.iter()
+                .filter(|v| (v.1 - offset).abs() <= tolerance)
+                .map(|v| v.0)
+                .collect()
+        };
+        let (at_a, at_b) = (near(&side_a), near(&side_b));
+        if at_b.is_empty() {
+            continue;
+        }
+        let count = (at_a.len() + at_b.len()) as f32;
+        let height = (at_a.iter().map(|&i| positions_a[i][1]).sum::<f32>()
+            + at_b.iter().map(|&i| positions_b[i][1]).sum::<f32>())
+            / count;
+        let normal: Vec3 = at_a
+            .iter()
+            .map(|&i| Vec3::from(normals_a[i]).normalize_or_zero())
+            .chain(
+                at_b.iter()
+                    .map(|&i| Vec3::from(normals_b[i]).normalize_or_zero()),
+            )
+            .sum();
+        let normal = normalize_or_up(normal);
+        updates_a.extend(at_a.into_iter().map(|i| (i, height, normal)));
+        updates_b.extend(at_b.into_iter().map(|i| (i, height, normal)));
+    }
+
+    let welded = updates_a
+        .iter()
+        .map(|u| u.0)
+        .collect::<bevy::platform::collections::HashSet<_>>()
+        .len();
+    apply_seam_updates(mesh_a, &updates_a);
+    apply_seam_updates(mesh_b, &updates_b);
+    welded
+}
+
+fn seam_positions(mesh: &Mesh) -> Option<Vec<[f32; 3]>> {
+    mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        .and_then(|p| p.as_float3())
+        .map(<[_]>::to_vec)
+}
+
+fn apply_seam_updates(mesh: &mut Mesh, updates: &[(usize, f32, [f32; 3])]) {
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for &(i, height, _) in updates {
+            positions[i][1] = height;
+        }
+    }
+    if let Some(VertexAttributeValues::Float32x3(normals)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+    {
+        for &(i, _, normal) in updates {
+            normals[i] = normal;
+        }
+    }
+}
+
+/// Removes zero-area triangles from an indexed `TriangleList` mesh.
+///
+/// A triangle is degenerate when two of its indices are equal or when the
+/// length of its face cross product is below `1e-6` times the product of its
+/// two edge lengths (i.e. the edges are parallel to within float precision).
+/// Its indices are dropped, and vertices no longer referenced are removed.
+///
+/// When `weld_vertices` is `true`, vertices with bit-identical positions are
+/// merged before the check, keeping the first occurrence's attributes; this
+/// turns coincident corners into repeated indices so their triangles are
+/// dropped as well.
+///
+/// Returns the number of triangles removed. Meshes without indices or
+/// positions are left untouched.
+pub fn weld_and_clean(mesh: &mut Mesh, weld_vertices: bool) -> usize {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return 0;
+    };
+    let Some(indices) = mesh.indices() else {
+        return 0;
+    };
+
+    let mut canonical: Vec<u32> = (0..positions.len() as u32).collect();
+    if weld_vertices {
+        let mut first = bevy::platform::collections::HashMap::new();
+        for (i, p) in positions.iter().enumerate() {
+            let key = p.map(f32::to_bits);
+            canonical[i] = *first.entry(key).or_insert(i as u32);
+        }
+    }
+
+    let remapped: Vec<u32> = indices.iter().map(|i| canonical[i]).collect();
+    let mut kept = Vec::with_capacity(remapped.len());
+    for tri in remapped.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]];
+        if a == b || b == c || a == c {
+            continue;
+        }
+        let pa = Vec3::from(positions[a as usize]);
+        let e1 = Vec3::from(positions[b as usize]) - pa;
+        let e2 = Vec3::from(positions[c as usize]) - pa;
+        if e1.cross(e2).length() <= 1e-6 * e1.length() * e2.length() {
+            continue;
+        }
+        kept.extend_from_slice(tri);
+    }
+
+    let removed = (remapped.len() - kept.len()) / 3;
+    if removed == 0 && !weld_vertices {
+        return 0;
+    }
+    mesh.insert_indices(Indices::U32(kept));
+    compact_vertices(mesh);
+    removed
+}
+
+/// Simulated post-transform cache size of [`optimize_vertex_cache`].
+const VERTEX_CACHE_SIZE: usize = 32;
+
+/// Forsyth's score of a vertex at LRU `cache_position` with `remaining`
+/// unemitted triangles. Recently used vertices and vertices with few
+/// triangles left score highest; the latter avoids stranding lone triangles.
+fn vertex_cache_score(cache_position: Option<usize>, remaining: usize) -> f32 {
+    if remaining == 0 {
+        return -1.0;
+    }
+    let cache_score = match cache_position {
+        None => 0.0,
+        // The last triangle's vertices are scored flat so the optimizer does
+        // not favour strips over fans.
+        Some(position) if position < 3 => 0.75,
+        Some(position) => {
+            let t = (position - 3) as f32 / (VERTEX_CACHE_SIZE - 3) as f32;
+            (1.0 - t).powf(1.5)
+        }
+    };
+    cache_score + 2.0 * (remaining as f32).powf(-0.5)
+}
+
+/// Reorders the triangles of a `TriangleList` index buffer with Forsyth's
+/// linear-speed vertex cache optimizer, keeping every triangle and its
+/// winding.
+fn optimize_vertex_cache(indices: &[u32], vertex_count: usize) -> Vec<u32> {
+    let triangle_count = indices.len() / 3;
+    let mut vertex_triangles = vec![Vec::new(); vertex_count];
+    for (t, tri) in indices.chunks_exact(3).enumerate() {
+        for &v in tri {
+            vertex_triangles[v as usize].push(t);
+        }
+    }
+
+    let mut cache_position: Vec<Option<usize>> = vec![None; vertex_count];
+    let mut vertex_score: Vec<f32> = vertex_triangles
+        .iter()
+        .map(|tris| vertex_cache_score(None, tris.len()))
+        .collect();
+    let triangle_score = |t: usize, vertex_score: &[f32]| -> f32 {
+        indices[t * 3..t * 3 + 3]
+            .iter()
+            .map(|&v| vertex_score[v as usize])
+            .sum()
+    };
+    let mut scores: Vec<f32> = (0..triangle_count)
+        .map(|t| triangle_score(t, &vertex_score))
+        .collect();
+    let mut emitted = vec![false; triangle_count];
+
+    let mut output = Vec::with_capacity(triangle_count * 3);
+    let mut cache: Vec<u32> = Vec::with_capacity(VERTEX_CACHE_SIZE + 3);
+    let mut best = None;
+    for _ in 0..triangle_count {
+        // Fall back to a full scan when no cached vertex has triangles left.
+        let t = best.unwrap_or_else(|| {
+            (0..triangle_count)
+                .filter(|&t| !emitted[t])
+                .max_by(|&a, &b| scores[a].total_cmp(&scores[b]))
+                .unwrap()
+        });
+        emitted[t] = true;
+        let tri = &indices[t * 3..t * 3 + 3];
+        output.extend_from_slice(tri);
+
+        for &v in tri {
+            vertex_triangles[v as usize].retain(|&other| other != t);
+        }
+        // Every vertex whose cache position may change needs rescoring,
+        // including the ones about to be evicted.
+        let mut touched = cache.clone();
+        for &v in tri.iter().rev() {
+            cache.retain(|&c| c != v);
+            cache.insert(0, v);
+        }
+        touched.extend_from_slice(tri);
+        cache.truncate(VERTEX_CACHE_SIZE);
+
+        for &v in &touched {
+            cache_position[v as usize] = None;
+        }
+        for (position, &v) in cache.iter().enumerate() {
+            cache_position[v as usize] = Some(position);
+        }
+        best = None;
+        let mut best_score = f32::NEG_INFINITY;
+        for &v in &touched {
+            let v = v as usize;
+            vertex_score[v] = vertex_cache_score(cache_position[v], vertex_triangles[v].len());
+        }
+        for &v in &touched {
+            for &other in &vertex_triangles[v as usize] {
+                scores[other] = triangle_score(other, &vertex_score);
+                if scores[other] > best_score {
+                    best_score = scores[other];
+                    best = Some(other);
+                }
+            }
+        }
+    }
+    output
+}
+
+/// Splits vertices on edges whose dihedral angle exceeds `sharp_angle`
+/// (radians), giving each smooth side of a crease its own vertex and normal.
+///
+/// Around each vertex, incident triangles that share an edge and meet below
+/// the threshold are grouped; the first group keeps the vertex and every
+/// other group gets a copy carrying all attributes. Split vertices take the
+/// area-weighted normal of their group. Degenerate triangles never split.
+fn split_sharp_edges(mesh: &mut Mesh, sharp_angle: f32) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let Some(indices) = mesh.indices() else {
+        return;
+    };
+    let indices: Vec<u32> = indices.iter().map(|i| i as u32).collect();
+    let vertex_count = positions.len();
+
+    let face_normals: Vec<Vec3> = indices
+        .chunks_exact(3)
+        .map(|t| {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| Vec3::from(positions[i as usize]));
+            (b - a).cross(c - a)
+        })
+        .collect();
+    let mut incident: Vec<Vec<usize>> = vec![Vec::new(); vertex_count];
+    for (face, tri) in indices.chunks_exact(3).enumerate() {
+        for &v in tri {
+            incident[v as usize].push(face);
+        }
+    }
+
+    let min_cos = sharp_angle.cos();
+    let smooth = |f: usize, g: usize| {
+        let (a, b) = (
+            face_normals[f].normalize_or_zero(),
+            face_normals[g].normalize_or_zero(),
+        );
+        a == Vec3::ZERO || b == Vec3::ZERO || a.dot(b) >= min_cos
+    };
+    let shares_edge = |f: usize, g: usize, v: u32| {
+        let tri = |face: usize| &indices[face * 3..face * 3 + 3];
+        tri(f).iter().any(|&i| i != v && tri(g).contains(&i))
+    };
+
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut source: Vec<u32> = (0..vertex_count as u32).collect();
+    let mut split_normals: Vec<(usize, Vec3)> = Vec::new();
+    let mut new_indices = indices.clone();
+    for (v, faces) in incident.iter().enumerate() {
+        if faces.len() < 2 {
+            continue;
+        }
+        // Union-find over the faces around `v`.
+        let mut parent: Vec<usize> = (0..faces.len()).collect();
+        for i in 0..faces.len() {
+            for j in (i + 1)..faces.len() {
+                if shares_edge(faces[i], faces[j], v as u32) && smooth(faces[i], faces[j]) {
+                    let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                    parent[ri] = rj;
+                }
+            }
+        }
+
+        let mut groups: Vec<(usize, Vec<usize>)> = Vec::new();
+        for (i, &face) in faces.iter().enumerate() {
+            let r = root(&mut parent, i);
+            match groups.iter_mut().find(|(gr, _)| *gr == r) {
+                Some((_, members)) => members.push(face),
+                None => groups.push((r, vec![face])),
+            }
+        }
+        if groups.len() < 2 {
+            continue;
+        }
+
+        for (n, (_, members)) in groups.iter().enumerate() {
+            let target = if n == 0 {
+                v
+            } else {
+                source.push(v as u32);
+                source.len() - 1
+            };
+            let normal: Vec3 = members.iter().map(|&f| face_normals[f]).sum();
+            split_normals.push((target, normal));
+            for &f in members {
+                for slot in &mut new_indices[f * 3..f * 3 + 3] {
+                    if *slot == v as u32 {
+                        *slot = target as u32;
+                    }
+                }
+            }
+        }
+    }
+    if split_normals.is_empty() {
+        return;
+    }
+
+    mesh.insert_indices(Indices::U32(source));
+    mesh.duplicate_vertices();
+    if let Some(VertexAttributeValues::Float32x3(normals)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+    {
+        for (vertex, normal) in split_normals {
+            normals[vertex] = normalize_or_up(normal);
+        }
+    }
+    mesh.insert_indices(Indices::U32(new_indices));
+}
+
+/// Splits every triangle whose corners lie on different levels into flat
+/// regions joined by vertical risers, for quantized terraces.
+///
+/// Each edge is split at its midpoint. The corner opposite the longest edge —
+/// the cell diagonal — keeps the quadrant between its two edge midpoints and
+/// the diagonal midpoint, and the other two corners the half-quadrants beside
+/// it, so steps follow the cell midlines and line up across cells. Every
+/// region stays at its corner's height, and a riser quad closes the step
+/// between the opposite corner's region and each neighbour on another level.
+/// Split-off vertices interpolate every float attribute along their edge.
+/// Afterwards every triangle is horizontal or vertical, so tops get `+Y`
+/// normals and risers their horizontal face normal, facing the lower side.
+fn split_terrace_risers(mesh: &mut Mesh) {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        return;
+    };
+    let Some(indices) = mesh.indices() else {
+        return;
+    };
+    let indices: Vec<u32> = indices.iter().map(|i| i as u32).collect();
+    let positions = positions.clone();
+    let p = |i: u32| Vec3::from(positions[i as usize]);
+
+    // Heights of the corners facing each edge, keyed by its endpoints, so a
+    // triangle can find the far corner of its cell across the diagonal.
+    let key = |i: u32| positions[i as usize].map(f32::to_bits);
+    let edge = |i: u32, j: u32| {
+        let (a, b) = (key(i), key(j));
+        if a <= b { (a, b) } else { (b, a) }
+    };
+    let mut facing_heights: HashMap<_, Vec<f32>> = HashMap::new();
+    for tri in indices.chunks_exact(3) {
+        for k in 0..3 {
+            facing_heights
+                .entry(edge(tri[k], tri[(k + 1) % 3]))
+                .or_default()
+                .push(p(tri[(k + 2) % 3]).y);
+        }
+    }
+
+    // Vertex `v` copies `source[v]`; split-off vertices then average their
+    // float attributes with a partner and take an override height and normal.
+    let mut source: Vec<u32> = (0..positions.len() as u32).collect();
+    let mut partners: Vec<(usize, u32)> = Vec::new();
+    let mut overrides: Vec<(usize, f32, Vec3)> = Vec::new();
+    let mut split = |a: u32, b: u32, y: f32, normal: Vec3| {
+        let v = source.len();
+        source.push(a);
+        partners.push((v, b));
+        overrides.push((v, y, normal));
+        v as u32
+    };
+
+    let mut new_indices = Vec::with_capacity(indices.len());
+    for tri in indices.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]];
+        if p(a).y == p(b).y && p(b).y == p(c).y {
+            new_indices.extend_from_slice(tri);
+            continue;
+        }
+        // Rotate so `r` faces the longest edge, keeping the winding.
+        let span = |i: u32, j: u32| (p(i) - p(j)).xz().length_squared();
+        let [r, d1, d2] = [[a, b, c], [b, c, a], [c, a, b]]
+            .into_iter()
+            .max_by(|u, v| span(u[1], u[2]).total_cmp(&span(v[1], v[2])))
+            .unwrap_or([a, b, c]);
+        let hr = p(r).y;
+        let mut levels = vec![p(d1).y, p(d2).y];
+        levels.extend_from_slice(&facing_heights[&edge(d1, d2)]);
+
+        let m1 = split(r, d1, hr, Vec3::Y);
+        let centre = split(d1, d2, hr, Vec3::Y);
+        let m2 = split(r, d2, hr, Vec3::Y);
+        new_indices.extend([r, m1, centre, r, centre, m2]);
+        for (d, other, before) in [(d1, d2, true), (d2, d1, false)] {
+            let h = p(d).y;
+            let edge = split(d, r, h, Vec3::Y);
+            let centre = split(d, other, h, Vec3::Y);
+            new_indices.extend(if before {
+                [edge, d, centre]
+            } else {
+                [centre, d, edge]
+            });
+            if h == hr {
+                continue;
+            }
+
+            // The riser runs from the midpoint of `r–d` to the centre and
+            // faces whichever side is lower. Its centre edge is split at
+            // every corner height of the cell it spans, matching the risers
+            // of the other quadrants meeting there.
+            let (from, to) = ((p(r) + p(d)) * 0.5, (p(d1) + p(d2)) * 0.5);
+            let along = (to - from).xz();
+            let mut facing = Vec3::new(-along.y, 0.0, along.x).normalize_or_zero();
+            if facing.dot(p(d) - from) < 0.0 {
+                facing = -facing;
+            }
+            if h > hr {
+                facing = -facing;
+            }
+            let (lo, hi) = (hr.min(h), hr.max(h));
+            let mut chain: Vec<f32> = levels
+                .iter()
+                .copied()
+                .filter(|&l| l > lo && l < hi)
+                .chain([lo, hi])
+                .collect();
+            chain.sort_by(f32::total_cmp);
+            chain.dedup();
+
+            let base = (split(r, d, lo, facing), from.with_y(lo));
+            let top = (split(r, d, hi, facing), from.with_y(hi));
+            let centre: Vec<(u32, Vec3)> = chain
+                .iter()
+                .map(|&y| (split(d1, d2, y, facing), to.with_y(y)))
+                .collect();
+            let fan = centre
+                .windows(2)
+                .map(|pair| [base, pair[0], pair[1]])
+                .chain([[base, centre[centre.len() - 1], top]]);
+            for [a, b, c] in fan {
+                let front = (b.1 - a.1).cross(c.1 - a.1).dot(facing) > 0.0;
+                new_indices.extend(if front {
+                    [a.0, b.0, c.0]
+                } else {
+                    [a.0, c.0, b.0]
+                });
+            }
+        }
+    }
+
+    mesh.insert_indices(Indices::U32(source));
+    mesh.duplicate_vertices();
+    for (_, values) in mesh.attributes_mut() {
+        match values {
+            VertexAttributeValues::Float32(v) => {
+                for &(i, j) in &partners {
+                    v[i] = 0.5 * (v[i] + v[j as usize]);
+                }
+            }
+            VertexAttributeValues::Float32x2(v) => {
+                for &(i, j) in &partners {
+                    v[i] = ((Vec2::from(v[i]) + Vec2::from(v[j as usize])) * 0.5).into();
+                }
+            }
+            VertexAttributeValues::Float32x3(v) => {
+                for &(i, j) in &partners {
+                    v[i] = ((Vec3::from(v[i]) + Vec3::from(v[j as usize])) * 0.5).into();
+                }
+            }
+            VertexAttributeValues::Float32x4(v) => {
+                for &(i, j) in &partners {
+                    v[i] = ((Vec4::from(v[i]) + Vec4::from(v[j as usize])) * 0.5).into();
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for &(i, y, _) in &overrides {
+            positions[i][1] = y;
+        }
+    }
+    if let Some(VertexAttributeValues::Float32x3(normals)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+    {
+        normals.fill([0.0, 1.0, 0.0]);
+        for &(i, _, normal) in &overrides {
+            normals[i] = normal.into();
+        }
+    }
+    mesh.insert_indices(Indices::U32(new_indices));
+    compact_vertices(mesh);
+}
+
+/// Gives every triangle of a grid mesh its own vertices, with UVs spanning
+/// `0..1` across the cell it belongs to.
+///
+/// `indices` are in grid numbering (`z * w + x`). Each triangle's cell is the
+/// minimum grid coordinate of its corners; corner UVs are their offsets from
+/// it, passed through the builder's UV transform.
+fn split_per_cell_uvs(mesh: &mut Mesh, indices: &[u32], w: usize, matrix: Mat2, offset: Vec2) {
+    let uvs: Vec<[f32; 2]> = indices
+        .chunks_exact(3)
+        .flat_map(|tri| {
+            let grid = [tri[0], tri[1], tri[2]].map(|i| UVec2::new(i % w as u32, i / w as u32));
+            let cell = grid[0].min(grid[1]).min(grid[2]);
+            grid.map(|g| (matrix * (g - cell).as_vec2() + offset).to_array())
+        })
+        .collect();
+
+    mesh.insert_indices(Indices::U32(indices.to_vec()));
+    mesh.duplicate_vertices();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32((0..indices.len() as u32).collect()));
+}
+
+/// World-space XZ distance of vertex `(x, z)` from the centre of the map.
+fn radial_distance(heightmap: &HeightMap, x: usize, z: usize) -> f32 {
+    let center = Vec2::new(
+        (heightmap.width() - 1) as f32,
+        (heightmap.height() - 1) as f32,
+    ) * 0.5;
+    (Vec2::new(x as f32, z as f32) - center).length() * heightmap.scale()
+}
+
+/// Gives every triangle its own three vertices and writes
+/// [`ATTRIBUTE_BARYCENTRIC`] basis vectors onto them.
+fn insert_barycentric(mesh: &mut Mesh) {
+    let Some(count) = mesh.indices().map(|i| i.len()) else {
+        return;
+    };
+    mesh.duplicate_vertices();
+    let basis = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let barycentric: Vec<[f32; 3]> = (0..count).map(|i| basis[i % 3]).collect();
+    mesh.insert_attribute(ATTRIBUTE_BARYCENTRIC, barycentric);
+    mesh.insert_indices(Indices::U32((0..count as u32).collect()));
+}
+
+/// Number of non-degenerate triangles drawn by `mesh`'s index buffer.
+fn triangle_count(mesh: &Mesh) -> usize {
+    let indices: Vec<usize> = mesh
+        .indices()
+        .map(|i| i.iter().collect())
+        .unwrap_or_default();
+    match mesh.primitive_topology() {
+        PrimitiveTopology::TriangleStrip => indices
+            .windows(3)
+            .filter(|t| t[0] != t[1] && t[1] != t[2] && t[0] != t[2])
+            .count(),
+        _ => indices.len() / 3,
+    }
+}
+
+/// Duplicates every vertex with a negated normal and adds each triangle again
+/// over the copies with reversed winding.
+fn append_back_faces(mesh: &mut Mesh) {
+    let Some(indices) = mesh.indices() else {
+        return;
+    };
+    let indices: Vec<u32> = indices.iter().map(|i| i as u32).collect();
+    let n = mesh.count_vertices() as u32;
+
+    mesh.insert_indices(Indices::U32((0..n).chain(0..n).collect()));
+    mesh.duplicate_vertices();
+    if let Some(VertexAttributeValues::Float32x3(normals)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+    {
+        for normal in &mut normals[n as usize..] {
+            *normal = normal.map(|c| -c);
+        }
+    }
+    let back = indices
+        .chunks_exact(3)
+        .flat_map(|t| [t[0] + n, t[2] + n, t[1] + n]);
+    let doubled = indices.iter().copied().chain(back).collect();
+    mesh.insert_indices(Indices::U32(doubled));
+}
+
+/// Removes vertices not referenced by the index buffer, preserving order.
+///
+/// Works on every attribute present by routing the kept vertex list through
+/// [`Mesh::duplicate_vertices`].
+pub(crate) fn compact_vertices(mesh: &mut Mesh) {
+    let Some(indices) = mesh.indices() else {
+        return;
+    };
+    let mut used = vec![false; mesh.count_vertices()];
+    for i in indices.iter() {
+        used[i] = true;
+    }
+    if used.iter().all(|&u| u) {
+        return;
+    }
+
+    let mut remap = vec![u32::MAX; used.len()];
+    let mut keep = Vec::new();
+    for (i, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+        remap[i] = keep.len() as u32;
+        keep.push(i as u32);
+    }
+    let remapped: Vec<u32> = indices.iter().map(|i| remap[i]).collect();
+
+    mesh.insert_indices(Indices::U32(keep));
+    mesh.duplicate_vertices();
+    mesh.insert_indices(Indices::U32(remapped));
+}
+
+/// Normalizes an accumulated normal, falling back to +Y when degenerate.
+fn normalize_or_up(n: Vec3) -> [f32; 3] {
+    let len = n.length();
+    if len > f32::EPSILON {
+        (n / len).into()
+    } else {
+        [0.0, 1.0, 0.0]
+    }
+}
+
+/// Computes area-weighted per-vertex normals for the vertices inside `rect`.
+///
+/// Accumulates unnormalized face normals (cross products) at each vertex. The
+/// cross-product magnitude equals twice the triangle area, so larger triangles
+/// contribute proportionally more (area weighting). Reflects the actual
+/// rendered geometry.
+///
+/// Faces are gathered from `rect` grown by one vertex, so vertices on the
+/// rectangle's border see the same neighbouring triangles they would in a
+/// full-map build.
+fn compute_normals_area_weighted(
+    heightmap: &HeightMap,
+    rect: GridRect,
+    diagonal: QuadDiagonal,
+) -> Vec<[f32; 3]> {
+    let outer = rect.grow(1, heightmap);
+    let ow = outer.width();
+    let oh = outer.height();
+    let s = heightmap.scale();
+
+    let positions: Vec<Vec3> = (outer.z0..=outer.z1)
+        .flat_map(|z| {
+            (outer.x0..=outer.x1)
+                .map(move |x| Vec3::new(x as f32 * s, heightmap.get(x, z), z as f32 * s))
+        })
+        .collect();
+
+    let mut acc: Vec<Vec3> = vec![Vec3::ZERO; ow * oh];
+    for tri in grid_indices(ow, oh, diagonal).chunks_exact(3) {
+        let [i0, i1, i2] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        let p0 = positions[i0];
+        let face_normal = (positions[i1] - p0).cross(positions[i2] - p0);
+        acc[i0] += face_normal;
+        acc[i1] += face_normal;
+        acc[i2] += face_normal;
+    }
+
+    crop(&acc, outer, rect)
+        .into_iter()
+        .map(normalize_or_up)
+        .collect()
+}
+
+/// Double-precision variant of [`compute_normals_area_weighted`].
+///
+/// Positions are rebuilt from the grid indices in `f64`, so large world
+/// coordinates and non-representable scales do not lose precision before
+/// the cross products are taken.
+fn compute_normals_area_weighted_f64(
+    heightmap: &HeightMap,
+    rect: GridRect,
+    diagonal: QuadDiagonal,
+) -> Vec<[f32; 3]> {
+    let outer = rect.grow(1, heightmap);
+    let ow = outer.width();
+    let oh = outer.height();
+    let s = heightmap.scale() as f64;
+
+    let positions: Vec<DVec3> = (outer.z0..=outer.z1)
+        .flat_map(|z| {
+            (outer.x0..=outer.x1)
+                .map(move |x| DVec3::new(x as f64 * s, heightmap.get(x, z) as f64, z as f64 * s))
+        })
+        .collect();
+
+    let mut acc: Vec<DVec3> = vec![DVec3::ZERO; ow * oh];
+    for tri in grid_indices(ow, oh, diagonal).chunks_exact(3) {
+        let [i0, i1, i2] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+        let p0 = positions[i0];
+        let face_normal = (positions[i1] - p0).cross(positions[i2] - p0);
+        acc[i0] += face_normal;
+        acc[i1] += face_normal;
+        acc[i2] += face_normal;
+    }
+
+    crop(&acc, outer, rect)
+        .into_iter()
+        .map(|n| match n.try_normalize() {
+            Some(n) => n.as_vec3().into(),
+            None => [0.0, 1.0, 0.0],
+        })
+        .collect()
+}
+
+/// Computes per-vertex normals using a 3×3 Sobel filter over the heightmap.
+///
+/// For each grid vertex `(xi, zi)` inside `rect`, the 3×3 neighborhood of
+/// heights is sampled from the full map (edge vertices clamp to the nearest
+/// valid index). The Sobel X kernel
+/// `[[-1,0,1],[-2,0,2],[-1,0,1]]` and Sobel Z kernel
+/// `[[-1,-2,-1],[0,0,0],[1,2,1]]` produce weighted height gradients `gx` and
+/// `gz`. The surface normal follows from the cross product of the two tangent
+/// vectors:
+///
+/// ```text
+/// normal ∝ (-gx, 8·scale, -gz)
+/// ```
+///
+/// where `scale` is the world-space grid spacing. The factor `8·scale` arises
+/// because the Sobel kernels approximate the derivative as `dh/dx ≈ gx/(8s)`,
+/// so the unnormalized normal `(-dh/dx, 1, -dh/dz)` scaled by `8s` becomes
+/// `(-gx, 8s, -gz)`.
+pub(crate) fn compute_normals_sobel(heightmap: &HeightMap, rect: GridRect) -> Vec<[f32; 3]> {
+    let s = heightmap.scale();
+    sobel_sums(heightmap, rect)
+        .into_iter()
+        .map(|g| normalize_or_up(Vec3::new(-g.x, 8.0 * s, -g.y)))
+        .collect()
+}
+
+/// Raw Sobel kernel responses `(gx, gz)` for the vertices inside `rect`.
+///
+/// Samples outside the map clamp to the border. Dividing by `8·scale` turns
+/// them into the world-space slopes `(dh/dx, dh/dz)`.
+pub(crate) fn sobel_sums(heightmap: &HeightMap, rect: GridRect) -> Vec<Vec2> {
+    let w = heightmap.width();
+    let h = heightmap.height();
+
+    let sample = |xi: usize, zi: usize, dx: i32, dz: i32| -> f32 {
+        let nx = (xi as i32 + dx).clamp(0, w as i32 - 1) as usize;
+        let nz = (zi as i32 + dz).clamp(0, h as i32 - 1) as usize;
+        heightmap.get(nx, nz)
+    };
+
+    let mut sums = Vec::with_capacity(rect.width() * rect.height());
+    for zi in rect.z0..=rect.z1 {
+        for xi in rect.x0..=rect.x1 {
+            // Sobel X kernel: horizontal gradient (dh/dx direction)
             //  -1  0  1
             //  -2  0  2
             //  -1  0  1
@@ -258,14 +3989,8 @@ fn compute_normals_sobel(heightmap: &HeightMap) -> Vec<[f32; 3]> {
                 + 2.0 * sample(xi, zi, 0, 1)
                 + sample(xi, zi, 1, 1);
 
-            let n = Vec3::new(-gx, 8.0 * s, -gz);
-            let len = n.length();
-            normals.push(if len > f32::EPSILON {
-                (n / len).into()
-            } else {
-                [0.0, 1.0, 0.0]
-            });
+            sums.push(Vec2::new(gx, gz));
         }
     }
-    normals
+    sums
 }
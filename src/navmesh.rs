@@ -0,0 +1,124 @@
+//! Walkable geometry for navmesh bakers.
+//!
+//! Pathfinding bakers such as Recast want a plain triangle soup of the
+//! walkable surface, usually coarser than the render mesh.
+//! [`build_navmesh_geometry`] produces that soup straight from a
+//! [`HeightMap`], dropping hole cells and slopes too steep to walk.
+
+use bevy::prelude::*;
+use symbios_ground::HeightMap;
+
+use crate::mesher::QuadDiagonal;
+
+/// Settings for [`build_navmesh_geometry`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavmeshOptions {
+    /// Keep every `downsample`-th grid vertex along each axis. `1` uses the
+    /// full grid. The last row and column are always kept so the geometry
+    /// covers the whole map. Values below `1` are treated as `1`.
+    pub downsample: usize,
+    /// Steepest walkable slope in degrees, measured from horizontal.
+    /// Triangles steeper than this are omitted.
+    pub max_slope_degrees: f32,
+    /// Optional per-cell hole mask in the layout of
+    /// [`HeightMapMeshBuilder::with_hole_mask`](crate::HeightMapMeshBuilder::with_hole_mask).
+    /// A downsampled cell is omitted if any grid cell it covers is a hole.
+    pub hole_mask: Option<Vec<bool>>,
+    /// Diagonal used to split each cell into triangles.
+    pub diagonal: QuadDiagonal,
+}
+
+impl Default for NavmeshOptions {
+    fn default() -> Self {
+        Self {
+            downsample: 1,
+            max_slope_degrees: 45.0,
+            hole_mask: None,
+            diagonal: QuadDiagonal::default(),
+        }
+    }
+}
+
+/// Builds the walkable triangle soup of `heightmap` for a navmesh baker.
+///
+/// Returns `(vertices, indices)`: positions in the same world space as
+/// [`HeightMapMeshBuilder::build`](crate::HeightMapMeshBuilder::build) and
+/// three indices per counter-clockwise (`+Y`-facing) triangle. Only vertices
+/// referenced by a kept triangle are returned.
+///
+/// Cells are taken from the grid downsampled by
+/// [`NavmeshOptions::downsample`]; cells covering a hole are omitted, and so
+/// is each triangle whose slope exceeds [`NavmeshOptions::max_slope_degrees`].
+///
+/// # Panics
+///
+/// Panics if the heightmap is smaller than 2×2 or the hole mask does not
+/// have `(width-1)·(height-1)` entries.
+pub fn build_navmesh_geometry(
+    heightmap: &HeightMap,
+    options: &NavmeshOptions,
+) -> (Vec<[f32; 3]>, Vec<u32>) {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    assert!(
+        w >= 2 && h >= 2,
+        "HeightMap must be at least 2×2 to produce navmesh geometry"
+    );
+    if let Some(mask) = &options.hole_mask {
+        assert_eq!(
+            mask.len(),
+            (w - 1) * (h - 1),
+            "hole mask must have one entry per grid cell"
+        );
+    }
+
+    let step = options.downsample.max(1);
+    let axis = |n: usize| {
+        let mut samples: Vec<usize> = (0..n).step_by(step).collect();
+        if samples.last() != Some(&(n - 1)) {
+            samples.push(n - 1);
+        }
+        samples
+    };
+    let xs = axis(w);
+    let zs = axis(h);
+
+    let s = heightmap.scale();
+    let point = |x: usize, z: usize| Vec3::new(x as f32 * s, heightmap.get(x, z), z as f32 * s);
+    let min_normal_y = options.max_slope_degrees.to_radians().cos();
+
+    let mut remap = vec![u32::MAX; xs.len() * zs.len()];
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    for (j, z) in zs.windows(2).enumerate() {
+        for (i, x) in xs.windows(2).enumerate() {
+            let is_hole = options.hole_mask.as_ref().is_some_and(|mask| {
+                (z[0]..z[1]).any(|cz| (x[0]..x[1]).any(|cx| mask[cz * (w - 1) + cx]))
+            });
+            if is_hole {
+                continue;
+            }
+
+            let tl = (j, i);
+            let tr = (j, i + 1);
+            let bl = (j + 1, i);
+            let br = (j + 1, i + 1);
+            for tri in options.diagonal.split(tl, tr, bl, br) {
+                let [a, b, c] = tri.map(|(r, c)| point(xs[c], zs[r]));
+                let normal = (b - a).cross(c - a).normalize_or_zero();
+                if normal.y < min_normal_y {
+                    continue;
+                }
+                for (r, c) in tri {
+                    let slot = &mut remap[r * xs.len() + c];
+                    if *slot == u32::MAX {
+                        *slot = vertices.len() as u32;
+                        vertices.push(point(xs[c], zs[r]).to_array());
+                    }
+                    indices.push(*slot);
+                }
+            }
+        }
+    }
+    (vertices, indices)
+}
@@ -0,0 +1,203 @@
+//! Whole-heightmap transformations.
+//!
+//! Functions here take a [`HeightMap`] and return a new one, for preparing
+//! data before it is meshed — for example [`mirror_heightmap`], which builds
+//! symmetric terrain from a single quadrant, [`downsample`], which
+//! produces coarser LOD levels, and [`resample`] for arbitrary sizes.
+//! [`clamp_max_slope`] and [`despike`] are the exceptions: they edit heights
+//! in place.
+
+use symbios_ground::HeightMap;
+
+/// Axis (or axes) across which [`mirror_heightmap`] reflects a heightmap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorMode {
+    /// Reflect across the far X edge, doubling the width.
+    X,
+    /// Reflect across the far Z edge, doubling the height.
+    Z,
+    /// Reflect across both edges, producing a 4-way symmetric map.
+    Both,
+}
+
+/// Returns `heightmap` mirrored across its far edge(s).
+///
+/// The source occupies the low-index corner of the result and its reflection
+/// is appended along the mirrored axis. The edge row/column the reflection
+/// pivots on is shared rather than duplicated, so a mirrored axis of `n`
+/// vertices becomes `2n - 1` vertices and the seam has no flat double step.
+/// The result is symmetric about its central row/column and keeps the
+/// source's grid scale.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::ops::{MirrorMode, mirror_heightmap};
+///
+/// // Author one quadrant of an island, then mirror it into a full island.
+/// let island = mirror_heightmap(&quadrant, MirrorMode::Both);
+/// ```
+pub fn mirror_heightmap(heightmap: &HeightMap, mode: MirrorMode) -> HeightMap {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let (mirror_x, mirror_z) = match mode {
+        MirrorMode::X => (true, false),
+        MirrorMode::Z => (false, true),
+        MirrorMode::Both => (true, true),
+    };
+    let out_w = if mirror_x { 2 * w - 1 } else { w };
+    let out_h = if mirror_z { 2 * h - 1 } else { h };
+
+    // Index into the source for an output index along an axis of `n` vertices.
+    let fold = |i: usize, n: usize| if i < n { i } else { 2 * (n - 1) - i };
+
+    let mut out = HeightMap::new(out_w, out_h, heightmap.scale());
+    for z in 0..out_h {
+        for x in 0..out_w {
+            out.set(x, z, heightmap.get(fold(x, w), fold(z, h)));
+        }
+    }
+    out
+}
+
+/// Returns every `factor`-th vertex of `heightmap` along each axis.
+///
+/// The result has `(n - 1) / factor + 1` vertices along an axis of `n` and a
+/// grid scale of `scale · factor`, so it covers the same world extent as the
+/// source when `factor` divides `n - 1` (e.g. power-of-two steps on a
+/// `2^k + 1` map). Otherwise the trailing `(n - 1) % factor` rows/columns do
+/// not fit the coarser grid and are dropped. Heights are sampled, not
+/// averaged, so peaks on the kept vertices survive exactly.
+///
+/// # Panics
+///
+/// Panics if `factor` is zero.
+pub fn downsample(heightmap: &HeightMap, factor: usize) -> HeightMap {
+    assert!(factor > 0, "downsample factor must be at least 1");
+    let w = (heightmap.width() - 1) / factor + 1;
+    let h = (heightmap.height() - 1) / factor + 1;
+
+    let mut out = HeightMap::new(w, h, heightmap.scale() * factor as f32);
+    for z in 0..h {
+        for x in 0..w {
+            out.set(x, z, heightmap.get(x * factor, z * factor));
+        }
+    }
+    out
+}
+
+/// Resamples `heightmap` to `target_w × target_h` vertices by bilinear
+/// interpolation.
+///
+/// The corner vertices of the result sit exactly on the source's corners, so
+/// the result covers the same terrain at any ratio — upsampling, downsampling,
+/// or both at once, unlike the integer-step [`downsample`]. The grid scale is
+/// adjusted to keep the world width: `scale · (w - 1) / (target_w - 1)`.
+/// Since a heightmap has one scale for both axes, the world depth is only
+/// preserved too when the aspect ratio of the cell counts is unchanged
+/// (e.g. `3×3 → 5×5`).
+///
+/// # Panics
+///
+/// Panics if either target dimension is less than 2.
+pub fn resample(heightmap: &HeightMap, target_w: usize, target_h: usize) -> HeightMap {
+    assert!(
+        target_w >= 2 && target_h >= 2,
+        "resampled heightmap must be at least 2×2"
+    );
+    let s = heightmap.scale();
+    let step_x = (heightmap.width() - 1) as f32 / (target_w - 1) as f32;
+    let step_z = (heightmap.height() - 1) as f32 / (target_h - 1) as f32;
+
+    let mut out = HeightMap::new(target_w, target_h, s * step_x);
+    for z in 0..target_h {
+        for x in 0..target_w {
+            let height = heightmap.get_height_at(x as f32 * step_x * s, z as f32 * step_z * s);
+            out.set(x, z, height);
+        }
+    }
+    out
+}
+
+/// Relaxes `heightmap` in place until no slope between neighbouring vertices
+/// exceeds `max_slope_degrees`, or `iterations` sweeps have run.
+///
+/// Each sweep visits every pair of X- and Z-adjacent vertices whose height
+/// difference exceeds `tan(max_slope) · scale` and moves both heights toward
+/// each other by half the excess, so material is moved rather than removed
+/// and the total height is preserved. Sweeps update in place, and the loop
+/// stops early once a sweep changes nothing. Cliffs spread into ramps at the
+/// limit, which is what constructible areas want; a few dozen iterations
+/// settle most maps, while very tall cliffs need more. Angles are clamped to
+/// `[0°, 89.9°]`.
+pub fn clamp_max_slope(heightmap: &mut HeightMap, max_slope_degrees: f32, iterations: usize) {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let limit = max_slope_degrees.clamp(0.0, 89.9).to_radians().tan() * heightmap.scale();
+
+    let data = heightmap.data_mut();
+    for _ in 0..iterations {
+        let mut changed = false;
+        for z in 0..h {
+            for x in 0..w {
+                let i = z * w + x;
+                let neighbours = [(x + 1 < w).then_some(i + 1), (z + 1 < h).then_some(i + w)];
+                for j in neighbours.into_iter().flatten() {
+                    let diff = data[i] - data[j];
+                    let excess = diff.abs() - limit;
+                    if excess > 0.0 {
+                        let shift = 0.5 * excess * diff.signum();
+                        data[i] -= shift;
+                        data[j] += shift;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Replaces single-cell spikes and pits with the median of their neighbours.
+///
+/// Every vertex whose height differs from the median of its (up to eight)
+/// neighbours by more than `threshold` is set to that median; an even number
+/// of neighbours uses the mean of the middle two. Medians are taken from the
+/// unmodified heights, so one pass never feeds on its own output. Smooth
+/// features such as ramps stay within the threshold of their median and are
+/// preserved, while isolated generator artifacts are removed.
+pub fn despike(heightmap: &mut HeightMap, threshold: f32) {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let source = heightmap.data().to_vec();
+
+    let data = heightmap.data_mut();
+    let mut neighbours = Vec::with_capacity(8);
+    for z in 0..h {
+        for x in 0..w {
+            neighbours.clear();
+            for nz in z.saturating_sub(1)..=(z + 1).min(h - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(w - 1) {
+                    if (nx, nz) != (x, z) {
+                        neighbours.push(source[nz * w + nx]);
+                    }
+                }
+            }
+            if neighbours.is_empty() {
+                continue;
+            }
+            neighbours.sort_by(f32::total_cmp);
+            let n = neighbours.len();
+            let median = if n % 2 == 1 {
+                neighbours[n / 2]
+            } else {
+                0.5 * (neighbours[n / 2 - 1] + neighbours[n / 2])
+            };
+            if (source[z * w + x] - median).abs() > threshold {
+                data[z * w + x] = median;
+            }
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Top-down CPU previews of terrain.
+//!
+//! Provides [`render_terrain_preview`], which rasterizes a small colored
+//! top-down image of a [`HeightMap`] and its [`WeightMap`]. The result is
+//! suitable as a distant-LOD impostor billboard or a minimap.
+//! [`height_palette_image`] colors a heightmap by height bands for quick
+//! debug visualization without authoring a material, and
+//! [`composite_weight_colors`] bakes a flat-colored terrain texture from the
+//! splat weights. [`buildable_mask_image`] turns a slope limit into a mask
+//! texture for placement-preview and decal shaders, and [`edge_fade_image`]
+//! masks the map borders for blending into a backdrop. [`bake_ao_image`]
+//! bakes ambient occlusion into a texture at any resolution.
+
+use bevy::image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use symbios_ground::{HeightMap, WeightMap};
+
+use crate::mesher::{GridRect, compute_normals_sobel};
+use crate::query::sample_height_bilinear;
+
+/// Base colors of the four splat layers, in sRGB, for channels R, G, B, A.
+///
+/// Matches the grass / dirt / rock / snow preset of `SplatMapper::default`.
+pub const PREVIEW_LAYER_COLORS: [[u8; 3]; 4] = [
+    [86, 125, 70],
+    [120, 94, 66],
+    [128, 128, 128],
+    [240, 240, 245],
+];
+
+/// Direction *towards* the preview light: from the north-west (−X, −Z), 45° up.
+const LIGHT_DIR: Vec3 = Vec3::new(-0.5, std::f32::consts::FRAC_1_SQRT_2, -0.5);
+
+/// Fraction of the base color kept on surfaces facing away from the light.
+const AMBIENT: f32 = 0.25;
+
+/// Renders a `size.x × size.y` top-down color image of the terrain.
+///
+/// Each pixel samples the nearest heightmap vertex and weight-map texel under
+/// its center. The color is the [`PREVIEW_LAYER_COLORS`] entry of the dominant
+/// splat layer, hillshaded by the Sobel normal against a fixed north-west
+/// light (`+X` is right and `+Z` is down in the image, as in the mesh).
+///
+/// The weight map may have a different resolution than the heightmap; both
+/// are sampled over the full image. The returned image is `Rgba8UnormSrgb`
+/// with clamp-to-edge sampling and opaque alpha.
+///
+/// # Panics
+///
+/// Panics if either dimension of `size` is zero or the heightmap is smaller
+/// than 2×2.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::render_terrain_preview;
+///
+/// let minimap = images.add(render_terrain_preview(&heightmap, &weight_map, UVec2::splat(128)));
+/// ```
+pub fn render_terrain_preview(heightmap: &HeightMap, weight_map: &WeightMap, size: UVec2) -> Image {
+    assert!(
+        size.x > 0 && size.y > 0,
+        "preview size must be non-zero (got {}×{})",
+        size.x,
+        size.y
+    );
+    assert!(
+        heightmap.width() >= 2 && heightmap.height() >= 2,
+        "HeightMap must be at least 2×2 to render a preview (got {}×{})",
+        heightmap.width(),
+        heightmap.height()
+    );
+
+    let normals = compute_normals_sobel(heightmap, GridRect::full(heightmap));
+    let w = heightmap.width();
+
+    // Index of the cell of an `n`-wide grid under pixel `p` of `pixels`.
+    let sample = |p: u32, pixels: u32, n: usize| {
+        (((p as f32 + 0.5) / pixels as f32 * n as f32) as usize).min(n - 1)
+    };
+
+    let mut raw = Vec::with_capacity(size.x as usize * size.y as usize * 4);
+    for py in 0..size.y {
+        for px in 0..size.x {
+            let hx = sample(px, size.x, w);
+            let hz = sample(py, size.y, heightmap.height());
+            let normal = Vec3::from(normals[hz * w + hx]);
+
+            let wx = sample(px, size.x, weight_map.width);
+            let wz = sample(py, size.y, weight_map.height);
+            let weights = weight_map.data[wz * weight_map.width + wx];
+            let layer = (0..4).max_by_key(|&c| (weights[c], 3 - c)).unwrap_or(0);
+
+            let shade = AMBIENT + (1.0 - AMBIENT) * normal.dot(LIGHT_DIR).max(0.0);
+            let [r, g, b] = PREVIEW_LAYER_COLORS[layer].map(|c| (c as f32 * shade).round() as u8);
+            raw.extend_from_slice(&[r, g, b, 255]);
+        }
+    }
+
+    srgb_image(size.x, size.y, raw)
+}
+
+/// Colors each heightmap texel by its normalized height through a gradient.
+///
+/// Heights are normalized to `[0, 1]` over the map's own min/max (a perfectly
+/// flat map maps to `0`). `palette` holds `(position, color)` control stops;
+/// they need not be sorted. Between two stops the color is interpolated
+/// linearly in linear RGB, and heights outside the first/last stop take that
+/// stop's color.
+///
+/// The image is `width × height` (one texel per vertex, `+Z` down),
+/// `Rgba8UnormSrgb`, with clamp-to-edge sampling — ready to use as a
+/// `base_color_texture` for quick prototyping.
+///
+/// # Panics
+///
+/// Panics if `palette` is empty.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::height_palette_image;
+///
+/// let bands = [
+///     (0.0, Color::srgb(0.1, 0.2, 0.6)),
+///     (0.4, Color::srgb(0.3, 0.6, 0.2)),
+///     (1.0, Color::WHITE),
+/// ];
+/// let texture = images.add(height_palette_image(&heightmap, &bands));
+/// ```
+pub fn height_palette_image(heightmap: &HeightMap, palette: &[(f32, Color)]) -> Image {
+    assert!(
+        !palette.is_empty(),
+        "palette must contain at least one stop"
+    );
+
+    let mut stops: Vec<(f32, LinearRgba)> = palette
+        .iter()
+        .map(|&(t, color)| (t, color.to_linear()))
+        .collect();
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let data = heightmap.data();
+    let (lo, hi) = data
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| {
+            (lo.min(h), hi.max(h))
+        });
+    let range = hi - lo;
+
+    let raw = data
+        .iter()
+        .flat_map(|&h| {
+            let t = if range > 0.0 { (h - lo) / range } else { 0.0 };
+            Color::from(gradient(&stops, t)).to_srgba().to_u8_array()
+        })
+        .collect();
+
+    srgb_image(heightmap.width() as u32, heightmap.height() as u32, raw)
+}
+
+/// Bakes a flat-colored terrain texture by blending one color per splat layer.
+///
+/// Each pixel is `Σ (weight_i / 255) · colors[i]` over the four channels of
+/// the weight map, blended in linear space and stored as `Rgba8UnormSrgb`
+/// (alpha is blended the same way). With weights summing to 255, as
+/// `SplatMapper` produces, a pixel fully weighted to one layer is exactly
+/// that layer's color. Use it as a plain `base_color_texture` when no custom
+/// splat shader is available.
+pub fn composite_weight_colors(weight_map: &WeightMap, colors: [Color; 4]) -> Image {
+    let colors = colors.map(|c| c.to_linear());
+    let raw = weight_map
+        .data
+        .iter()
+        .flat_map(|pixel| {
+            let blended = (0..4).fold(LinearRgba::NONE, |sum, i| {
+                sum + colors[i] * (pixel[i] as f32 / 255.0)
+            });
+            Color::from(blended).to_srgba().to_u8_array()
+        })
+        .collect();
+
+    srgb_image(weight_map.width as u32, weight_map.height as u32, raw)
+}
+
+/// Bakes [`slope_mask`](crate::analysis::slope_mask) into an `R8Unorm`
+/// texture, one texel per vertex: `255` where the slope is at most
+/// `max_slope_degrees` (buildable), `0` elsewhere.
+///
+/// ```ignore
+/// let mask = images.add(buildable_mask_image(&heightmap, 30.0));
+/// ```
+pub fn buildable_mask_image(heightmap: &HeightMap, max_slope_degrees: f32) -> Image {
+    buildable_mask_image_feathered(heightmap, max_slope_degrees, 0.0)
+}
+
+/// Like [`buildable_mask_image`], with a soft boundary `feather_degrees`
+/// wide for smooth edges in the shader.
+///
+/// Texels at slopes up to `max_slope_degrees − feather_degrees` read `255`
+/// and those at `max_slope_degrees` or steeper read `0`, with a linear ramp
+/// in between, so every fully opaque texel is still buildable. A feather of
+/// `0` gives the hard mask.
+pub fn buildable_mask_image_feathered(
+    heightmap: &HeightMap,
+    max_slope_degrees: f32,
+    feather_degrees: f32,
+) -> Image {
+    let feather = feather_degrees.max(0.0);
+    let raw = compute_normals_sobel(heightmap, GridRect::full(heightmap))
+        .into_iter()
+        .map(|n| {
+            let slope = n[1].clamp(-1.0, 1.0).acos().to_degrees();
+            let t = if feather > 0.0 {
+                (max_slope_degrees - slope) / feather
+            } else if slope <= max_slope_degrees {
+                1.0
+            } else {
+                0.0
+            };
+            (t.clamp(0.0, 1.0) * 255.0).round() as u8
+        })
+        .collect();
+
+    mask_image(heightmap.width() as u32, heightmap.height() as u32, raw)
+}
+
+/// Bakes an `R8Unorm` alpha mask that fades the terrain out at its borders,
+/// one texel per vertex.
+///
+/// Texels at least `fade_cells` cells from the nearest map edge read `255`;
+/// closer ones ramp linearly down to `0` on the edge itself, so a fog or
+/// backdrop shader can blend the terrain away instead of showing a hard cut.
+/// A `fade_cells` of `0` gives a fully opaque mask.
+pub fn edge_fade_image(heightmap: &HeightMap, fade_cells: usize) -> Image {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    let raw = (0..h)
+        .flat_map(|z| (0..w).map(move |x| (x, z)))
+        .map(|(x, z)| {
+            let edge = x.min(z).min(w - 1 - x).min(h - 1 - z);
+            if edge >= fade_cells {
+                255
+            } else {
+                (edge as f32 / fade_cells as f32 * 255.0).round() as u8
+            }
+        })
+        .collect();
+    mask_image(heightmap.width() as u32, heightmap.height() as u32, raw)
+}
+
+/// Samples a gradient of sorted `(position, color)` stops at `t`.
+fn gradient(stops: &[(f32, LinearRgba)], t: f32) -> LinearRgba {
+    let upper = stops.partition_point(|&(p, _)| p <= t);
+    if upper == 0 {
+        return stops[0].1;
+    }
+    if upper == stops.len() {
+        return stops[upper - 1].1;
+    }
+    let (p0, c0) = stops[upper - 1];
+    let (p1, c1) = stops[upper];
+    c0.mix(&c1, (t - p0) / (p1 - p0))
+}
+
+/// Number of one-cell steps each [`bake_ao_image`] ray marches.
+const AO_STEPS: usize = 16;
+
+/// Bakes an `R8Unorm` ambient-occlusion texture of the terrain at any
+/// resolution.
+///
+/// Each texel looks along `samples` evenly spaced horizontal directions,
+/// marching up to 16 cells over the bilinear height field for the highest
+/// horizon, and stores `1 − mean(sin(horizon elevation))`: `255` on open
+/// plateaus and flat ground, darker in valleys, gullies, and at cliff feet.
+/// Rays stop at the map border. Texel `(px, py)` samples the world point
+/// under its center, covering the map once with `+Z` down — the layout of
+/// the overlay UVs from
+/// [`with_overlay_uv`](crate::HeightMapMeshBuilder::with_overlay_uv), so
+/// the texture can be sampled with `UV_1` at a finer resolution than the
+/// mesh has vertices. Clamp-to-edge sampling. `samples` is clamped to at
+/// least 1.
+///
+/// # Panics
+///
+/// Panics if either dimension of `resolution` is zero or the heightmap is
+/// smaller than 2×2.
+pub fn bake_ao_image(heightmap: &HeightMap, samples: u32, resolution: UVec2) -> Image {
+    assert!(
+        resolution.x > 0 && resolution.y > 0,
+        "AO resolution must be non-zero (got {}×{})",
+        resolution.x,
+        resolution.y
+    );
+    assert!(
+        heightmap.width() >= 2 && heightmap.height() >= 2,
+        "HeightMap must be at least 2×2 to bake AO (got {}×{})",
+        heightmap.width(),
+        heightmap.height()
+    );
+
+    let s = heightmap.scale();
+    let extent = Vec2::new(
+        (heightmap.width() - 1) as f32,
+        (heightmap.height() - 1) as f32,
+    ) * s;
+    let directions: Vec<Vec2> = (0..samples.max(1))
+        .map(|k| Vec2::from_angle(std::f32::consts::TAU * k as f32 / samples.max(1) as f32))
+        .collect();
+
+    let mut raw = Vec::with_capacity(resolution.x as usize * resolution.y as usize);
+    for py in 0..resolution.y {
+        for px in 0..resolution.x {
+            let uv = (UVec2::new(px, py).as_vec2() + 0.5) / resolution.as_vec2();
+            let p = uv * extent;
+            let base = sample_height_bilinear(heightmap, p.x, p.y);
+
+            let occlusion: f32 = directions
+                .iter()
+                .map(|&dir| {
+                    let mut horizon = 0.0f32;
+                    for step in 1..=AO_STEPS {
+                        let distance = step as f32 * s;
+                        let q = p + dir * distance;
+                        if q.x < 0.0 || q.y < 0.0 || q.x > extent.x || q.y > extent.y {
+                            break;
+                        }
+                        let rise = sample_height_bilinear(heightmap, q.x, q.y) - base;
+                        horizon = horizon.max(rise / rise.hypot(distance));
+                    }
+                    horizon
+                })
+                .sum();
+            let ao = 1.0 - occlusion / directions.len() as f32;
+            raw.push((ao.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+    mask_image(resolution.x, resolution.y, raw)
+}
+
+/// Wraps one `R8Unorm` byte per texel in a clamp-to-edge [`Image`].
+fn mask_image(width: u32, height: u32, raw: Vec<u8>) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        raw,
+        TextureFormat::R8Unorm,
+        default(),
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::ClampToEdge,
+        address_mode_v: ImageAddressMode::ClampToEdge,
+        ..default()
+    });
+    image
+}
+
+/// Wraps raw sRGB RGBA8 bytes in a clamp-to-edge [`Image`].
+fn srgb_image(width: u32, height: u32, raw: Vec<u8>) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        raw,
+        TextureFormat::Rgba8UnormSrgb,
+        default(),
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::ClampToEdge,
+        address_mode_v: ImageAddressMode::ClampToEdge,
+        ..default()
+    });
+    image
+}
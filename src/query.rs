@@ -0,0 +1,361 @@
+//! Point queries against the surface produced by [`HeightMapMeshBuilder`].
+//!
+//! These functions answer questions about the triangulated terrain directly
+//! from the [`HeightMap`], without building or inspecting a [`Mesh`]. They
+//! follow the same grid layout and quad split as the mesh builder, so results
+//! agree exactly with the rendered surface. The bilinear samplers trade that
+//! exactness for a smooth surface and come in batched forms for scattering
+//! many objects. [`pick_cell`] raycasts the surface from a camera for
+//! click-to-edit tools.
+//!
+//! [`HeightMapMeshBuilder`]: crate::HeightMapMeshBuilder
+
+use bevy::prelude::*;
+use symbios_ground::HeightMap;
+
+use crate::mesher::QuadDiagonal;
+
+/// Returns the triangle of the terrain surface lying under world point `(world_x, world_z)`.
+///
+/// The result holds the triangle's three world-space vertices, in the same
+/// winding order emitted by [`HeightMapMeshBuilder::build`], and its unit face
+/// normal. Each grid quad is split along the default [`QuadDiagonal`], as the
+/// mesh builder does unless configured otherwise:
+///
+/// ```text
+///   tl──tr
+///   │╲  │     Triangle 1: tl, bl, tr  (upper-left half, fx + fz <= 1)
+///   │ ╲ │     Triangle 2: tr, bl, br  (lower-right half)
+///   bl──br
+/// ```
+///
+/// Points exactly on the diagonal resolve to triangle 1. Points on the far
+/// edges of the map belong to the last row/column of quads.
+///
+/// Returns `None` if the point lies outside `[0, (w-1)·scale] × [0, (h-1)·scale]`,
+/// or if the heightmap is smaller than 2×2.
+///
+/// [`HeightMapMeshBuilder::build`]: crate::HeightMapMeshBuilder::build
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::query::triangle_at;
+///
+/// if let Some((verts, normal)) = triangle_at(&heightmap, 3.2, 7.9) {
+///     // Align a decal or footing to the face under the point.
+/// }
+/// ```
+pub fn triangle_at(heightmap: &HeightMap, world_x: f32, world_z: f32) -> Option<([Vec3; 3], Vec3)> {
+    triangle_at_with_diagonal(heightmap, world_x, world_z, QuadDiagonal::default())
+}
+
+/// Like [`triangle_at`], but splits each quad along the given [`QuadDiagonal`].
+///
+/// Use this to query a mesh built with [`HeightMapMeshBuilder::with_diagonal`].
+/// Points exactly on the diagonal resolve to the first triangle of the split.
+///
+/// [`HeightMapMeshBuilder::with_diagonal`]: crate::HeightMapMeshBuilder::with_diagonal
+pub fn triangle_at_with_diagonal(
+    heightmap: &HeightMap,
+    world_x: f32,
+    world_z: f32,
+    diagonal: QuadDiagonal,
+) -> Option<([Vec3; 3], Vec3)> {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    if w < 2 || h < 2 {
+        return None;
+    }
+
+    let s = heightmap.scale();
+    let gx = world_x / s;
+    let gz = world_z / s;
+    if !(0.0..=(w - 1) as f32).contains(&gx) || !(0.0..=(h - 1) as f32).contains(&gz) {
+        return None;
+    }
+
+    // Clamp so points on the far edges fall into the last quad.
+    let x0 = (gx.floor() as usize).min(w - 2);
+    let z0 = (gz.floor() as usize).min(h - 2);
+    let fx = gx - x0 as f32;
+    let fz = gz - z0 as f32;
+
+    let vertex = |x: usize, z: usize| Vec3::new(x as f32 * s, heightmap.get(x, z), z as f32 * s);
+    let tl = vertex(x0, z0);
+    let tr = vertex(x0 + 1, z0);
+    let bl = vertex(x0, z0 + 1);
+    let br = vertex(x0 + 1, z0 + 1);
+
+    let [first, second] = diagonal.split(tl, tr, bl, br);
+    let in_first = match diagonal {
+        QuadDiagonal::TopRightBottomLeft => fx + fz <= 1.0,
+        QuadDiagonal::TopLeftBottomRight => fz >= fx,
+    };
+    let verts = if in_first { first } else { second };
+    let normal = (verts[1] - verts[0])
+        .cross(verts[2] - verts[0])
+        .normalize_or(Vec3::Y);
+
+    Some((verts, normal))
+}
+
+/// Samples the terrain height at world point `(world_x, world_z)` by bilinear
+/// interpolation of the four surrounding grid heights.
+///
+/// Points outside the map are clamped to its border, so this never fails.
+/// Unlike [`triangle_at`], the result is the smooth bilinear surface rather
+/// than the triangulated one; the two agree at grid vertices and along cell
+/// edges. For many points at once, see [`sample_heights_bilinear`].
+pub fn sample_height_bilinear(heightmap: &HeightMap, world_x: f32, world_z: f32) -> f32 {
+    Sampler::new(heightmap).height(world_x, world_z)
+}
+
+/// Samples the unit surface normal at world point `(world_x, world_z)`.
+///
+/// The normal is derived from central differences of
+/// [`sample_height_bilinear`] one grid cell either side of the point, so it
+/// varies smoothly across cells. Points outside the map are clamped. For many
+/// points at once, see [`sample_normals_bilinear`].
+pub fn sample_normal_bilinear(heightmap: &HeightMap, world_x: f32, world_z: f32) -> Vec3 {
+    Sampler::new(heightmap).normal(world_x, world_z)
+}
+
+/// Batched [`sample_height_bilinear`] over many world-space XZ points.
+///
+/// Results are identical to the scalar function, in the order of `points`.
+/// This is a convenience for scatter placement; it does the same per-point
+/// work as calling [`sample_height_bilinear`] in a loop.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy_symbios_ground::query::sample_heights_bilinear;
+///
+/// let heights = sample_heights_bilinear(&heightmap, &tree_positions);
+/// for (p, y) in tree_positions.iter().zip(heights) {
+///     commands.spawn((tree.clone(), Transform::from_xyz(p.x, y, p.y)));
+/// }
+/// ```
+pub fn sample_heights_bilinear(heightmap: &HeightMap, points: &[Vec2]) -> Vec<f32> {
+    let sampler = Sampler::new(heightmap);
+    points.iter().map(|p| sampler.height(p.x, p.y)).collect()
+}
+
+/// Batched [`sample_normal_bilinear`] over many world-space XZ points.
+///
+/// Results are identical to the scalar function, in the order of `points`.
+pub fn sample_normals_bilinear(heightmap: &HeightMap, points: &[Vec2]) -> Vec<Vec3> {
+    let sampler = Sampler::new(heightmap);
+    points.iter().map(|p| sampler.normal(p.x, p.y)).collect()
+}
+
+/// Height profile of the terrain along the world-space segment `start → end`.
+///
+/// Returns `samples` evenly spaced `(distance_along, height)` pairs, from
+/// `(0, height at start)` to `(|end − start|, height at end)`, with heights
+/// from [`sample_height_bilinear`]. Suited to side-view profile graphs and
+/// line-of-sight checks. A single sample returns just the start point, and
+/// zero samples an empty profile.
+pub fn cross_section(heightmap: &HeightMap, start: Vec2, end: Vec2, samples: usize) -> Vec<Vec2> {
+    let sampler = Sampler::new(heightmap);
+    let length = start.distance(end);
+    let last = samples.saturating_sub(1).max(1) as f32;
+    (0..samples)
+        .map(|i| {
+            let t = i as f32 / last;
+            let p = start.lerp(end, t);
+            Vec2::new(t * length, sampler.height(p.x, p.y))
+        })
+        .collect()
+}
+
+/// Returns the grid cell under the screen position `cursor`, for
+/// click-to-edit tools.
+///
+/// `cursor` is in logical viewport pixels, as reported by
+/// `Window::cursor_position`. The viewport ray from `camera` is moved into
+/// the terrain's local space with the inverse of `terrain_transform`, then
+/// walked cell by cell across the grid and tested against both triangles of
+/// each quad, split along the default [`QuadDiagonal`] as in
+/// [`triangle_at`]. The first surface hit wins, so ridges occlude the cells
+/// behind them. Returns the `(x, z)` cell index — the quad spanning vertices
+/// `x..=x+1`, `z..=z+1` — or `None` if the ray misses the terrain, the
+/// camera has no render target yet, or the map is smaller than 2×2.
+///
+/// # Example
+///
+/// ```ignore
+/// fn edit(
+///     window: Single<&Window>,
+///     camera: Single<(&Camera, &GlobalTransform)>,
+///     terrain: Single<&Transform, With<Terrain>>,
+/// ) {
+///     let (camera, camera_transform) = *camera;
+///     if let Some(cursor) = window.cursor_position()
+///         && let Some((x, z)) = pick_cell(camera, camera_transform, cursor, &heightmap, *terrain)
+///     {
+///         // Raise cell (x, z).
+///     }
+/// }
+/// ```
+pub fn pick_cell(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    cursor: Vec2,
+    heightmap: &HeightMap,
+    terrain_transform: &Transform,
+) -> Option<(usize, usize)> {
+    let w = heightmap.width();
+    let h = heightmap.height();
+    if w < 2 || h < 2 {
+        return None;
+    }
+    let ray = camera.viewport_to_world(camera_transform, cursor).ok()?;
+    let local = terrain_transform.compute_affine().inverse();
+    let origin = local.transform_point3(ray.origin);
+    let dir = local.transform_vector3(*ray.direction);
+
+    // Clip the ray to the terrain's bounding box.
+    let s = heightmap.scale();
+    let (min_y, max_y) = heightmap
+        .data()
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &y| {
+            (lo.min(y), hi.max(y))
+        });
+    let lo = Vec3::new(0.0, min_y, 0.0);
+    let hi = Vec3::new((w - 1) as f32 * s, max_y, (h - 1) as f32 * s);
+    let t_lo = (lo - origin) / dir;
+    let t_hi = (hi - origin) / dir;
+    let t_enter = t_lo.min(t_hi).max_element().max(0.0);
+    let t_exit = t_lo.max(t_hi).min_element();
+    if t_enter > t_exit {
+        return None;
+    }
+
+    // Walk the cells the ray crosses in XZ, nearest first (2D DDA).
+    let start = (origin + dir * t_enter) / s;
+    let mut cell = IVec2::new(
+        (start.x.floor() as i32).clamp(0, w as i32 - 2),
+        (start.z.floor() as i32).clamp(0, h as i32 - 2),
+    );
+    let step = IVec2::new(dir.x.signum() as i32, dir.z.signum() as i32);
+    let dir_xz = Vec2::new(dir.x, dir.z) / s;
+    let origin_xz = Vec2::new(origin.x, origin.z) / s;
+    let next_boundary = |c: i32, step: i32| (c + (step > 0) as i32) as f32;
+    let t_delta = dir_xz.recip().abs();
+    let mut t_max = Vec2::new(
+        (next_boundary(cell.x, step.x) - origin_xz.x) / dir_xz.x,
+        (next_boundary(cell.y, step.y) - origin_xz.y) / dir_xz.y,
+    );
+    // An axis the ray runs parallel to never crosses a boundary.
+    t_max = Vec2::select(dir_xz.cmpeq(Vec2::ZERO), Vec2::INFINITY, t_max);
+
+    let vertex = |x: usize, z: usize| Vec3::new(x as f32 * s, heightmap.get(x, z), z as f32 * s);
+    loop {
+        let (x, z) = (cell.x as usize, cell.y as usize);
+        let hit = QuadDiagonal::default()
+            .split(
+                vertex(x, z),
+                vertex(x + 1, z),
+                vertex(x, z + 1),
+                vertex(x + 1, z + 1),
+            )
+            .into_iter()
+            .filter_map(|tri| ray_triangle(origin, dir, tri))
+            .any(|t| t >= 0.0);
+        if hit {
+            return Some((x, z));
+        }
+
+        if t_max.x.min(t_max.y) > t_exit {
+            return None;
+        }
+        if t_max.x < t_max.y {
+            cell.x += step.x;
+            t_max.x += t_delta.x;
+        } else {
+            cell.y += step.y;
+            t_max.y += t_delta.y;
+        }
+        if !(0..w as i32 - 1).contains(&cell.x) || !(0..h as i32 - 1).contains(&cell.y) {
+            return None;
+        }
+    }
+}
+
+/// Möller–Trumbore intersection, returning the ray parameter of the hit.
+fn ray_triangle(origin: Vec3, dir: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let e1 = b - a;
+    let e2 = c - a;
+    let p = dir.cross(e2);
+    let det = e1.dot(p);
+    if det.abs() < f32::EPSILON {
+        return None;
+    }
+    let inv = det.recip();
+    let o = origin - a;
+    let u = o.dot(p) * inv;
+    let q = o.cross(e1);
+    let v = dir.dot(q) * inv;
+    if u < 0.0 || v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    Some(e2.dot(q) * inv)
+}
+
+/// Grid constants shared by the scalar and batched bilinear samplers.
+struct Sampler<'a> {
+    data: &'a [f32],
+    width: usize,
+    max_x: f32,
+    max_z: f32,
+    scale: f32,
+    inv_scale: f32,
+}
+
+impl<'a> Sampler<'a> {
+    fn new(heightmap: &'a HeightMap) -> Self {
+        Self {
+            data: heightmap.data(),
+            width: heightmap.width(),
+            max_x: (heightmap.width() - 1) as f32,
+            max_z: (heightmap.height() - 1) as f32,
+            scale: heightmap.scale(),
+            inv_scale: heightmap.scale().recip(),
+        }
+    }
+
+    fn height(&self, world_x: f32, world_z: f32) -> f32 {
+        let gx = (world_x * self.inv_scale).clamp(0.0, self.max_x);
+        let gz = (world_z * self.inv_scale).clamp(0.0, self.max_z);
+        let x0 = gx.floor();
+        let z0 = gz.floor();
+        let fx = gx - x0;
+        let fz = gz - z0;
+
+        let row0 = z0 as usize * self.width;
+        // On the far edge the "next" row/column clamps onto the current one.
+        let row1 = if gz < self.max_z {
+            row0 + self.width
+        } else {
+            row0
+        };
+        let x0 = x0 as usize;
+        let x1 = if gx < self.max_x { x0 + 1 } else { x0 };
+
+        let h0 = self.data[row0 + x0] + (self.data[row0 + x1] - self.data[row0 + x0]) * fx;
+        let h1 = self.data[row1 + x0] + (self.data[row1 + x1] - self.data[row1 + x0]) * fx;
+        h0 + (h1 - h0) * fz
+    }
+
+    fn normal(&self, world_x: f32, world_z: f32) -> Vec3 {
+        let step = self.scale;
+        let dhdx = (self.height(world_x + step, world_z) - self.height(world_x - step, world_z))
+            / (2.0 * step);
+        let dhdz = (self.height(world_x, world_z + step) - self.height(world_x, world_z - step))
+            / (2.0 * step);
+        Vec3::new(-dhdx, 1.0, -dhdz).normalize()
+    }
+}
@@ -0,0 +1,196 @@
+//! One-call terrain entity setup.
+//!
+//! Provides [`spawn_terrain`], which wires a [`HeightMap`] and [`WeightMap`]
+//! into a ready-to-render PBR entity: mesh, splat texture, material, and the
+//! transform that centers the terrain on the world origin. With the `physics`
+//! feature, a matching heightfield collider is attached as well.
+
+use bevy::camera::primitives::Aabb;
+use bevy::prelude::*;
+use symbios_ground::{HeightMap, WeightMap};
+
+use crate::mesher::HeightMapMeshBuilder;
+use crate::splat::splat_to_image;
+
+/// Returns the transform that centers a mesh built from `heightmap` on the origin.
+///
+/// The mesh spans `[0, (w-1)·scale] × [0, (h-1)·scale]` in XZ; this transform
+/// translates it by half that extent so the terrain's center sits at
+/// `(0, 0, 0)` — the same place the heightfield collider is centered.
+pub fn terrain_alignment(heightmap: &HeightMap) -> Transform {
+    let half_x = (heightmap.width() - 1) as f32 * heightmap.scale() * 0.5;
+    let half_z = (heightmap.height() - 1) as f32 * heightmap.scale() * 0.5;
+    Transform::from_xyz(-half_x, 0.0, -half_z)
+}
+
+/// Returns a [`StandardMaterial`] with sensible terrain defaults, using
+/// `splat` as the base color texture.
+///
+/// Ground is rough and non-metallic and reflects little light at grazing
+/// angles, so the material sets `perceptual_roughness = 0.9`,
+/// `metallic = 0.0`, and `reflectance = 0.2`. It stays lit and single-sided:
+/// terrain is only seen from above, and back-face culling halves the
+/// fragment work of steep slopes facing away from the camera. Override any
+/// field with struct update syntax.
+///
+/// ```ignore
+/// let material = materials.add(StandardMaterial {
+///     perceptual_roughness: 0.7,
+///     ..terrain_standard_material(splat_handle)
+/// });
+/// ```
+pub fn terrain_standard_material(splat: Handle<Image>) -> StandardMaterial {
+    StandardMaterial {
+        base_color_texture: Some(splat),
+        perceptual_roughness: 0.9,
+        metallic: 0.0,
+        reflectance: 0.2,
+        unlit: false,
+        double_sided: false,
+        ..default()
+    }
+}
+
+/// Returns a transform that fits a mesh built from `heightmap` inside `target`.
+///
+/// The terrain's local bounds are its mesh footprint
+/// `[0, (w-1)·scale] × [0, (h-1)·scale]` and its min/max height. With
+/// `stretch = false` a single uniform scale is used — the largest that keeps
+/// every axis inside the box — so the terrain keeps its proportions and
+/// touches the box on its limiting axis; with `stretch = true` each axis is
+/// scaled independently so the bounds fill the box exactly. Either way the
+/// terrain is centered in `target`.
+///
+/// Axes with zero extent (e.g. the height of a perfectly flat map) do not
+/// constrain the uniform scale and keep scale `1.0` when stretching.
+///
+/// # Example
+///
+/// ```ignore
+/// use bevy::camera::primitives::Aabb;
+///
+/// let play_area = Aabb::from_min_max(Vec3::new(-500.0, 0.0, -500.0), Vec3::new(500.0, 80.0, 500.0));
+/// commands.spawn((Mesh3d(mesh), fit_to_box(&heightmap, play_area, false)));
+/// ```
+pub fn fit_to_box(heightmap: &HeightMap, target: Aabb, stretch: bool) -> Transform {
+    let bounds = world_bounds(heightmap);
+    let local_min = Vec3::from(bounds.min());
+    let local_max = Vec3::from(bounds.max());
+    let extent = local_max - local_min;
+    let target_extent = Vec3::from(target.half_extents) * 2.0;
+
+    let ratio = |axis: usize| (extent[axis] > 0.0).then(|| target_extent[axis] / extent[axis]);
+    let scale = if stretch {
+        Vec3::from_array(std::array::from_fn(|axis| ratio(axis).unwrap_or(1.0)))
+    } else {
+        let uniform = (0..3).filter_map(ratio).reduce(f32::min).unwrap_or(1.0);
+        Vec3::splat(uniform)
+    };
+
+    let local_center = (local_min + local_max) * 0.5;
+    Transform {
+        translation: Vec3::from(target.center) - scale * local_center,
+        scale,
+        ..default()
+    }
+}
+
+/// World-space XZ size of a mesh built from `heightmap`:
+/// `((w-1)·scale, (h-1)·scale)`.
+pub fn world_extent(heightmap: &HeightMap) -> Vec2 {
+    Vec2::new(
+        heightmap.width().saturating_sub(1) as f32,
+        heightmap.height().saturating_sub(1) as f32,
+    ) * heightmap.scale()
+}
+
+/// Local-space bounds of the mesh [`HeightMapMeshBuilder::build`] produces
+/// from `heightmap`, without building it.
+///
+/// Spans `[0, (w-1)·scale] × [min height, max height] × [0, (h-1)·scale]`,
+/// from a single pass over the heights (non-finite heights are skipped).
+/// Useful for reserving space or framing a camera before meshing; apply the
+/// entity's transform, e.g. [`terrain_alignment`], for world placement.
+pub fn world_bounds(heightmap: &HeightMap) -> Aabb {
+    let (lo, hi) = heightmap
+        .data()
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &h| {
+            (lo.min(h), hi.max(h))
+        });
+    let (lo, hi) = if lo <= hi { (lo, hi) } else { (0.0, 0.0) };
+    let extent = world_extent(heightmap);
+    Aabb::from_min_max(Vec3::new(0.0, lo, 0.0), Vec3::new(extent.x, hi, extent.y))
+}
+
+/// Builds and spawns a complete terrain entity in one call.
+///
+/// This:
+/// 1. builds the mesh from `heightmap` with `builder`,
+/// 2. converts `weight_map` into a splat [`Image`] via [`splat_to_image`],
+/// 3. creates a [`StandardMaterial`] from [`terrain_standard_material`],
+/// 4. spawns an entity with [`Mesh3d`], [`MeshMaterial3d`], and the
+///    [`terrain_alignment`] transform, centering the terrain on the origin.
+///
+/// With the `physics` feature, a child entity carrying the heightfield
+/// collider is added, offset so that it lines up with the rendered surface.
+///
+/// Returns the terrain entity. Insert extra components on it as needed.
+///
+/// # Panics
+///
+/// Panics if the heightmap is smaller than 2×2.
+///
+/// # Example
+///
+/// ```ignore
+/// fn setup(
+///     mut commands: Commands,
+///     mut meshes: ResMut<Assets<Mesh>>,
+///     mut materials: ResMut<Assets<StandardMaterial>>,
+///     mut images: ResMut<Assets<Image>>,
+/// ) {
+///     let heightmap = HeightMap::new(128, 128, 1.0);
+///     let weight_map = SplatMapper::default().generate(&heightmap);
+///     spawn_terrain(
+///         &mut commands,
+///         &mut meshes,
+///         &mut materials,
+///         &mut images,
+///         &heightmap,
+///         &weight_map,
+///         &HeightMapMeshBuilder::new(),
+///     );
+/// }
+/// ```
+pub fn spawn_terrain(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    images: &mut Assets<Image>,
+    heightmap: &HeightMap,
+    weight_map: &WeightMap,
+    builder: &HeightMapMeshBuilder,
+) -> Entity {
+    let mesh = meshes.add(builder.build(heightmap));
+    let splat = images.add(splat_to_image(weight_map));
+    let material = materials.add(terrain_standard_material(splat));
+    let transform = terrain_alignment(heightmap);
+
+    let entity = commands
+        .spawn((Mesh3d(mesh), MeshMaterial3d(material), transform))
+        .id();
+
+    #[cfg(feature = "physics")]
+    {
+        // The heightfield is centered on its local origin, so undo the
+        // alignment offset for the collider child.
+        let collider = crate::collider::build_heightfield_collider(heightmap);
+        commands.entity(entity).with_child((
+            collider,
+            Transform::from_translation(-transform.translation),
+        ));
+    }
+
+    entity
+}
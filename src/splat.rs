@@ -9,6 +9,8 @@ use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use symbios_ground::WeightMap;
 
+use crate::mesher::chunk_layout;
+
 /// Converts a [`WeightMap`] into a tiling Bevy [`Image`] (RGBA8Unorm).
 ///
 /// Each pixel maps directly: R = layer 0 weight, G = layer 1, B = layer 2, A = layer 3.
@@ -32,10 +34,307 @@ pub fn splat_to_image(weight_map: &WeightMap) -> Image {
         .flat_map(|pixel| pixel.iter().copied())
         .collect();
 
+    rgba8_image(weight_map.width as u32, weight_map.height as u32, raw)
+}
+
+/// Memory order of the pixels in a [`WeightMap`]'s `data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightMapLayout {
+    /// `data[z * width + x]`, as produced by `symbios-ground` (default).
+    #[default]
+    RowMajor,
+    /// `data[x * height + z]`, as written by some external tools.
+    ColumnMajor,
+}
+
+/// Like [`splat_to_image`], reading `weight_map.data` in the given `layout`.
+///
+/// Use [`WeightMapLayout::ColumnMajor`] for weight maps imported from tools
+/// that store pixels column by column; the image comes out in the usual
+/// row-major orientation instead of transposed. `width` and `height` keep
+/// their meaning (pixels along X and Z) in both layouts.
+pub fn splat_to_image_with_layout(weight_map: &WeightMap, layout: WeightMapLayout) -> Image {
+    splat_to_image_with_options(
+        weight_map,
+        &SplatImageOptions {
+            layout,
+            ..default()
+        },
+    )
+}
+
+/// How the shader reads the four channels of a splat texel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightInterpretation {
+    /// Channels are four independent layer weights, uploaded verbatim
+    /// (default).
+    #[default]
+    Straight,
+    /// Alpha is an overall opacity that multiplies the RGB layer weights,
+    /// which are premultiplied by it at conversion: `rgb · a / 255`, rounded.
+    /// Alpha itself is kept.
+    PremultipliedAlpha,
+}
+
+/// Settings for [`splat_to_image_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplatImageOptions {
+    /// Memory order of the weight map's pixels. Defaults to
+    /// [`WeightMapLayout::RowMajor`].
+    pub layout: WeightMapLayout,
+    /// Sampler address mode applied to both U and V.
+    ///
+    /// Defaults to `ClampToEdge`, so the splatmap does not wrap at terrain
+    /// borders. `MirrorRepeat` tiles a weight map used as a detail texture
+    /// without the hard seam plain `Repeat` shows where it wraps.
+    pub address_mode: ImageAddressMode,
+    /// How channel values are prepared for the shader. Defaults to
+    /// [`WeightInterpretation::Straight`].
+    pub interpretation: WeightInterpretation,
+}
+
+impl Default for SplatImageOptions {
+    fn default() -> Self {
+        Self {
+            layout: WeightMapLayout::default(),
+            address_mode: ImageAddressMode::ClampToEdge,
+            interpretation: WeightInterpretation::default(),
+        }
+    }
+}
+
+/// Like [`splat_to_image`], with the pixel layout, sampler addressing, and
+/// weight interpretation taken from `options`.
+pub fn splat_to_image_with_options(weight_map: &WeightMap, options: &SplatImageOptions) -> Image {
+    let (w, h) = (weight_map.width, weight_map.height);
+    let mut raw: Vec<u8> = match options.layout {
+        WeightMapLayout::RowMajor => weight_map.data.iter().flatten().copied().collect(),
+        WeightMapLayout::ColumnMajor => (0..h)
+            .flat_map(|z| (0..w).map(move |x| x * h + z))
+            .flat_map(|i| weight_map.data[i])
+            .collect(),
+    };
+    if options.interpretation == WeightInterpretation::PremultipliedAlpha {
+        for texel in raw.chunks_exact_mut(4) {
+            let alpha = texel[3] as u32;
+            for channel in &mut texel[..3] {
+                *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+            }
+        }
+    }
+    let mut image = rgba8_image(w as u32, h as u32, raw);
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: options.address_mode,
+        address_mode_v: options.address_mode,
+        ..default()
+    });
+    image
+}
+
+/// Packs up to four scalar layer fields into an RGBA8 splat [`Image`].
+///
+/// `layers[i]` is row-major (`[z * width + x]`) and becomes channel `i`;
+/// missing channels are zero. Each texel's weights are clamped to be
+/// non-negative (NaN counts as zero), normalized to sum to one, and quantized
+/// to `u8`. A texel whose total weight is zero or infinite puts it all in
+/// layer 0, like a fresh [`WeightMap8`]. The image has the same format and sampler as
+/// [`splat_to_image`].
+///
+/// # Panics
+///
+/// Panics if `layers` holds more than four fields or a field does not have
+/// `width · height` values.
+pub fn pack_layers_to_image(layers: &[&[f32]], width: usize, height: usize) -> Image {
+    assert!(
+        layers.len() <= 4,
+        "an RGBA8 splat holds at most 4 layers (got {})",
+        layers.len()
+    );
+    for (i, layer) in layers.iter().enumerate() {
+        assert_eq!(
+            layer.len(),
+            width * height,
+            "layer {i} must have one value per {width}×{height} texel"
+        );
+    }
+
+    let mut raw = Vec::with_capacity(width * height * 4);
+    for i in 0..width * height {
+        let mut weights = [0.0f32; 4];
+        for (weight, layer) in weights.iter_mut().zip(layers) {
+            *weight = layer[i].max(0.0);
+        }
+        let sum: f32 = weights.iter().sum();
+        if sum > 0.0 && sum.is_finite() {
+            raw.extend(weights.map(|w| (w / sum * 255.0).round() as u8));
+        } else {
+            raw.extend([255, 0, 0, 0]);
+        }
+    }
+    rgba8_image(width as u32, height as u32, raw)
+}
+
+/// An 8-layer weight map, for terrains with more materials than one RGBA8
+/// texture can carry.
+///
+/// Laid out like [`WeightMap`], with eight `u8` weights per pixel. Split it
+/// into two textures with [`splat_to_images_split`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightMap8 {
+    /// Row-major weights; `data[z * width + x]` holds layers 0–7.
+    pub data: Vec<[u8; 8]>,
+    /// Pixels along X.
+    pub width: usize,
+    /// Pixels along Z.
+    pub height: usize,
+}
+
+impl WeightMap8 {
+    /// Creates a weight map with all weight in layer 0.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            data: vec![[255, 0, 0, 0, 0, 0, 0, 0]; width * height],
+            width,
+            height,
+        }
+    }
+}
+
+/// Splits an 8-layer weight map into a primary and a secondary splat image.
+///
+/// Layers 0–3 go to the RGBA channels of the first image and layers 4–7 to
+/// the second, so a shader samples both at the same UV and blends eight
+/// materials. Both images have the weight map's dimensions and the same
+/// format and sampler as [`splat_to_image`].
+pub fn splat_to_images_split(weight_map: &WeightMap8) -> (Image, Image) {
+    let (w, h) = (weight_map.width as u32, weight_map.height as u32);
+    let half = |range: std::ops::Range<usize>| -> Vec<u8> {
+        weight_map
+            .data
+            .iter()
+            .flat_map(|pixel| pixel[range.clone()].iter().copied())
+            .collect()
+    };
+    (rgba8_image(w, h, half(0..4)), rgba8_image(w, h, half(4..8)))
+}
+
+/// Error returned by [`splat_write_into`] when the target image does not fit
+/// the weight map.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplatError {
+    /// The image's width/height differ from the weight map's.
+    SizeMismatch {
+        /// Weight-map dimensions `(width, height)`.
+        expected: UVec2,
+        /// Image dimensions `(width, height)`.
+        actual: UVec2,
+    },
+    /// The image is not an `Rgba8Unorm` texture.
+    FormatMismatch(TextureFormat),
+}
+
+impl std::fmt::Display for SplatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SizeMismatch { expected, actual } => write!(
+                f,
+                "splat image is {}×{}, but the weight map is {}×{}",
+                actual.x, actual.y, expected.x, expected.y
+            ),
+            Self::FormatMismatch(format) => {
+                write!(f, "splat image format is {format:?}, expected Rgba8Unorm")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SplatError {}
+
+/// Overwrites an existing splat [`Image`] with `weight_map`, in place.
+///
+/// Use this instead of [`splat_to_image`] when cycling splat states, to reuse
+/// the same asset (and handle) rather than allocating a new image. The image
+/// must have the weight map's dimensions and the `Rgba8Unorm` format, as
+/// produced by [`splat_to_image`]; its sampler and other settings are kept.
+///
+/// # Errors
+///
+/// Returns [`SplatError`] if the size or format does not match. The image is
+/// unchanged on error.
+pub fn splat_write_into(weight_map: &WeightMap, image: &mut Image) -> Result<(), SplatError> {
+    let size = image.texture_descriptor.size;
+    let expected = UVec2::new(weight_map.width as u32, weight_map.height as u32);
+    let actual = UVec2::new(size.width, size.height);
+    if expected != actual || size.depth_or_array_layers != 1 {
+        return Err(SplatError::SizeMismatch { expected, actual });
+    }
+    let format = image.texture_descriptor.format;
+    if format != TextureFormat::Rgba8Unorm {
+        return Err(SplatError::FormatMismatch(format));
+    }
+
+    let data = image.data.get_or_insert_with(Vec::new);
+    data.clear();
+    data.extend(weight_map.data.iter().flatten());
+    Ok(())
+}
+
+/// Splits a [`WeightMap`] into per-chunk RGBA8 images matching
+/// [`HeightMapMeshBuilder::build_chunked`].
+///
+/// The weight map is expected to have one pixel per heightmap vertex (as
+/// produced by `SplatMapper`). For each chunk, the returned image covers the
+/// chunk's vertices plus a one-pixel border on every side, so bilinear
+/// filtering at the chunk edge blends with the neighbouring chunk's texels
+/// instead of clamping. Border pixels that fall outside the map repeat the
+/// nearest edge pixel. A chunk spanning `n × m` vertices therefore yields an
+/// `(n + 2) × (m + 2)` image, where vertex `(i, j)` of the chunk lives at
+/// texel `(i + 1, j + 1)`; map a chunk-local UV `t ∈ [0, 1]` to
+/// `(1.5 + t·(n − 1)) / (n + 2)` to sample texel centers.
+///
+/// Chunk coordinates and order match `build_chunked` for the same
+/// `chunk_size`. Images use clamp-to-edge sampling like [`splat_to_image`].
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero or the weight map is smaller than 2×2.
+///
+/// [`HeightMapMeshBuilder::build_chunked`]: crate::HeightMapMeshBuilder::build_chunked
+pub fn splat_chunked(weight_map: &WeightMap, chunk_size: usize) -> Vec<(IVec2, Image)> {
+    assert!(
+        weight_map.width >= 2 && weight_map.height >= 2,
+        "WeightMap must be at least 2×2 to chunk (got {}×{})",
+        weight_map.width,
+        weight_map.height
+    );
+
+    let pixel = |x: isize, z: isize| -> [u8; 4] {
+        let cx = x.clamp(0, weight_map.width as isize - 1) as usize;
+        let cz = z.clamp(0, weight_map.height as isize - 1) as usize;
+        weight_map.data[cz * weight_map.width + cx]
+    };
+
+    chunk_layout(weight_map.width, weight_map.height, chunk_size)
+        .into_iter()
+        .map(|(coord, rect)| {
+            let x_range = rect.x0 as isize - 1..=rect.x1 as isize + 1;
+            let z_range = rect.z0 as isize - 1..=rect.z1 as isize + 1;
+            let raw: Vec<u8> = z_range
+                .flat_map(|z| x_range.clone().map(move |x| (x, z)))
+                .flat_map(|(x, z)| pixel(x, z))
+                .collect();
+            let image = rgba8_image(rect.width() as u32 + 2, rect.height() as u32 + 2, raw);
+            (coord, image)
+        })
+        .collect()
+}
+
+/// Wraps raw RGBA8 bytes in a clamp-to-edge [`Image`].
+fn rgba8_image(width: u32, height: u32, raw: Vec<u8>) -> Image {
     let mut image = Image::new(
         Extent3d {
-            width: weight_map.width as u32,
-            height: weight_map.height as u32,
+            width,
+            height,
             depth_or_array_layers: 1,
         },
         TextureDimension::D2,
@@ -54,6 +353,60 @@ pub fn splat_to_image(weight_map: &WeightMap) -> Image {
     image
 }
 
+/// Bilinearly samples the four splat weights at world point `(world_x, world_z)`.
+///
+/// `scale` is the world distance between neighbouring weight-map pixels. Pixel
+/// `(x, z)` sits at world `(x·scale, z·scale)` — the same place as heightmap
+/// vertex `(x, z)` when the weight map was generated from a heightmap of
+/// equal size with that grid scale. Points outside the map are clamped to its
+/// border.
+///
+/// Returns the R, G, B, A weights normalized to `[0, 1]`.
+///
+/// # Example
+///
+/// ```ignore
+/// let weights = sample_weights_bilinear(&weight_map, pos.x, pos.z, heightmap.scale());
+/// let footstep_volume = 0.5 + 0.5 * weights[2]; // louder on rock
+/// ```
+pub fn sample_weights_bilinear(
+    weight_map: &WeightMap,
+    world_x: f32,
+    world_z: f32,
+    scale: f32,
+) -> [f32; 4] {
+    let max_x = (weight_map.width - 1) as f32;
+    let max_z = (weight_map.height - 1) as f32;
+    let gx = (world_x / scale).clamp(0.0, max_x);
+    let gz = (world_z / scale).clamp(0.0, max_z);
+    let x0 = gx.floor() as usize;
+    let z0 = gz.floor() as usize;
+    let x1 = (x0 + 1).min(weight_map.width - 1);
+    let z1 = (z0 + 1).min(weight_map.height - 1);
+    let fx = gx - x0 as f32;
+    let fz = gz - z0 as f32;
+
+    let pixel = |x: usize, z: usize| weight_map.data[z * weight_map.width + x];
+    let (p00, p10, p01, p11) = (pixel(x0, z0), pixel(x1, z0), pixel(x0, z1), pixel(x1, z1));
+    std::array::from_fn(|c| {
+        let top = p00[c] as f32 + (p10[c] as f32 - p00[c] as f32) * fx;
+        let bottom = p01[c] as f32 + (p11[c] as f32 - p01[c] as f32) * fx;
+        (top + (bottom - top) * fz) / 255.0
+    })
+}
+
+/// Returns the index (0 = R … 3 = A) of the strongest splat layer at a world point.
+///
+/// Uses [`sample_weights_bilinear`]; ties resolve to the lower index. Handy for
+/// gameplay keyed on ground type, such as footstep sounds or traction.
+pub fn dominant_layer(weight_map: &WeightMap, world_x: f32, world_z: f32, scale: f32) -> usize {
+    let weights = sample_weights_bilinear(weight_map, world_x, world_z, scale);
+    (0..4).fold(
+        0,
+        |best, c| if weights[c] > weights[best] { c } else { best },
+    )
+}
+
 /// Resource holding the current [`WeightMap`] and whether it has changed.
 ///
 /// Mutate `weight_map` and call [`mark_dirty`] to trigger the next
@@ -65,6 +418,16 @@ pub struct GroundMaterialSettings {
     /// The current weight map data. Replace or modify to update terrain appearance.
     pub weight_map: WeightMap,
     dirty: bool,
+    rows_per_frame: Option<usize>,
+    /// Next row to upload while a streamed upload is in progress.
+    next_row: Option<usize>,
+    crossfade: Option<f32>,
+    /// Whether a full upload has completed, so later ones have an old
+    /// appearance to fade from.
+    uploaded: bool,
+    /// Snapshot of the last complete texture, held until the upload in
+    /// progress finishes and the fade from it can start.
+    fade_from: Option<Handle<Image>>,
 }
 
 impl GroundMaterialSettings {
@@ -74,13 +437,54 @@ impl GroundMaterialSettings {
         Self {
             weight_map,
             dirty: true,
+            rows_per_frame: None,
+            next_row: None,
+            crossfade: None,
+            uploaded: false,
+            fade_from: None,
         }
     }
 
+    /// Streams uploads over several frames, `rows` texture rows per
+    /// [`sync_splat_texture`] run.
+    ///
+    /// Converting a huge weight map (e.g. 4096²) in one run causes a frame
+    /// spike; with streaming, each run writes at most `rows` rows into the
+    /// image until the whole map is flushed. Marking the map dirty again
+    /// mid-upload restarts from the first row. This bounds the per-frame CPU
+    /// work; note that Bevy still re-extracts the image to the GPU whenever it
+    /// changes. `rows` is clamped to at least 1.
+    pub fn with_rows_per_frame(mut self, rows: usize) -> Self {
+        self.rows_per_frame = Some(rows.max(1));
+        self
+    }
+
+    /// Cross-fades from the previous appearance over `seconds` whenever the
+    /// weight map is regenerated.
+    ///
+    /// Each re-upload after the first makes [`sync_splat_texture`] snapshot
+    /// the current texture into a new image when the upload starts, and
+    /// insert a [`SplatCrossfade`] from that snapshot to
+    /// [`SplatTexture::handle`] once it finishes — with
+    /// [`with_rows_per_frame`](Self::with_rows_per_frame), after the last
+    /// row, so the fade never shows a half-written texture. Marking the map
+    /// dirty mid-upload keeps the first snapshot. Add
+    /// [`update_splat_crossfade`] to advance the fade. Clamped to
+    /// non-negative.
+    pub fn with_crossfade(mut self, seconds: f32) -> Self {
+        self.crossfade = Some(seconds.max(0.0));
+        self
+    }
+
     /// Marks the weight map as changed so [`sync_splat_texture`] re-uploads it.
     pub fn mark_dirty(&mut self) {
         self.dirty = true;
     }
+
+    /// Returns `true` while a streamed upload still has rows left to write.
+    pub fn is_uploading(&self) -> bool {
+        self.next_row.is_some()
+    }
 }
 
 /// Resource holding the GPU-side splat texture handle.
@@ -102,43 +506,187 @@ pub struct SplatTexture {
     pub handle: Handle<Image>,
 }
 
+/// Optional resource scaling each splat channel when the texture is uploaded.
+///
+/// [`sync_splat_texture`] multiplies every pixel's R, G, B, A weights by the
+/// matching entry of `per_channel_scale` (clamped to the `u8` range) before
+/// writing the GPU texture. The authoritative [`GroundMaterialSettings::weight_map`]
+/// is never modified, so a tint can be animated over time (e.g. scorch or
+/// damage effects) and removed without losing the source data.
+///
+/// Changing this resource triggers a re-upload on the next sync.
+///
+/// # Example
+///
+/// ```ignore
+/// // Fade layer 3 to half strength.
+/// commands.insert_resource(SplatTint { per_channel_scale: [1.0, 1.0, 1.0, 0.5] });
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct SplatTint {
+    /// Multiplier applied to the R, G, B, and A channel respectively.
+    pub per_channel_scale: [f32; 4],
+}
+
+impl Default for SplatTint {
+    fn default() -> Self {
+        Self {
+            per_channel_scale: [1.0; 4],
+        }
+    }
+}
+
+impl SplatTint {
+    /// Applies the tint to one RGBA8 pixel.
+    fn apply(&self, pixel: [u8; 4]) -> [u8; 4] {
+        std::array::from_fn(|c| {
+            (pixel[c] as f32 * self.per_channel_scale[c])
+                .round()
+                .clamp(0.0, 255.0) as u8
+        })
+    }
+}
+
+/// Resource describing an in-progress fade between two splat textures.
+///
+/// Bind both textures to the terrain material and blend them in the shader
+/// as `mix(old, new, blend)`, reading [`blend`](Self::blend) each frame —
+/// e.g. by copying it into a material uniform. Inserted automatically by
+/// [`sync_splat_texture`] when
+/// [`GroundMaterialSettings::with_crossfade`] is set, or by hand with
+/// [`SplatCrossfade::new`]; [`update_splat_crossfade`] advances it.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct SplatCrossfade {
+    /// Texture faded out.
+    pub old: Handle<Image>,
+    /// Texture faded in.
+    pub new: Handle<Image>,
+    /// Length of the fade in seconds.
+    pub duration: f32,
+    elapsed: f32,
+}
+
+impl SplatCrossfade {
+    /// Starts a fade from `old` to `new` lasting `duration` seconds.
+    pub fn new(old: Handle<Image>, new: Handle<Image>, duration: f32) -> Self {
+        Self {
+            old,
+            new,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Weight of the new texture, rising from `0.0` to `1.0` over the fade.
+    /// A non-positive duration completes immediately.
+    pub fn blend(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Returns `true` once the new texture is fully faded in.
+    pub fn is_complete(&self) -> bool {
+        self.blend() >= 1.0
+    }
+}
+
+/// Bevy system that advances [`SplatCrossfade`] by the frame's delta time.
+///
+/// Add to your `Update` schedule; it does nothing without the resource or
+/// once the fade is complete. The resource is left in place at `blend = 1.0`
+/// so the shader keeps showing the new texture.
+pub fn update_splat_crossfade(time: Res<Time>, fade: Option<ResMut<SplatCrossfade>>) {
+    let Some(mut fade) = fade else {
+        return;
+    };
+    if !fade.is_complete() {
+        fade.elapsed += time.delta_secs();
+    }
+}
+
 /// Bevy system that re-uploads the splat texture when [`GroundMaterialSettings`]
 /// is marked dirty.
 ///
 /// Add to your `Update` schedule. Only re-uploads when data has changed,
-/// so it is safe to run every frame.
+/// so it is safe to run every frame. If a [`SplatTint`] resource exists, it is
+/// applied to the uploaded bytes, and changing it also triggers a re-upload.
+/// With [`GroundMaterialSettings::with_rows_per_frame`], the upload is spread
+/// over several runs. With [`GroundMaterialSettings::with_crossfade`], every
+/// re-upload after the first starts a [`SplatCrossfade`] from a snapshot of
+/// the previous texture once its last row is written.
 pub fn sync_splat_texture(
+    mut commands: Commands,
     mut settings: ResMut<GroundMaterialSettings>,
     splat_texture: Res<SplatTexture>,
     mut images: ResMut<Assets<Image>>,
+    tint: Option<Res<SplatTint>>,
 ) {
-    if !settings.dirty {
+    let tint_changed = tint.as_ref().is_some_and(|t| t.is_changed());
+    if !settings.dirty && !tint_changed && settings.next_row.is_none() {
         return;
     }
 
+    if settings.dirty
+        && settings.uploaded
+        && settings.crossfade.is_some()
+        && settings.fade_from.is_none()
+        && let Some(snapshot) = images.get(&splat_texture.handle).cloned()
+    {
+        settings.fade_from = Some(images.add(snapshot));
+    }
+
     let Some(image) = images.get_mut(&splat_texture.handle) else {
         // Image not yet available; keep dirty=true so we retry next frame.
         return;
     };
-    settings.dirty = false;
+    if settings.dirty || tint_changed {
+        // (Re)start from the first row.
+        settings.dirty = false;
+        settings.next_row = Some(0);
+    }
 
+    let settings = &mut *settings;
     let weight_map = &settings.weight_map;
 
     // Resize texture data in-place if dimensions changed
     let expected_bytes = weight_map.width * weight_map.height * 4;
-    if image.data.as_ref().map(|d| d.len()).unwrap_or(0) != expected_bytes {
+    let data = image.data.get_or_insert_with(Vec::new);
+    if data.len() != expected_bytes {
         image.texture_descriptor.size = Extent3d {
             width: weight_map.width as u32,
             height: weight_map.height as u32,
             depth_or_array_layers: 1,
         };
+        data.resize(expected_bytes, 0);
     }
 
-    let raw: Vec<u8> = weight_map
-        .data
-        .iter()
-        .flat_map(|pixel| pixel.iter().copied())
-        .collect();
+    let start = settings.next_row.unwrap_or(0);
+    let end = match settings.rows_per_frame {
+        Some(rows) => (start + rows).min(weight_map.height),
+        None => weight_map.height,
+    };
+    let pixels = start * weight_map.width..end * weight_map.width;
+    let bytes = &mut data[pixels.start * 4..pixels.end * 4];
+    for (dst, src) in bytes.chunks_exact_mut(4).zip(&weight_map.data[pixels]) {
+        let pixel = match tint.as_deref() {
+            Some(tint) => tint.apply(*src),
+            None => *src,
+        };
+        dst.copy_from_slice(&pixel);
+    }
 
-    image.data = Some(raw);
+    settings.next_row = (end < weight_map.height).then_some(end);
+    if settings.next_row.is_none() {
+        settings.uploaded = true;
+        if let (Some(old), Some(duration)) = (settings.fade_from.take(), settings.crossfade) {
+            commands.insert_resource(SplatCrossfade::new(
+                old,
+                splat_texture.handle.clone(),
+                duration,
+            ));
+        }
+    }
 }
@@ -0,0 +1,312 @@
+//! Tiled, compressed on-disk heightmaps for worlds too large to hold in memory.
+//!
+//! [`write_tiled_heightmap`] splits a [`HeightMap`] into fixed-size tiles and
+//! stores each one deflate-compressed behind an offset table.
+//! [`TiledHeightMap`] reads only that table up front and decodes a tile when
+//! [`TiledHeightMap::tile`] asks for it, so a 16k×16k world can be streamed
+//! tile by tile into [`HeightMapMeshBuilder::build_chunked`](crate::HeightMapMeshBuilder::build_chunked).
+//!
+//! Requires the `tiled` feature.
+//!
+//! # Format
+//!
+//! All values are little-endian.
+//!
+//! | Field | Type |
+//! |-------|------|
+//! | magic `b"SYGT"` | 4 bytes |
+//! | version (`1`) | `u32` |
+//! | width, height, tile size | `u32` × 3 |
+//! | grid scale | `f32` |
+//! | per tile, row-major: byte offset, byte length | (`u64`, `u32`) × tiles |
+//! | per tile: deflated row-major `f32` heights | bytes |
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use bevy::prelude::*;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use symbios_ground::HeightMap;
+
+use crate::mesher::chunk_layout;
+
+const MAGIC: &[u8; 4] = b"SYGT";
+const VERSION: u32 = 1;
+/// Magic, version, width, height, tile size, scale.
+const HEADER_LEN: u64 = 4 + 4 * 5;
+/// Offset and length of one tile.
+const INDEX_ENTRY_LEN: u64 = 8 + 4;
+/// Index entries reserved up front before the header's tile count is borne
+/// out by the data.
+const MAX_INDEX_PREALLOC: usize = 4096;
+
+/// Error returned when reading a tiled heightmap.
+#[derive(Debug)]
+pub enum TiledHeightMapError {
+    /// The underlying reader failed.
+    Io(io::Error),
+    /// The data is not a tiled heightmap this crate can read.
+    InvalidFormat(&'static str),
+    /// The requested tile coordinate is outside the tile grid.
+    TileOutOfRange(IVec2),
+}
+
+impl std::fmt::Display for TiledHeightMapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read tiled heightmap: {err}"),
+            Self::InvalidFormat(reason) => write!(f, "invalid tiled heightmap: {reason}"),
+            Self::TileOutOfRange(coord) => {
+                write!(
+                    f,
+                    "tile ({}, {}) is outside the tile grid",
+                    coord.x, coord.y
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TiledHeightMapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TiledHeightMapError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Writes `heightmap` in the tiled format, `tile_size` cells per tile side.
+///
+/// Tiles follow the layout of
+/// [`HeightMapMeshBuilder::build_chunked`](crate::HeightMapMeshBuilder::build_chunked):
+/// tile `(tx, tz)` holds vertices
+/// `[tx·tile_size, (tx+1)·tile_size] × [tz·tile_size, (tz+1)·tile_size]`
+/// clamped to the map, so neighbouring tiles share their edge vertices and
+/// the last row/column of tiles may be smaller. Heights are stored losslessly.
+///
+/// # Errors
+///
+/// Returns the writer's error, or [`io::ErrorKind::InvalidInput`] if a
+/// dimension, the tile size, or a compressed tile does not fit the format's
+/// `u32` fields.
+///
+/// # Panics
+///
+/// Panics if `tile_size` is zero.
+pub fn write_tiled_heightmap(
+    heightmap: &HeightMap,
+    tile_size: usize,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let tiles = chunk_layout(heightmap.width(), heightmap.height(), tile_size);
+
+    let mut payloads = Vec::with_capacity(tiles.len());
+    for (_, rect) in &tiles {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        for z in rect.z0..=rect.z1 {
+            for x in rect.x0..=rect.x1 {
+                encoder.write_all(&heightmap.get(x, z).to_le_bytes())?;
+            }
+        }
+        payloads.push(encoder.finish()?);
+    }
+
+    writer.write_all(MAGIC)?;
+    for value in [
+        VERSION,
+        to_u32(heightmap.width(), "width")?,
+        to_u32(heightmap.height(), "height")?,
+        to_u32(tile_size, "tile size")?,
+    ] {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.write_all(&heightmap.scale().to_le_bytes())?;
+
+    let mut offset = HEADER_LEN + INDEX_ENTRY_LEN * tiles.len() as u64;
+    for payload in &payloads {
+        writer.write_all(&offset.to_le_bytes())?;
+        writer.write_all(&to_u32(payload.len(), "tile payload")?.to_le_bytes())?;
+        offset += payload.len() as u64;
+    }
+    for payload in &payloads {
+        writer.write_all(payload)?;
+    }
+    Ok(())
+}
+
+/// Lazily loaded reader for the tiled format written by
+/// [`write_tiled_heightmap`].
+///
+/// Opening reads only the header and offset table; each call to
+/// [`tile`](Self::tile) seeks to one tile and decodes it. Tiles are not
+/// cached, so memory use is bounded by the tiles the caller keeps alive.
+///
+/// # Example
+///
+/// ```ignore
+/// use std::fs::File;
+/// use bevy_symbios_ground::{HeightMapMeshBuilder, TiledHeightMap};
+///
+/// let mut world = TiledHeightMap::from_reader(File::open("world.sygt")?)?;
+/// let tile = world.tile(3, 7)?;
+/// let chunks = HeightMapMeshBuilder::new().build_chunked(&tile, 64);
+/// // Spawn the chunks under a parent at `world.tile_origin(3, 7)`.
+/// ```
+#[derive(Debug)]
+pub struct TiledHeightMap<R> {
+    reader: R,
+    width: usize,
+    height: usize,
+    tile_size: usize,
+    scale: f32,
+    tiles: UVec2,
+    index: Vec<(u64, u32)>,
+    tiles_loaded: usize,
+}
+
+impl<R: Read + Seek> TiledHeightMap<R> {
+    /// Reads the header and tile index from `reader`.
+    pub fn from_reader(mut reader: R) -> Result<Self, TiledHeightMapError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(TiledHeightMapError::InvalidFormat("bad magic"));
+        }
+        if read_u32(&mut reader)? != VERSION {
+            return Err(TiledHeightMapError::InvalidFormat("unsupported version"));
+        }
+        let width = read_u32(&mut reader)? as usize;
+        let height = read_u32(&mut reader)? as usize;
+        let tile_size = read_u32(&mut reader)? as usize;
+        let mut scale = [0; 4];
+        reader.read_exact(&mut scale)?;
+        let scale = f32::from_le_bytes(scale);
+        if width < 2 || height < 2 || tile_size == 0 {
+            return Err(TiledHeightMapError::InvalidFormat("degenerate dimensions"));
+        }
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(TiledHeightMapError::InvalidFormat(
+                "non-positive grid scale",
+            ));
+        }
+
+        let tiles = UVec2::new(
+            (width - 1).div_ceil(tile_size) as u32,
+            (height - 1).div_ceil(tile_size) as u32,
+        );
+        let tile_count = u64::from(tiles.x)
+            .checked_mul(u64::from(tiles.y))
+            .and_then(|count| usize::try_from(count).ok())
+            .ok_or(TiledHeightMapError::InvalidFormat("too many tiles"))?;
+        // The count comes from the file, so grow the index as entries are
+        // actually read rather than trusting it for one allocation.
+        let mut index = Vec::with_capacity(tile_count.min(MAX_INDEX_PREALLOC));
+        for _ in 0..tile_count {
+            let mut offset = [0; 8];
+            reader.read_exact(&mut offset)?;
+            index.push((u64::from_le_bytes(offset), read_u32(&mut reader)?));
+        }
+
+        Ok(Self {
+            reader,
+            width,
+            height,
+            tile_size,
+            scale,
+            tiles,
+            index,
+            tiles_loaded: 0,
+        })
+    }
+
+    /// Decodes tile `(x, z)` into a [`HeightMap`] with the source's scale.
+    ///
+    /// The tile's vertex `(0, 0)` is source vertex
+    /// `(x·tile_size, z·tile_size)`; see [`tile_origin`](Self::tile_origin)
+    /// for its world position.
+    pub fn tile(&mut self, x: u32, z: u32) -> Result<HeightMap, TiledHeightMapError> {
+        if x >= self.tiles.x || z >= self.tiles.y {
+            return Err(TiledHeightMapError::TileOutOfRange(IVec2::new(
+                x as i32, z as i32,
+            )));
+        }
+        let x0 = x as usize * self.tile_size;
+        let z0 = z as usize * self.tile_size;
+        let w = (x0 + self.tile_size).min(self.width - 1) - x0 + 1;
+        let h = (z0 + self.tile_size).min(self.height - 1) - z0 + 1;
+
+        let (offset, len) = self.index[(z * self.tiles.x + x) as usize];
+        self.reader.seek(SeekFrom::Start(offset))?;
+        // The tile size comes from the header, so let the buffer grow with
+        // the data that actually decodes rather than allocating it up front.
+        let expected = w * h * 4;
+        let mut bytes = Vec::new();
+        DeflateDecoder::new((&mut self.reader).take(len as u64))
+            .take(expected as u64)
+            .read_to_end(&mut bytes)
+            .map_err(|_| TiledHeightMapError::InvalidFormat("corrupt tile data"))?;
+        if bytes.len() != expected {
+            return Err(TiledHeightMapError::InvalidFormat("truncated tile data"));
+        }
+
+        let mut tile = HeightMap::new(w, h, self.scale);
+        for (dst, src) in tile.data_mut().iter_mut().zip(bytes.chunks_exact(4)) {
+            *dst = f32::from_le_bytes([src[0], src[1], src[2], src[3]]);
+        }
+        self.tiles_loaded += 1;
+        Ok(tile)
+    }
+
+    /// Number of tiles along X and Z.
+    pub fn tile_count(&self) -> UVec2 {
+        self.tiles
+    }
+
+    /// Cells per tile side, as passed to [`write_tiled_heightmap`].
+    pub fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    /// Full-map vertex dimensions `(width, height)`.
+    pub fn dimensions(&self) -> UVec2 {
+        UVec2::new(self.width as u32, self.height as u32)
+    }
+
+    /// Grid scale shared by every tile.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// World-space XZ offset of tile `(x, z)` relative to the full map.
+    pub fn tile_origin(&self, x: u32, z: u32) -> Vec2 {
+        UVec2::new(x, z).as_vec2() * (self.tile_size as f32 * self.scale)
+    }
+
+    /// Number of tiles decoded so far by [`tile`](Self::tile).
+    pub fn tiles_loaded(&self) -> usize {
+        self.tiles_loaded
+    }
+}
+
+fn to_u32(value: usize, what: &str) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{what} {value} does not fit the tiled format"),
+        )
+    })
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
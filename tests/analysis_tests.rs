@@ -0,0 +1,261 @@
+use bevy::prelude::*;
+use bevy_symbios_ground::{
+    D8_OFFSETS, FLOW_PIT, bake_hillshade, deposition_potential, find_pits, flow_directions,
+    gradient_field, slope_mask,
+};
+use symbios_ground::HeightMap;
+
+fn bowl(n: usize) -> HeightMap {
+    let c = (n / 2) as f32;
+    let mut map = HeightMap::new(n, n, 1.0);
+    for z in 0..n {
+        for x in 0..n {
+            map.set(x, z, (x as f32 - c).powi(2) + (z as f32 - c).powi(2));
+        }
+    }
+    map
+}
+
+#[test]
+fn bowl_flows_toward_center_pit() {
+    let n = 9;
+    let map = bowl(n);
+    let flow = flow_directions(&map);
+    assert_eq!(flow.len(), n * n);
+
+    let center = IVec2::splat((n / 2) as i32);
+    assert_eq!(flow[(n / 2) * n + n / 2], FLOW_PIT);
+    for z in 0..n {
+        for x in 0..n {
+            let here = IVec2::new(x as i32, z as i32);
+            if here == center {
+                continue;
+            }
+            let code = flow[z * n + x];
+            assert_ne!(code, FLOW_PIT, "({x}, {z}) should drain");
+            let step = D8_OFFSETS[code as usize];
+            assert!(
+                step.dot(center - here) > 0,
+                "({x}, {z}) flows along {step}, away from the center"
+            );
+        }
+    }
+}
+
+#[test]
+fn flat_map_is_all_pits() {
+    let flow = flow_directions(&HeightMap::new(4, 3, 1.0));
+    assert!(flow.iter().all(|&code| code == FLOW_PIT));
+}
+
+#[test]
+fn hillshade_brightens_slopes_facing_light() {
+    // Ridge along Z: rises towards x = 4, falls after it.
+    let mut map = HeightMap::new(9, 5, 1.0);
+    for z in 0..5 {
+        for x in 0..9 {
+            map.set(x, z, 4.0 - (x as f32 - 4.0).abs());
+        }
+    }
+    let light = Vec3::new(-1.0, 1.0, 0.0);
+    let shade = bake_hillshade(&map, light);
+    assert_eq!(shade.len(), 9 * 5);
+
+    let facing = shade[2 * 9 + 2]; // west slope, facing −X towards the light
+    let away = shade[2 * 9 + 6]; // east slope
+    assert!(facing > away, "{facing} should exceed {away}");
+    assert!(shade.iter().all(|v| (0.0..=1.0).contains(v)));
+}
+
+#[test]
+fn hillshade_of_flat_map_is_light_elevation() {
+    let light = Vec3::new(1.0, 2.0, -2.0);
+    let shade = bake_hillshade(&HeightMap::new(4, 4, 1.0), light);
+    let expected = light.normalize().y;
+    assert!(shade.iter().all(|v| (v - expected).abs() < 1e-6));
+}
+
+#[test]
+fn aspect_of_east_facing_ramp_and_flat_ground() {
+    use bevy_symbios_ground::{ASPECT_FLAT, aspect_map};
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    // Falls towards +X on the west half, flat on the east half.
+    let mut map = HeightMap::new(12, 6, 1.0);
+    for z in 0..6 {
+        for x in 0..12 {
+            map.set(x, z, 5.0 - x.min(6) as f32 * 0.5);
+        }
+    }
+    let aspect = aspect_map(&map);
+    assert_eq!(aspect.len(), 12 * 6);
+    for z in 0..6 {
+        for x in 1..5 {
+            let a = aspect[z * 12 + x];
+            assert!((a - FRAC_PI_2).abs() < 1e-4, "({x}, {z}) faces {a}");
+        }
+        for x in 8..12 {
+            assert_eq!(aspect[z * 12 + x], ASPECT_FLAT);
+        }
+    }
+
+    // Rotated: falling towards +Z faces south.
+    let mut south = HeightMap::new(5, 5, 1.0);
+    for z in 0..5 {
+        for x in 0..5 {
+            south.set(x, z, -(z as f32));
+        }
+    }
+    assert!((aspect_map(&south)[12] - PI).abs() < 1e-4);
+}
+
+#[test]
+fn distance_to_water_grows_from_single_pit() {
+    use bevy_symbios_ground::distance_to_water;
+
+    let n = 11;
+    let mut map = HeightMap::new(n, n, 2.0);
+    for h in map.data_mut() {
+        *h = 5.0;
+    }
+    map.set(5, 5, -1.0);
+    let dist = distance_to_water(&map, 0.0);
+    assert_eq!(dist.len(), n * n);
+    assert_eq!(dist[5 * n + 5], 0.0);
+
+    // Axial and diagonal steps are exact multiples of the grid scale.
+    assert_eq!(dist[5 * n + 8], 3.0 * 2.0);
+    assert!((dist[8 * n + 8] - 3.0 * 2.0 * std::f32::consts::SQRT_2).abs() < 1e-4);
+
+    // Every step away from the pit increases the distance.
+    for z in 0..n {
+        for x in 0..n {
+            let here = IVec2::new(x as i32, z as i32);
+            let out = here + (here - IVec2::splat(5)).signum();
+            if out == here || out.min_element() < 0 || out.max_element() >= n as i32 {
+                continue;
+            }
+            assert!(dist[out.y as usize * n + out.x as usize] > dist[z * n + x]);
+        }
+    }
+
+    assert!(
+        distance_to_water(&map, -5.0)
+            .iter()
+            .all(|d| d.is_infinite())
+    );
+}
+
+#[test]
+fn deposition_favours_valley_floors_over_ridges() {
+    // Parallel V-shaped valleys at x = 4 and 12, ridges at x = 0, 8, 16.
+    let (w, h) = (17, 6);
+    let mut map = HeightMap::new(w, h, 1.0);
+    for z in 0..h {
+        for x in 0..w {
+            map.set(x, z, ((x % 8) as f32 - 4.0).abs());
+        }
+    }
+    let score = deposition_potential(&map);
+    assert_eq!(score.len(), w * h);
+    assert!(score.iter().all(|s| (0.0..=1.0).contains(s)));
+
+    for z in 0..h {
+        for valley in [4, 12] {
+            assert!(score[z * w + valley] > 0.9, "valley ({valley}, {z})");
+        }
+        assert_eq!(score[z * w + 8], 0.0, "ridge (8, {z})");
+        // Planar valley walls are not concave.
+        assert_eq!(score[z * w + 6], 0.0, "slope (6, {z})");
+    }
+    assert!(
+        deposition_potential(&HeightMap::new(4, 4, 1.0))
+            .iter()
+            .all(|&s| s == 0.0)
+    );
+}
+
+#[test]
+fn gradient_of_x_ramp_is_constant_along_x() {
+    // Rises 0.75 per vertex over a grid spacing of 2.5.
+    let (w, h) = (7, 5);
+    let mut map = HeightMap::new(w, h, 2.5);
+    for z in 0..h {
+        for x in 0..w {
+            map.set(x, z, x as f32 * 0.75);
+        }
+    }
+    let gradient = gradient_field(&map);
+    assert_eq!(gradient.len(), w * h);
+    for z in 0..h {
+        for x in 1..w - 1 {
+            let g = gradient[z * w + x];
+            assert!((g.x - 0.3).abs() < 1e-6, "({x}, {z}): {g}");
+            assert_eq!(g.y, 0.0, "({x}, {z})");
+        }
+    }
+}
+
+#[test]
+fn slope_mask_splits_at_the_angle_limit() {
+    // A 45° plane: rise equals run everywhere.
+    let mut map = HeightMap::new(5, 5, 2.0);
+    for z in 0..5 {
+        for x in 0..5 {
+            map.set(x, z, z as f32 * 2.0);
+        }
+    }
+    let interior = |mask: Vec<bool>| (1..4).map(move |z| mask[z * 5 + 2]).collect::<Vec<_>>();
+    assert!(interior(slope_mask(&map, 46.0)).iter().all(|&m| m));
+    assert!(interior(slope_mask(&map, 44.0)).iter().all(|&m| !m));
+}
+
+#[test]
+fn single_basin_has_one_pit_with_spill_depth() {
+    // A ramp rising towards +X, carrying a crater: a 3×3 mound of height 6
+    // at x ∈ 4..=6, z ∈ 3..=5 with its centre dug down to 1.
+    let (w, h) = (11, 9);
+    let mut map = HeightMap::new(w, h, 1.0);
+    for z in 0..h {
+        for x in 0..w {
+            map.set(x, z, x as f32);
+        }
+    }
+    for z in 3..=5 {
+        for x in 4..=6 {
+            map.set(x, z, 6.0);
+        }
+    }
+    map.set(5, 4, 1.0);
+
+    let pits = find_pits(&map);
+    assert_eq!(pits.len(), 1, "{pits:?}");
+    let (x, z, depth) = pits[0];
+    assert_eq!((x, z), (5, 4));
+    // The crater fills to its rim at 6, then spills down the ramp.
+    assert_eq!(depth, 5.0);
+
+    // Flattening the crater's downhill wall lets the water out at 3.
+    for z in 3..=5 {
+        map.set(4, z, 3.0);
+    }
+    assert_eq!(find_pits(&map), vec![(5, 4, 2.0)]);
+}
+
+#[test]
+fn every_pit_reports_its_own_spill_depth() {
+    // Two craters on a flat plain, one of rim 4 around a floor of 1 and one
+    // of rim 2 around a floor of 0.
+    let crater = |map: &mut HeightMap, cx: usize, rim: f32, floor: f32| {
+        for z in 2..=4 {
+            for x in cx - 1..=cx + 1 {
+                map.set(x, z, rim);
+            }
+        }
+        map.set(cx, 3, floor);
+    };
+    let mut both = HeightMap::new(11, 7, 1.0);
+    crater(&mut both, 3, 4.0, 1.0);
+    crater(&mut both, 7, 2.0, 0.0);
+    assert_eq!(find_pits(&both), vec![(3, 3, 3.0), (7, 3, 2.0)]);
+}
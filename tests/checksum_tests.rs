@@ -0,0 +1,42 @@
+use bevy_symbios_ground::{HeightMapMeshBuilder, heightmap_checksum, mesh_checksum};
+use symbios_ground::HeightMap;
+
+fn hilly_map() -> HeightMap {
+    let mut map = HeightMap::new(6, 6, 1.0);
+    for z in 0..6 {
+        for x in 0..6 {
+            map.set(x, z, ((x * 5 + z * 3) % 7) as f32 * 0.5);
+        }
+    }
+    map
+}
+
+#[test]
+fn identical_meshes_hash_equal() {
+    let builder = HeightMapMeshBuilder::new();
+    let a = builder.build(&hilly_map());
+    let b = builder.build(&hilly_map());
+
+    assert_eq!(mesh_checksum(&a), mesh_checksum(&b));
+    assert_eq!(
+        heightmap_checksum(&hilly_map()),
+        heightmap_checksum(&hilly_map())
+    );
+}
+
+#[test]
+fn perturbed_height_changes_hash() {
+    let builder = HeightMapMeshBuilder::new();
+    let original = hilly_map();
+    let mut perturbed = hilly_map();
+    perturbed.set(2, 3, perturbed.get(2, 3) + 0.01);
+
+    assert_ne!(
+        mesh_checksum(&builder.build(&original)),
+        mesh_checksum(&builder.build(&perturbed))
+    );
+    assert_ne!(
+        heightmap_checksum(&original),
+        heightmap_checksum(&perturbed)
+    );
+}
@@ -0,0 +1,373 @@
+#![cfg(feature = "physics")]
+
+use avian3d::parry::math::{Point, Vector};
+use avian3d::parry::query::Ray;
+use avian3d::prelude::Collider;
+use bevy::prelude::*;
+use bevy_symbios_ground::{
+    HEIGHTFIELD_DIAGONAL, HeightMapMeshBuilder, QuadDiagonal, build_heightfield_collider,
+    build_heightfield_collider_with_diagonal, heightfield_diagonal, verify_collider_orientation,
+};
+use symbios_ground::HeightMap;
+
+/// Map whose cells are strongly non-planar, so the two diagonals disagree.
+fn saddle_map(w: usize, h: usize, scale: f32) -> HeightMap {
+    let mut map = HeightMap::new(w, h, scale);
+    for z in 0..h {
+        for x in 0..w {
+            map.set(
+                x,
+                z,
+                if (x + z) % 2 == 0 { 2.0 } else { 0.0 } + x as f32 * 0.1,
+            );
+        }
+    }
+    map
+}
+
+/// Height of the collider surface at mesh-space `(x, z)` via a downward ray.
+fn collider_height(collider: &Collider, map: &HeightMap, x: f32, z: f32) -> f32 {
+    let half_w = (map.width() - 1) as f32 * map.scale() * 0.5;
+    let half_d = (map.height() - 1) as f32 * map.scale() * 0.5;
+    let ray = Ray::new(
+        Point::new(x - half_w, 100.0, z - half_d),
+        Vector::new(0.0, -1.0, 0.0),
+    );
+    let toi = collider
+        .shape()
+        .cast_local_ray(&ray, 1000.0, true)
+        .expect("ray must hit the heightfield");
+    100.0 - toi
+}
+
+/// Height of the rendered mesh surface at `(x, z)`, by searching its triangles.
+fn mesh_height(mesh: &Mesh, x: f32, z: f32) -> f32 {
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+    for tri in indices.chunks_exact(3) {
+        let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| Vec3::from(positions[i]));
+        let p = Vec2::new(x, z);
+        let (a2, b2, c2) = (a.xz(), b.xz(), c.xz());
+        let area = (b2 - a2).perp_dot(c2 - a2);
+        let u = (b2 - p).perp_dot(c2 - p) / area;
+        let v = (c2 - p).perp_dot(a2 - p) / area;
+        let w = 1.0 - u - v;
+        if u >= -1e-5 && v >= -1e-5 && w >= -1e-5 {
+            return u * a.y + v * b.y + w * c.y;
+        }
+    }
+    panic!("point ({x}, {z}) is not covered by the mesh");
+}
+
+#[test]
+fn default_diagonals_are_aligned() {
+    let map = saddle_map(5, 5, 1.0);
+    let collider = build_heightfield_collider(&map);
+    assert_eq!(heightfield_diagonal(&collider), Some(HEIGHTFIELD_DIAGONAL));
+    assert_eq!(QuadDiagonal::default(), HEIGHTFIELD_DIAGONAL);
+}
+
+#[test]
+fn cell_centers_match_when_diagonals_aligned() {
+    let map = saddle_map(5, 4, 2.0);
+    for diagonal in [
+        QuadDiagonal::TopRightBottomLeft,
+        QuadDiagonal::TopLeftBottomRight,
+    ] {
+        let mesh = HeightMapMeshBuilder::new()
+            .with_diagonal(diagonal)
+            .build(&map);
+        let collider = build_heightfield_collider_with_diagonal(&map, diagonal);
+        assert_eq!(heightfield_diagonal(&collider), Some(diagonal));
+
+        for cz in 0..3 {
+            for cx in 0..4 {
+                let x = (cx as f32 + 0.5) * 2.0;
+                let z = (cz as f32 + 0.5) * 2.0;
+                let mesh_y = mesh_height(&mesh, x, z);
+                let collider_y = collider_height(&collider, &map, x, z);
+                assert!(
+                    (mesh_y - collider_y).abs() < 1e-4,
+                    "{diagonal:?} cell ({cx}, {cz}): mesh {mesh_y} vs collider {collider_y}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn cell_centers_differ_when_diagonals_mismatched() {
+    let map = saddle_map(4, 4, 1.0);
+    let mesh = HeightMapMeshBuilder::new()
+        .with_diagonal(QuadDiagonal::TopLeftBottomRight)
+        .build(&map);
+    let collider = build_heightfield_collider(&map);
+    let mesh_y = mesh_height(&mesh, 0.5, 0.5);
+    let collider_y = collider_height(&collider, &map, 0.5, 0.5);
+    assert!((mesh_y - collider_y).abs() > 0.5);
+}
+
+/// Asymmetric L: a full-height arm along Z at x ∈ {0, 1} and a shorter foot
+/// along X at z ∈ {3, 4}, on a non-square map.
+fn l_shaped_map() -> HeightMap {
+    let mut map = HeightMap::new(7, 5, 1.5);
+    for z in 0..5 {
+        for x in 0..7 {
+            let in_arm = x <= 1;
+            let in_foot = z >= 3 && x <= 4;
+            map.set(x, z, if in_arm || in_foot { 4.0 } else { 0.0 });
+        }
+    }
+    map
+}
+
+#[test]
+fn l_shaped_collider_orientation_is_correct() {
+    let map = l_shaped_map();
+    let collider = build_heightfield_collider(&map);
+    verify_collider_orientation(&map, &collider).expect("collider must match the heightmap");
+}
+
+#[test]
+fn transposed_collider_is_rejected() {
+    let map = l_shaped_map();
+    // Naive layout: rows along X, as Avian's docs suggest.
+    let heights: Vec<Vec<f32>> = (0..map.width())
+        .map(|x| (0..map.height()).map(|z| map.get(x, z)).collect())
+        .collect();
+    let collider = Collider::heightfield(heights, Vec3::new(9.0, 1.0, 6.0));
+    let err = verify_collider_orientation(&map, &collider).unwrap_err();
+    assert!(err.to_string().contains("mirrored or transposed"), "{err}");
+}
+
+#[cfg(feature = "debug")]
+#[test]
+fn debug_check_accepts_built_collider() {
+    use bevy_symbios_ground::collider::debug_check_collider_orientation;
+    assert!(debug_check_collider_orientation(&l_shaped_map()).is_ok());
+}
+
+#[test]
+fn trimesh_collider_omits_hole_cells() {
+    use bevy_symbios_ground::build_trimesh_collider_with_holes;
+
+    let map = saddle_map(5, 5, 1.0);
+    let mut holes = vec![false; 4 * 4];
+    holes[2 * 4 + 1] = true; // cell (1, 2)
+    let collider = build_trimesh_collider_with_holes(&map, &holes);
+    let trimesh = collider.shape().as_trimesh().expect("trimesh collider");
+
+    assert_eq!(trimesh.num_triangles(), (16 - 1) * 2);
+    // Cell (1, 2) spans local x ∈ [-1, 0], z ∈ [0, 1] around the centered origin.
+    for tri in trimesh.triangles() {
+        let c = (tri.a + tri.b.coords + tri.c.coords) / 3.0;
+        let inside = (-1.0..0.0).contains(&c.x) && (0.0..1.0).contains(&c.z);
+        assert!(!inside, "triangle over the hole at {c:?}");
+    }
+
+    // A ray through the hole falls through; next to it, it hits.
+    let hit = |x: f32, z: f32| {
+        let ray = Ray::new(Point::new(x, 100.0, z), Vector::new(0.0, -1.0, 0.0));
+        collider.shape().cast_local_ray(&ray, 1000.0, true)
+    };
+    assert!(hit(-0.5, 0.5).is_none());
+    assert!(hit(0.5, 0.5).is_some());
+}
+
+#[test]
+fn capped_collider_adds_cap_triangles() {
+    use bevy_symbios_ground::build_capped_trimesh_collider;
+
+    let map = saddle_map(5, 5, 1.0);
+    let mut holes = vec![false; 4 * 4];
+    holes[2 * 4 + 1] = true; // cell (1, 2), mesh-space x ∈ [1, 2], z ∈ [2, 3]
+    // A funnel floor one unit below the hole, in mesh space.
+    let cap = (
+        vec![
+            Vec3::new(1.0, -1.0, 2.0),
+            Vec3::new(2.0, -1.0, 2.0),
+            Vec3::new(1.0, -1.0, 3.0),
+            Vec3::new(2.0, -1.0, 3.0),
+        ],
+        vec![[0, 2, 1], [1, 2, 3]],
+    );
+    let collider = build_capped_trimesh_collider(&map, &holes, [cap]);
+
+    let trimesh = collider.shape().as_trimesh().expect("trimesh collider");
+    assert_eq!(trimesh.num_triangles(), (16 - 1) * 2 + 2);
+    let below = trimesh.triangles().filter(|tri| tri.a.y == -1.0).count();
+    assert_eq!(below, 2);
+
+    // A ray through the hole now lands on the cap, centered like the terrain.
+    let ray = Ray::new(Point::new(-0.5, 100.0, 0.5), Vector::new(0.0, -1.0, 0.0));
+    let toi = collider
+        .shape()
+        .cast_local_ray(&ray, 1000.0, true)
+        .expect("ray hits the cap");
+    assert!((toi - 101.0).abs() < 1e-4);
+}
+
+#[test]
+fn conservative_collider_never_dips_below_source() {
+    use bevy_symbios_ground::build_heightfield_collider_conservative;
+
+    let mut map = HeightMap::new(10, 10, 0.5);
+    for z in 0..10 {
+        for x in 0..10 {
+            map.set(x, z, ((x * 7 + z * 13) % 5) as f32 - (x as f32 * 0.3));
+        }
+    }
+    let collider = build_heightfield_collider_conservative(&map, 3);
+    let heights = collider
+        .shape()
+        .as_heightfield()
+        .expect("heightfield collider")
+        .heights();
+    assert_eq!((heights.nrows(), heights.ncols()), (4, 4));
+
+    // Each coarse cell covers fine vertices [3i, 3i + 3]; its corners must be
+    // at least the maximum source height in that region.
+    for cz in 0..3 {
+        for cx in 0..3 {
+            let region_max = (cz * 3..=cz * 3 + 3)
+                .flat_map(|z| (cx * 3..=cx * 3 + 3).map(move |x| (x, z)))
+                .map(|(x, z)| map.get(x, z))
+                .fold(f32::NEG_INFINITY, f32::max);
+            for (dz, dx) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                assert!(heights[(cz + dz, cx + dx)] >= region_max);
+            }
+        }
+    }
+
+    // So the collider surface is on or above every source vertex.
+    for z in 0..10 {
+        for x in 0..10 {
+            // Nudge off cell edges, where rays can slip between triangles.
+            let inside = |i: usize| (i as f32 * 0.5 + 1e-3).min(4.5 - 1e-3);
+            let (wx, wz) = (inside(x), inside(z));
+            assert!(collider_height(&collider, &map, wx, wz) >= map.get(x, z) - 1e-2);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_collider_is_built_and_replaced() {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::tasks::{AsyncComputeTaskPool, TaskPool};
+    use bevy_symbios_ground::{AsyncColliderSource, apply_collider_tasks, spawn_collider_tasks};
+
+    AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    let mut world = World::new();
+    let entity = world
+        .spawn(AsyncColliderSource::new(saddle_map(5, 5, 1.0)))
+        .id();
+
+    let run_until_built = |world: &mut World| {
+        world.run_system_once(spawn_collider_tasks).unwrap();
+        assert!(
+            world
+                .get::<AsyncColliderSource>(entity)
+                .unwrap()
+                .is_building()
+        );
+        for _ in 0..1000 {
+            world.run_system_once(apply_collider_tasks).unwrap();
+            if !world
+                .get::<AsyncColliderSource>(entity)
+                .unwrap()
+                .is_building()
+            {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        panic!("collider task did not finish");
+    };
+
+    run_until_built(&mut world);
+    let height_at_origin = |world: &World| {
+        let collider = world.get::<Collider>(entity).expect("collider inserted");
+        let ray = Ray::new(Point::new(0.0, 100.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+        100.0 - collider.shape().cast_local_ray(&ray, 1000.0, true).unwrap()
+    };
+    let before = height_at_origin(&world);
+
+    // Raise the terrain and rebuild: the component is replaced.
+    let mut source = world.get_mut::<AsyncColliderSource>(entity).unwrap();
+    for h in source.heightmap.data_mut() {
+        *h += 10.0;
+    }
+    source.mark_dirty();
+    run_until_built(&mut world);
+    assert!((height_at_origin(&world) - before - 10.0).abs() < 1e-4);
+}
+
+#[test]
+fn matched_mesh_and_collider_share_the_coarse_surface() {
+    use bevy_symbios_ground::{build_matched, downsample};
+
+    let map = saddle_map(9, 7, 0.5);
+    let coarse = downsample(&map, 2);
+    for (builder, diagonal) in [
+        (HeightMapMeshBuilder::new(), HEIGHTFIELD_DIAGONAL),
+        (
+            HeightMapMeshBuilder::new()
+                .with_diagonal(QuadDiagonal::TopLeftBottomRight)
+                .with_subdivisions(1),
+            QuadDiagonal::TopLeftBottomRight,
+        ),
+    ] {
+        let (mesh, collider) = build_matched(&map, 2, &builder);
+        assert_eq!(heightfield_diagonal(&collider), Some(diagonal));
+        for (x, z) in [(0.3, 0.2), (1.1, 0.9), (2.6, 1.7), (3.9, 2.9), (1.75, 2.25)] {
+            let mesh_y = mesh_height(&mesh, x, z);
+            let collider_y = collider_height(&collider, &coarse, x, z);
+            assert!(
+                (mesh_y - collider_y).abs() < 1e-4,
+                "({x}, {z}): mesh {mesh_y} vs collider {collider_y}"
+            );
+        }
+    }
+}
+
+#[test]
+fn hull_collider_encloses_terrain_bounds() {
+    use bevy_symbios_ground::{build_hull_collider, world_bounds};
+
+    let mut map = saddle_map(9, 6, 1.5);
+    map.set(4, 3, 7.0);
+    map.set(6, 1, -3.0);
+    let collider = build_hull_collider(&map);
+    assert!(collider.shape().as_convex_polyhedron().is_some());
+
+    // The hull's AABB covers the terrain's world bounds, shifted to centered
+    // collider space.
+    let hull = collider.shape().compute_local_aabb();
+    let bounds = world_bounds(&map);
+    let center = Vec3::from(bounds.center) * Vec3::new(1.0, 0.0, 1.0);
+    let (min, max) = (
+        Vec3::from(bounds.min()) - center,
+        Vec3::from(bounds.max()) - center,
+    );
+    assert!(Vec3::from(hull.mins).cmple(min + 1e-4).all());
+    assert!(Vec3::from(hull.maxs).cmpge(max - 1e-4).all());
+
+    // Every vertex lies inside the hull, not just the extremes.
+    for z in 0..map.height() {
+        for x in 0..map.width() {
+            let p = Vec3::new(x as f32 * 1.5, map.get(x, z), z as f32 * 1.5) - center;
+            let distance = collider
+                .shape()
+                .distance_to_local_point(&Point::new(p.x, p.y, p.z), true);
+            assert!(distance < 1e-4, "vertex ({x}, {z}) is outside the hull");
+        }
+    }
+
+    let flat = build_hull_collider(&HeightMap::new(4, 4, 1.0));
+    assert!(flat.shape().compute_local_aabb().maxs.y > 0.0);
+}
@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_symbios_ground::{TerrainEditQueue, TerrainRebuild, flush_terrain_edits};
+
+fn world_with_queue(queue: TerrainEditQueue) -> World {
+    let mut world = World::new();
+    world.init_resource::<Messages<TerrainRebuild>>();
+    world.insert_resource(Time::<()>::default());
+    world.insert_resource(queue);
+    world
+}
+
+fn step(world: &mut World, millis: u64) -> Vec<TerrainRebuild> {
+    world
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(millis));
+    world.resource_mut::<Messages<TerrainRebuild>>().update();
+    world.run_system_once(flush_terrain_edits).unwrap();
+    world
+        .resource::<Messages<TerrainRebuild>>()
+        .iter_current_update_messages()
+        .copied()
+        .collect()
+}
+
+#[test]
+fn edits_within_the_interval_rebuild_once_with_their_union() {
+    let mut world = world_with_queue(TerrainEditQueue::new(0.5));
+    let brush = [
+        URect::new(2, 2, 5, 5),
+        URect::new(4, 3, 8, 6),
+        URect::new(1, 6, 3, 9),
+    ];
+    for rect in brush {
+        world.resource_mut::<TerrainEditQueue>().push(rect);
+        assert!(step(&mut world, 100).is_empty());
+    }
+    assert_eq!(world.resource::<TerrainEditQueue>().len(), 3);
+
+    let rebuilds = step(&mut world, 250);
+    assert_eq!(
+        rebuilds,
+        vec![TerrainRebuild {
+            rect: URect::new(1, 2, 8, 9),
+            splat: true,
+        }]
+    );
+    assert!(world.resource::<TerrainEditQueue>().is_empty());
+    assert!(step(&mut world, 1000).is_empty());
+}
+
+#[test]
+fn reaching_max_edits_flushes_early() {
+    let mut world = world_with_queue(TerrainEditQueue::new(10.0).with_max_edits(2));
+    world
+        .resource_mut::<TerrainEditQueue>()
+        .push(URect::new(0, 0, 2, 2));
+    assert!(step(&mut world, 16).is_empty());
+    world
+        .resource_mut::<TerrainEditQueue>()
+        .push(URect::new(3, 3, 4, 4));
+    assert_eq!(step(&mut world, 16).len(), 1);
+}
+
+#[test]
+fn diff_regions_finds_a_changed_block() {
+    use bevy_symbios_ground::diff_regions;
+    use symbios_ground::HeightMap;
+
+    let old = HeightMap::new(16, 12, 1.0);
+    assert!(diff_regions(&old, &old, 0.0).is_empty());
+
+    let mut new = old.clone();
+    for (x, z) in [(7, 5), (8, 5), (7, 6), (8, 6)] {
+        new.set(x, z, 1.0);
+    }
+    new.set(0, 0, 1e-4);
+    assert_eq!(diff_regions(&old, &new, 1e-3), [URect::new(7, 5, 9, 7)]);
+}
+
+#[test]
+fn diff_regions_merges_nearby_changes_and_keeps_distant_ones() {
+    use bevy_symbios_ground::diff_regions;
+    use symbios_ground::HeightMap;
+
+    let old = HeightMap::new(20, 20, 1.0);
+    let mut new = old.clone();
+    // Two diagonal neighbours form one region; a third vertex touches its
+    // rectangle and merges in; a far corner stays separate.
+    new.set(3, 3, 1.0);
+    new.set(4, 4, 1.0);
+    new.set(5, 2, 1.0);
+    new.set(18, 17, -1.0);
+    assert_eq!(
+        diff_regions(&old, &new, 0.0),
+        [URect::new(3, 2, 6, 5), URect::new(18, 17, 19, 18)]
+    );
+}
+
+#[test]
+fn queued_edits_rebuild_mesh_splat_and_collider_once() {
+    use bevy_symbios_ground::{
+        GroundMaterialSettings, HeightMapMeshBuilder, SplatTexture, TerrainLive,
+        apply_terrain_rebuilds, splat_to_image, sync_splat_texture,
+    };
+    use symbios_ground::{HeightMap, WeightMap};
+
+    let builder = HeightMapMeshBuilder::new();
+    let map = HeightMap::new(9, 9, 1.0);
+    let weight_map = WeightMap::new(9, 9);
+    let mut meshes = Assets::<Mesh>::default();
+    let mesh = meshes.add(builder.build(&map));
+    let mut images = Assets::<Image>::default();
+    let splat = images.add(splat_to_image(&weight_map));
+
+    let mut world = world_with_queue(TerrainEditQueue::new(0.5));
+    world.insert_resource(meshes);
+    world.insert_resource(images);
+    world.insert_resource(SplatTexture {
+        handle: splat.clone(),
+    });
+    world.insert_resource(GroundMaterialSettings::new(weight_map));
+    world.run_system_once(sync_splat_texture).unwrap();
+    let terrain = world
+        .spawn(TerrainLive::new(mesh.clone(), map.clone(), builder.clone()))
+        .id();
+    #[cfg(feature = "async")]
+    world
+        .entity_mut(terrain)
+        .insert(bevy_symbios_ground::AsyncColliderSource::new(map.clone()));
+
+    let positions = |world: &World| -> Vec<[f32; 3]> {
+        let mesh = world.resource::<Assets<Mesh>>().get(&mesh).unwrap();
+        let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION).unwrap();
+        positions.as_float3().unwrap().to_vec()
+    };
+    let texels = |world: &World| -> Vec<u8> {
+        let image = world.resource::<Assets<Image>>().get(&splat).unwrap();
+        image.data.clone().unwrap()
+    };
+    let run = |world: &mut World, millis: u64| {
+        step(world, millis);
+        world.run_system_once(apply_terrain_rebuilds).unwrap();
+        world.run_system_once(sync_splat_texture).unwrap();
+    };
+    let (flat, blank) = (positions(&world), texels(&world));
+
+    for (x, z) in [(2, 2), (5, 3), (2, 7)] {
+        world
+            .get_mut::<TerrainLive>(terrain)
+            .unwrap()
+            .heightmap_mut()
+            .set(x, z, 3.0);
+        world
+            .resource_mut::<GroundMaterialSettings>()
+            .weight_map
+            .data[z * 9 + x] = [0, 255, 0, 0];
+        world.resource_mut::<TerrainEditQueue>().push(URect::new(
+            x as u32,
+            z as u32,
+            x as u32 + 1,
+            z as u32 + 1,
+        ));
+        run(&mut world, 100);
+        assert_eq!(positions(&world), flat, "rebuilt before the interval");
+        assert_eq!(texels(&world), blank, "re-uploaded before the interval");
+    }
+
+    run(&mut world, 250);
+    let live = world.get::<TerrainLive>(terrain).unwrap();
+    let fresh = builder.build(live.heightmap());
+    assert_eq!(
+        positions(&world),
+        fresh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap()
+    );
+    let expected: Vec<u8> = world
+        .resource::<GroundMaterialSettings>()
+        .weight_map
+        .data
+        .iter()
+        .flatten()
+        .copied()
+        .collect();
+    assert_eq!(texels(&world), expected);
+    #[cfg(feature = "async")]
+    assert_eq!(
+        world
+            .get::<bevy_symbios_ground::AsyncColliderSource>(terrain)
+            .unwrap()
+            .heightmap
+            .data(),
+        live.heightmap().data()
+    );
+    assert!(world.resource::<TerrainEditQueue>().is_empty());
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "image-export")]
+
+use std::fs::File;
+use std::io::BufReader;
+
+use bevy_symbios_ground::{save_heightmap_png, write_heightmap_png};
+use symbios_ground::HeightMap;
+
+fn read_png(reader: impl std::io::BufRead + std::io::Seek) -> (png::OutputInfo, Vec<u16>) {
+    let mut reader = png::Decoder::new(reader).read_info().unwrap();
+    let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+    let info = reader.next_frame(&mut buf).unwrap();
+    let values = buf[..info.buffer_size()]
+        .chunks_exact(2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .collect();
+    (info, values)
+}
+
+#[test]
+fn png_round_trip_reproduces_heights_within_quantization() {
+    let (w, h) = (13, 7);
+    let mut map = HeightMap::new(w, h, 2.0);
+    for z in 0..h {
+        for x in 0..w {
+            map.set(x, z, (x as f32 * 0.6).sin() * 40.0 + z as f32 * 3.0 - 12.0);
+        }
+    }
+    let path = std::env::temp_dir().join("bevy_symbios_ground_export_test.png");
+    save_heightmap_png(&map, &path, None).unwrap();
+    let (info, values) = read_png(BufReader::new(File::open(&path).unwrap()));
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!((info.width, info.height), (w as u32, h as u32));
+    assert_eq!(info.color_type, png::ColorType::Grayscale);
+    assert_eq!(info.bit_depth, png::BitDepth::Sixteen);
+    assert_eq!(values.iter().min(), Some(&0));
+    assert_eq!(values.iter().max(), Some(&u16::MAX));
+
+    let (lo, hi) = map
+        .data()
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    let step = (hi - lo) / u16::MAX as f32;
+    for (i, &v) in values.iter().enumerate() {
+        let restored = lo + v as f32 * step;
+        let original = map.data()[i];
+        assert!(
+            (restored - original).abs() <= step,
+            "{restored} vs {original}"
+        );
+    }
+}
+
+#[test]
+fn explicit_range_clamps_out_of_range_heights() {
+    let mut map = HeightMap::new(3, 1, 1.0);
+    map.set(0, 0, -5.0);
+    map.set(1, 0, 50.0);
+    map.set(2, 0, 500.0);
+    let mut bytes = Vec::new();
+    write_heightmap_png(&map, &mut bytes, Some((0.0, 100.0))).unwrap();
+    let (_, values) = read_png(std::io::Cursor::new(bytes));
+    assert_eq!(values, vec![0, 32768, u16::MAX]);
+}
@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_symbios_ground::{
+    HeightMapMeshBuilder, TerrainHeightModulator, TerrainLive, TerrainRebuild,
+    modulate_terrain_heights,
+};
+use symbios_ground::HeightMap;
+
+fn hilly_map() -> HeightMap {
+    let mut map = HeightMap::new(9, 9, 1.0);
+    for z in 0..9 {
+        for x in 0..9 {
+            map.set(x, z, ((x * 5 + z * 3) % 7) as f32 * 0.5);
+        }
+    }
+    map
+}
+
+fn float3(mesh: &Mesh, attribute: bevy::mesh::MeshVertexAttribute) -> Vec<[f32; 3]> {
+    mesh.attribute(attribute)
+        .unwrap()
+        .as_float3()
+        .unwrap()
+        .to_vec()
+}
+
+#[test]
+fn patch_updates_exactly_the_affected_vertices() {
+    let builder = HeightMapMeshBuilder::new();
+    let map = hilly_map();
+    let mut meshes = Assets::<Mesh>::default();
+    let handle = meshes.add(builder.build(&map));
+    let before_positions = float3(meshes.get(&handle).unwrap(), Mesh::ATTRIBUTE_POSITION);
+    let before_normals = float3(meshes.get(&handle).unwrap(), Mesh::ATTRIBUTE_NORMAL);
+
+    let mut live = TerrainLive::new(handle.clone(), map, builder.clone());
+    // Vertices x ∈ [3, 5), z ∈ [4, 6).
+    assert!(live.patch_heights(&mut meshes, URect::new(3, 4, 5, 6), &[9.0, 8.0, 7.0, 6.0]));
+
+    let mesh = meshes.get(&handle).unwrap();
+    let positions = float3(mesh, Mesh::ATTRIBUTE_POSITION);
+    let normals = float3(mesh, Mesh::ATTRIBUTE_NORMAL);
+    for z in 0..9 {
+        for x in 0..9 {
+            let i = z * 9 + x;
+            let patched = (3..5).contains(&x) && (4..6).contains(&z);
+            let border = (2..6).contains(&x) && (3..7).contains(&z);
+            if patched {
+                assert_eq!(positions[i][1], live.heightmap().get(x, z));
+            } else {
+                assert_eq!(positions[i], before_positions[i], "vertex ({x}, {z})");
+            }
+            if !border {
+                assert_eq!(normals[i], before_normals[i], "normal ({x}, {z})");
+            }
+        }
+    }
+    assert_eq!(live.heightmap().get(4, 5), 6.0);
+
+    // The patched mesh matches a fresh build of the edited heights.
+    let fresh = builder.build(live.heightmap());
+    assert_eq!(positions, float3(&fresh, Mesh::ATTRIBUTE_POSITION));
+    assert_eq!(normals, float3(&fresh, Mesh::ATTRIBUTE_NORMAL));
+    assert_ne!(normals, before_normals);
+}
+
+#[test]
+fn patch_applies_preprocessing_and_packed_normals() {
+    use bevy::mesh::VertexAttributeValues;
+    use bevy_symbios_ground::{ATTRIBUTE_NORMAL_OCT16, NanPolicy, NormalEncoding};
+
+    let builder = HeightMapMeshBuilder::new()
+        .with_nan_policy(NanPolicy::ReplaceWith(1.0))
+        .with_geomorph(0.5)
+        .with_radial_mask(4.0, 2.0)
+        .with_normal_encoding(NormalEncoding::Oct16);
+    let map = hilly_map();
+    let mut meshes = Assets::<Mesh>::default();
+    let handle = meshes.add(builder.build(&map));
+
+    let mut live = TerrainLive::new(handle.clone(), map, builder.clone());
+    let patch = [9.0, f32::NAN, 7.3, 6.1, 2.2, 4.9];
+    assert!(live.patch_heights(&mut meshes, URect::new(5, 6, 8, 8), &patch));
+
+    let packed = |mesh: &Mesh| match mesh.attribute(ATTRIBUTE_NORMAL_OCT16) {
+        Some(VertexAttributeValues::Snorm16x2(encoded)) => encoded.clone(),
+        _ => panic!("missing packed normals"),
+    };
+    let mesh = meshes.get(&handle).unwrap();
+    let fresh = builder.build(live.heightmap());
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_none());
+    assert_eq!(
+        float3(mesh, Mesh::ATTRIBUTE_POSITION),
+        float3(&fresh, Mesh::ATTRIBUTE_POSITION)
+    );
+    assert_eq!(packed(mesh), packed(&fresh));
+}
+
+fn world_with(modulator: TerrainHeightModulator) -> (World, Handle<Mesh>) {
+    let builder = HeightMapMeshBuilder::new();
+    let map = hilly_map();
+    let mut meshes = Assets::<Mesh>::default();
+    let handle = meshes.add(builder.build(&map));
+
+    let mut world = World::new();
+    world.insert_resource(meshes);
+    world.init_resource::<Messages<TerrainRebuild>>();
+    world.insert_resource(Time::<()>::default());
+    world.insert_resource(modulator);
+    world.spawn(TerrainLive::new(handle.clone(), map, builder));
+    (world, handle)
+}
+
+fn positions(world: &World, handle: &Handle<Mesh>) -> Vec<[f32; 3]> {
+    let meshes = world.resource::<Assets<Mesh>>();
+    float3(meshes.get(handle).unwrap(), Mesh::ATTRIBUTE_POSITION)
+}
+
+fn step(world: &mut World, handle: &Handle<Mesh>, millis: u64) -> Vec<[f32; 3]> {
+    world
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(millis));
+    world.run_system_once(modulate_terrain_heights).unwrap();
+    positions(world, handle)
+}
+
+#[test]
+fn advancing_time_follows_the_height_function() {
+    let (mut world, handle) = world_with(TerrainHeightModulator::new(|x, z, h, t| {
+        h + t * (x + 2.0 * z)
+    }));
+    let base = hilly_map();
+    for (millis, t) in [(500, 0.5), (1500, 2.0)] {
+        let after = step(&mut world, &handle, millis);
+        for z in 0..9 {
+            for x in 0..9 {
+                let expected = base.get(x, z) + t * (x as f32 + 2.0 * z as f32);
+                let y = after[z * 9 + x][1];
+                assert!((y - expected).abs() < 1e-4, "({x}, {z}) at {t}s: {y}");
+            }
+        }
+        let rebuilds: Vec<_> = world
+            .resource::<Messages<TerrainRebuild>>()
+            .iter_current_update_messages()
+            .copied()
+            .collect();
+        assert!(rebuilds.contains(&TerrainRebuild {
+            rect: URect::new(0, 0, 9, 9),
+            splat: false,
+        }));
+    }
+}
+
+#[test]
+fn interval_throttles_and_stride_interpolates() {
+    let (mut world, handle) = world_with(
+        TerrainHeightModulator::new(|x, _z, h, t| h + t * x)
+            .with_interval(1.0)
+            .with_stride(3),
+    );
+    let before = positions(&world, &handle);
+    assert_eq!(step(&mut world, &handle, 400), before);
+
+    // The offset is linear in x, so interpolation between the sampled
+    // columns reproduces it exactly.
+    let after = step(&mut world, &handle, 600);
+    let base = hilly_map();
+    for z in 0..9 {
+        for x in 0..9 {
+            let expected = base.get(x, z) + x as f32;
+            assert!((after[z * 9 + x][1] - expected).abs() < 1e-4);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn per_frame_modulation_still_lands_colliders_without_touching_the_splat() {
+    use avian3d::prelude::Collider;
+    use bevy::tasks::{AsyncComputeTaskPool, TaskPool};
+    use bevy_symbios_ground::{
+        AsyncColliderSource, GroundMaterialSettings, SplatTexture, apply_collider_tasks,
+        apply_terrain_rebuilds, spawn_collider_tasks, splat_to_image, sync_splat_texture,
+    };
+    use symbios_ground::WeightMap;
+
+    AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    // The default interval of zero modulates, and dirties the collider, on
+    // every frame.
+    let (mut world, _) = world_with(TerrainHeightModulator::new(|x, _z, h, t| h + t * x));
+    let terrain = world
+        .query_filtered::<Entity, With<TerrainLive>>()
+        .single(&world)
+        .unwrap();
+    // A large collider map keeps each build running over several frames;
+    // the live heights are copied into its corner.
+    world
+        .entity_mut(terrain)
+        .insert(AsyncColliderSource::new(HeightMap::new(1025, 1025, 1.0)));
+
+    let weight_map = WeightMap::new(9, 9);
+    let mut images = Assets::<Image>::default();
+    let splat = images.add(splat_to_image(&weight_map));
+    world.insert_resource(images);
+    world.insert_resource(SplatTexture { handle: splat });
+    world.insert_resource(GroundMaterialSettings::new(weight_map).with_crossfade(1.0));
+    world.run_system_once(sync_splat_texture).unwrap();
+    let image_count = world.resource::<Assets<Image>>().len();
+
+    for _ in 0..1000 {
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(16));
+        world.resource_mut::<Messages<TerrainRebuild>>().update();
+        world.run_system_once(modulate_terrain_heights).unwrap();
+        world.run_system_once(apply_terrain_rebuilds).unwrap();
+        world.run_system_once(spawn_collider_tasks).unwrap();
+        world.run_system_once(apply_collider_tasks).unwrap();
+        world.run_system_once(sync_splat_texture).unwrap();
+        // Height-only rebuilds never re-upload or snapshot the splat.
+        assert_eq!(world.resource::<Assets<Image>>().len(), image_count);
+        if world.get::<Collider>(terrain).is_some() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    panic!("collider never landed while the modulator ran");
+}
@@ -0,0 +1,83 @@
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_symbios_ground::{TerrainLod, update_terrain_lod};
+
+fn lod_world() -> (World, Vec<Handle<Mesh>>, Entity, Entity) {
+    let mut meshes = Assets::<Mesh>::default();
+    let handles: Vec<Handle<Mesh>> = (0..3)
+        .map(|_| meshes.add(Mesh::from(Cuboid::default())))
+        .collect();
+
+    let mut world = World::new();
+    let camera = world
+        .spawn((
+            Camera3d::default(),
+            GlobalTransform::from_xyz(0.0, 0.0, 50.0),
+        ))
+        .id();
+    let terrain = world
+        .spawn((
+            Mesh3d(handles[0].clone()),
+            GlobalTransform::IDENTITY,
+            TerrainLod::new(handles.clone(), vec![100.0, 300.0]).with_hysteresis(0.1),
+        ))
+        .id();
+    (world, handles, camera, terrain)
+}
+
+fn move_camera_and_update(world: &mut World, camera: Entity, distance: f32) {
+    *world.get_mut::<GlobalTransform>(camera).unwrap() =
+        GlobalTransform::from_xyz(0.0, 0.0, distance);
+    world.run_system_once(update_terrain_lod).unwrap();
+}
+
+#[test]
+fn moving_past_threshold_swaps_to_coarser_mesh() {
+    let (mut world, handles, camera, terrain) = lod_world();
+    let shown = |world: &World| world.get::<Mesh3d>(terrain).unwrap().0.clone();
+
+    move_camera_and_update(&mut world, camera, 50.0);
+    assert_eq!(shown(&world), handles[0]);
+
+    // Past 100 but inside the hysteresis band: stay fine.
+    move_camera_and_update(&mut world, camera, 105.0);
+    assert_eq!(shown(&world), handles[0]);
+
+    move_camera_and_update(&mut world, camera, 120.0);
+    assert_eq!(shown(&world), handles[1]);
+
+    // Back inside 100, still within the band: no flicker back.
+    move_camera_and_update(&mut world, camera, 95.0);
+    assert_eq!(shown(&world), handles[1]);
+
+    move_camera_and_update(&mut world, camera, 80.0);
+    assert_eq!(shown(&world), handles[0]);
+
+    // Jumping straight past both thresholds lands on the coarsest level.
+    move_camera_and_update(&mut world, camera, 1000.0);
+    assert_eq!(shown(&world), handles[2]);
+}
+
+#[test]
+fn distance_is_measured_to_bounds() {
+    use bevy::camera::primitives::Aabb;
+
+    let (mut world, handles, camera, terrain) = lod_world();
+    // A 400-wide terrain centered on the origin: the camera at z = 250 is
+    // only 50 from its edge.
+    world.entity_mut(terrain).insert(Aabb::from_min_max(
+        Vec3::new(-200.0, 0.0, -200.0),
+        Vec3::new(200.0, 10.0, 200.0),
+    ));
+    move_camera_and_update(&mut world, camera, 250.0);
+    assert_eq!(world.get::<Mesh3d>(terrain).unwrap().0, handles[0]);
+}
+
+#[test]
+fn select_level_keeps_current_inside_band() {
+    let lod = TerrainLod::new(vec![Handle::default(); 3], vec![10.0, 20.0]);
+    assert_eq!(lod.select_level(0, 10.2), 0);
+    assert_eq!(lod.select_level(1, 10.2), 1);
+    assert_eq!(lod.select_level(0, 25.0), 2);
+    assert_eq!(lod.select_level(2, 5.0), 0);
+}
@@ -1,3 +1,7 @@
+// Grid indices are spelled out as `z * width + x`, even when `z` is 1.
+#![allow(clippy::identity_op)]
+
+use bevy::math::DVec3;
 use bevy::prelude::*;
 use bevy_symbios_ground::{HeightMapMeshBuilder, NormalMethod};
 use symbios_ground::HeightMap;
@@ -215,3 +219,2227 @@ fn ramp_normals_have_x_component() {
         interior
     );
 }
+
+#[test]
+fn chunked_covers_map_with_shared_borders() {
+    let map = ramp_map(10, 7, 1.0);
+    let chunks = HeightMapMeshBuilder::new().build_chunked(&map, 4);
+    // 9 cells → 3 chunks along X; 6 cells → 2 chunks along Z.
+    assert_eq!(chunks.len(), 6);
+    assert_eq!(chunks[0].0, IVec2::new(0, 0));
+    assert_eq!(chunks[5].0, IVec2::new(2, 1));
+
+    // Chunk (0, 0) spans vertices [0, 4] × [0, 4]; the last chunk [8, 9] × [4, 6].
+    assert_eq!(chunks[0].1.count_vertices(), 5 * 5);
+    assert_eq!(chunks[5].1.count_vertices(), 2 * 3);
+
+    let positions = chunks[5]
+        .1
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert_eq!(
+        positions[0],
+        [8.0, 8.0, 4.0],
+        "chunk positions are in map space"
+    );
+}
+
+#[test]
+fn chunked_normals_match_full_build() {
+    let mut map = HeightMap::new(9, 9, 1.0);
+    for z in 0..9 {
+        for x in 0..9 {
+            map.set(x, z, ((x * 7 + z * 3) % 5) as f32 * 0.4);
+        }
+    }
+    for method in [NormalMethod::AreaWeighted, NormalMethod::Sobel] {
+        let builder = HeightMapMeshBuilder::new().with_normal_method(method);
+        let full = builder.build(&map);
+        let full_normals = full
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        for (coord, chunk) in builder.build_chunked(&map, 4) {
+            let normals = chunk
+                .attribute(Mesh::ATTRIBUTE_NORMAL)
+                .unwrap()
+                .as_float3()
+                .unwrap();
+            let (x0, z0) = (coord.x as usize * 4, coord.y as usize * 4);
+            for (i, n) in normals.iter().enumerate() {
+                let (x, z) = (x0 + i % 5, z0 + i / 5);
+                assert_eq!(*n, full_normals[z * 9 + x], "{method:?} vertex ({x}, {z})");
+            }
+        }
+    }
+}
+
+fn noisy_map(w: usize, h: usize) -> HeightMap {
+    let mut map = HeightMap::new(w, h, 1.0);
+    let mut state = 0x2545_f491_u32;
+    for z in 0..h {
+        for x in 0..w {
+            // xorshift32 — deterministic pseudo-random heights
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            map.set(x, z, (state % 1000) as f32 / 1000.0);
+        }
+    }
+    map
+}
+
+fn normal_variance(mesh: &Mesh) -> f32 {
+    let normals = mesh
+        .attribute(Mesh::ATTRIBUTE_NORMAL)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let mean: Vec3 = normals.iter().map(|n| Vec3::from(*n)).sum::<Vec3>() / normals.len() as f32;
+    normals
+        .iter()
+        .map(|n| (Vec3::from(*n) - mean).length_squared())
+        .sum::<f32>()
+        / normals.len() as f32
+}
+
+#[test]
+fn normal_smoothing_reduces_variance() {
+    let map = noisy_map(24, 24);
+    let v0 = normal_variance(&HeightMapMeshBuilder::new().build(&map));
+    let v1 = normal_variance(
+        &HeightMapMeshBuilder::new()
+            .with_normal_smoothing(1)
+            .build(&map),
+    );
+    let v3 = normal_variance(
+        &HeightMapMeshBuilder::new()
+            .with_normal_smoothing(3)
+            .build(&map),
+    );
+    assert!(
+        v1 < v0,
+        "radius 1 ({v1}) should be smoother than radius 0 ({v0})"
+    );
+    assert!(
+        v3 < v1,
+        "radius 3 ({v3}) should be smoother than radius 1 ({v1})"
+    );
+}
+
+#[test]
+fn normal_smoothing_zero_is_unchanged() {
+    let map = noisy_map(8, 8);
+    let plain = HeightMapMeshBuilder::new().build(&map);
+    let smoothed = HeightMapMeshBuilder::new()
+        .with_normal_smoothing(0)
+        .build(&map);
+    assert_eq!(
+        plain.attribute(Mesh::ATTRIBUTE_NORMAL).unwrap().as_float3(),
+        smoothed
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .unwrap()
+            .as_float3()
+    );
+}
+
+/// Large flat plain with a small bump near one corner.
+fn plain_with_bump() -> HeightMap {
+    let mut map = HeightMap::new(17, 17, 1.0);
+    map.set(3, 3, 2.0);
+    map.set(4, 3, 1.0);
+    map
+}
+
+#[test]
+fn flat_merge_reduces_triangles() {
+    let map = plain_with_bump();
+    let uniform = HeightMapMeshBuilder::new().build(&map);
+    let merged = HeightMapMeshBuilder::new()
+        .with_flat_merge(1e-4)
+        .build(&map);
+    let uniform_tris = uniform.indices().unwrap().len() / 3;
+    let merged_tris = merged.indices().unwrap().len() / 3;
+    assert!(
+        merged_tris < uniform_tris / 2,
+        "merged {merged_tris} should be far fewer than {uniform_tris}"
+    );
+    assert!(merged.count_vertices() < uniform.count_vertices());
+}
+
+#[test]
+fn flat_merge_minimum_block_saves_triangles() {
+    // 4×4 vertices = 3×3 cells: 12 fan triangles instead of 18.
+    let map = HeightMap::new(4, 4, 1.0);
+    let merged = HeightMapMeshBuilder::new()
+        .with_flat_merge(1e-4)
+        .build(&map);
+    assert_eq!(merged.indices().unwrap().len() / 3, 12);
+
+    // A 2×k strip merges too: 2×3 cells give 10 instead of 12, and 2×8
+    // cells give 20 instead of 32.
+    for (depth, expected) in [(4, 10), (9, 20)] {
+        let map = HeightMap::new(3, depth, 1.0);
+        let merged = HeightMapMeshBuilder::new()
+            .with_flat_merge(1e-4)
+            .build(&map);
+        assert_eq!(merged.indices().unwrap().len() / 3, expected);
+    }
+
+    // A 2×2 block would fan into as many triangles as its cells, so it is
+    // left as a regular grid.
+    let map = HeightMap::new(3, 3, 1.0);
+    let merged = HeightMapMeshBuilder::new()
+        .with_flat_merge(1e-4)
+        .build(&map);
+    assert_eq!(merged.indices().unwrap().len() / 3, 8);
+    assert_eq!(merged.count_vertices(), 9);
+}
+
+#[test]
+fn flat_merge_is_watertight() {
+    let map = plain_with_bump();
+    let mesh = HeightMapMeshBuilder::new()
+        .with_flat_merge(1e-4)
+        .build(&map);
+    let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+
+    // Every interior edge must be shared by exactly two triangles with
+    // opposite directions; boundary edges lie on the map border.
+    let mut edges = std::collections::HashMap::new();
+    for tri in indices.chunks_exact(3) {
+        for (a, b) in [(tri[0], tri[1]), (tri[1], tri[2]), (tri[2], tri[0])] {
+            *edges.entry((a, b)).or_insert(0) += 1;
+        }
+    }
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    for (&(a, b), &count) in &edges {
+        assert_eq!(
+            count, 1,
+            "edge ({a}, {b}) emitted twice in the same direction"
+        );
+        if !edges.contains_key(&(b, a)) {
+            let (pa, pb) = (positions[a], positions[b]);
+            let on_border =
+                |c: usize| (pa[c] == 0.0 && pb[c] == 0.0) || (pa[c] == 16.0 && pb[c] == 16.0);
+            assert!(on_border(0) || on_border(2), "open edge {pa:?} → {pb:?}");
+        }
+    }
+
+    // Total projected area must still cover the whole map.
+    let area: f32 = indices
+        .chunks_exact(3)
+        .map(|t| {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| Vec2::new(positions[i][0], positions[i][2]));
+            (b - a).perp_dot(c - a).abs() * 0.5
+        })
+        .sum();
+    assert!((area - 256.0).abs() < 1e-3, "covered area {area}");
+}
+
+/// RMS angular-ish error of mesh normals against `f(x, z) -> normal` in f64.
+fn normal_rms_error(mesh: &Mesh, w: usize, reference: impl Fn(usize, usize) -> DVec3) -> f64 {
+    let normals = mesh
+        .attribute(Mesh::ATTRIBUTE_NORMAL)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let sum: f64 = normals
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (Vec3::from(*n).as_dvec3() - reference(i % w, i / w)).length_squared())
+        .sum();
+    (sum / normals.len() as f64).sqrt()
+}
+
+#[test]
+fn high_precision_normals_reduce_error() {
+    // Large map with a fractional scale that f32 cannot represent exactly, and
+    // a gentle long-wavelength sinusoid (f64 reference computed analytically).
+    let n = 1024;
+    let scale = 0.37_f64;
+    let (amp, k) = (3.0_f64, 0.004_f64);
+    let mut map = HeightMap::new(n, n, scale as f32);
+    for z in 0..n {
+        for x in 0..n {
+            let (wx, wz) = (x as f64 * scale, z as f64 * scale);
+            map.set(x, z, (amp * (k * wx).sin() * (k * wz).cos()) as f32);
+        }
+    }
+    let reference = |x: usize, z: usize| {
+        let (wx, wz) = (x as f64 * scale, z as f64 * scale);
+        let dhdx = amp * k * (k * wx).cos() * (k * wz).cos();
+        let dhdz = -amp * k * (k * wx).sin() * (k * wz).sin();
+        DVec3::new(-dhdx, 1.0, -dhdz).normalize()
+    };
+
+    let low = HeightMapMeshBuilder::new().build(&map);
+    let high = HeightMapMeshBuilder::new()
+        .with_high_precision_normals(true)
+        .build(&map);
+    let low_err = normal_rms_error(&low, n, reference);
+    let high_err = normal_rms_error(&high, n, reference);
+    assert!(
+        high_err < low_err,
+        "f64 error {high_err:e} should be below f32 error {low_err:e}"
+    );
+}
+
+fn has_zero_area_triangle(mesh: &Mesh) -> bool {
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+    indices.chunks_exact(3).any(|t| {
+        let a = Vec3::from(positions[t[0]]);
+        let e1 = Vec3::from(positions[t[1]]) - a;
+        let e2 = Vec3::from(positions[t[2]]) - a;
+        e1.cross(e2).length() <= 1e-6 * e1.length() * e2.length()
+    })
+}
+
+#[test]
+fn weld_and_clean_drops_triangles_at_coincident_vertex() {
+    use bevy::mesh::VertexAttributeValues;
+    use bevy_symbios_ground::weld_and_clean;
+
+    let hm = ramp_map(3, 3, 1.0);
+    let mut mesh = HeightMapMeshBuilder::new().build(&hm);
+    let before = mesh.indices().unwrap().len();
+
+    // Collapse the centre vertex onto the top-left corner, so the cell
+    // between them degenerates.
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("missing positions");
+    };
+    positions[4] = positions[0];
+    assert!(has_zero_area_triangle(&mesh));
+
+    let removed = weld_and_clean(&mut mesh, true);
+
+    assert!(removed > 0);
+    assert_eq!(mesh.indices().unwrap().len(), before - removed * 3);
+    assert_eq!(
+        mesh.count_vertices(),
+        8,
+        "coincident vertices should be welded"
+    );
+    assert!(!has_zero_area_triangle(&mesh));
+}
+
+#[test]
+fn weld_and_clean_removes_degenerate_cells_from_builder() {
+    // At a vanishingly small grid scale the flat cells' cross products
+    // underflow to zero, leaving collapsed triangles in the raw grid.
+    let mut hm = HeightMap::new(3, 3, 1e-30);
+    hm.set(1, 1, 2.0);
+    let raw = HeightMapMeshBuilder::new().build(&hm);
+    let cleaned = HeightMapMeshBuilder::new()
+        .with_weld_and_clean(false)
+        .build(&hm);
+
+    assert!(cleaned.indices().unwrap().len() < raw.indices().unwrap().len());
+    assert!(!has_zero_area_triangle(&cleaned));
+}
+
+#[test]
+fn weld_and_clean_keeps_regular_grid_intact() {
+    let hm = ramp_map(6, 5, 1.0);
+    let raw = HeightMapMeshBuilder::new().build(&hm);
+    let cleaned = HeightMapMeshBuilder::new()
+        .with_weld_and_clean(true)
+        .build(&hm);
+
+    assert_eq!(
+        cleaned.indices().unwrap().len(),
+        raw.indices().unwrap().len()
+    );
+    assert_eq!(cleaned.count_vertices(), raw.count_vertices());
+}
+
+#[test]
+fn flat_chunk_bounding_sphere_matches_footprint() {
+    let map = flat_map(10, 7, 2.0);
+    let chunks = HeightMapMeshBuilder::new().build_chunked_with_bounds(&map, 4);
+    assert_eq!(chunks.len(), 6);
+
+    // Chunk (1, 0) spans vertices [4, 8] × [0, 4]: 8×8 world units.
+    let (coord, _, sphere) = &chunks[1];
+    assert_eq!(*coord, IVec2::new(1, 0));
+    assert!((sphere.center - Vec3::new(12.0, 0.0, 4.0)).length() < 1e-5);
+    assert!((sphere.radius - (8.0f32 * 8.0 * 2.0).sqrt() / 2.0).abs() < 1e-5);
+
+    // Last chunk [8, 9] × [4, 6]: 2×4 world units.
+    let (_, _, sphere) = &chunks[5];
+    assert!((sphere.center - Vec3::new(17.0, 0.0, 10.0)).length() < 1e-5);
+    assert!((sphere.radius - (2.0f32 * 2.0 + 4.0 * 4.0).sqrt() / 2.0).abs() < 1e-5);
+}
+
+#[test]
+fn chunk_bounding_sphere_encloses_vertices() {
+    let map = ramp_map(9, 9, 1.5);
+    for (_, mesh, sphere) in HeightMapMeshBuilder::new().build_chunked_with_bounds(&map, 3) {
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        for p in positions {
+            assert!(Vec3::from(*p).distance(sphere.center) <= sphere.radius + 1e-4);
+        }
+    }
+}
+
+fn uvs(mesh: &Mesh) -> Vec<[f32; 2]> {
+    match mesh.attribute(Mesh::ATTRIBUTE_UV_0).unwrap() {
+        bevy::mesh::VertexAttributeValues::Float32x2(v) => v.clone(),
+        other => panic!("unexpected UV format {other:?}"),
+    }
+}
+
+#[test]
+fn identity_uv_transform_matches_tile_size() {
+    let map = ramp_map(5, 4, 1.5);
+    let plain = HeightMapMeshBuilder::new()
+        .with_uv_tile_size(3.0)
+        .build(&map);
+    let identity = HeightMapMeshBuilder::new()
+        .with_uv_tile_size(3.0)
+        .with_uv_transform(Mat2::IDENTITY, Vec2::ZERO)
+        .build(&map);
+    assert_eq!(uvs(&plain), uvs(&identity));
+}
+
+#[test]
+fn quarter_turn_uv_transform_swaps_components() {
+    let map = ramp_map(5, 4, 1.5);
+    let plain = HeightMapMeshBuilder::new().build(&map);
+    // Reflecting across u = v exchanges the components exactly.
+    let swap = Mat2::from_cols(Vec2::Y, Vec2::X);
+    let rotated = HeightMapMeshBuilder::new()
+        .with_uv_transform(Mat2::from_angle(std::f32::consts::FRAC_PI_2), Vec2::ZERO)
+        .build(&map);
+    let swapped = HeightMapMeshBuilder::new()
+        .with_uv_transform(swap, Vec2::ZERO)
+        .build(&map);
+
+    for ((p, r), s) in uvs(&plain).iter().zip(uvs(&rotated)).zip(uvs(&swapped)) {
+        // A 90° rotation maps (u, v) to (-v, u).
+        assert!((r[0] + p[1]).abs() < 1e-5 && (r[1] - p[0]).abs() < 1e-5);
+        assert_eq!(s, [p[1], p[0]]);
+    }
+}
+
+fn sculpt(map: &mut HeightMap, mesh: &mut Mesh) {
+    use bevy::mesh::VertexAttributeValues;
+
+    for (x, z, dh) in [(3, 2, 1.5), (4, 2, 0.75), (3, 3, -0.5)] {
+        map.set(x, z, map.get(x, z) + dh);
+    }
+    let s = map.scale();
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("missing positions");
+    };
+    for p in positions.iter_mut() {
+        p[1] = map.get((p[0] / s).round() as usize, (p[2] / s).round() as usize);
+    }
+}
+
+fn normals(mesh: &Mesh) -> Vec<[f32; 3]> {
+    mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        .unwrap()
+        .as_float3()
+        .unwrap()
+        .to_vec()
+}
+
+#[test]
+fn update_normals_matches_full_rebuild() {
+    use bevy_symbios_ground::update_normals;
+
+    for method in [NormalMethod::AreaWeighted, NormalMethod::Sobel] {
+        let mut map = ramp_map(8, 7, 0.5);
+        let builder = HeightMapMeshBuilder::new().with_normal_method(method);
+        let mut mesh = builder.build(&map);
+
+        sculpt(&mut map, &mut mesh);
+        let patched = mesh.attribute(Mesh::ATTRIBUTE_POSITION).cloned();
+        update_normals(&mut mesh, &map, method);
+
+        assert_eq!(
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+                .map(|p| p.get_bytes().to_vec()),
+            patched.map(|p| p.get_bytes().to_vec()),
+            "positions must be untouched"
+        );
+        assert_eq!(normals(&mesh), normals(&builder.build(&map)));
+    }
+
+    let subdivided = HeightMapMeshBuilder::new().with_subdivisions(2);
+    let mut map = ramp_map(6, 5, 0.5);
+    let mut mesh = subdivided.build(&map);
+    map.set(3, 2, 1.5);
+    map.set(2, 3, -0.75);
+    subdivided.update_normals(&mut mesh, &map);
+    assert_eq!(normals(&mesh), normals(&subdivided.build(&map)));
+
+    use bevy::mesh::VertexAttributeValues;
+    use bevy_symbios_ground::{ATTRIBUTE_NORMAL_OCT16, NormalEncoding};
+
+    let packed = HeightMapMeshBuilder::new().with_normal_encoding(NormalEncoding::Oct16);
+    let mut map = ramp_map(8, 7, 0.5);
+    let mut mesh = packed.build(&map);
+    sculpt(&mut map, &mut mesh);
+    packed.update_normals(&mut mesh, &map);
+    assert!(
+        mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_none(),
+        "packed meshes must not gain a float normal attribute"
+    );
+    let oct16 = |mesh: &Mesh| match mesh.attribute(ATTRIBUTE_NORMAL_OCT16) {
+        Some(VertexAttributeValues::Snorm16x2(encoded)) => encoded.clone(),
+        _ => panic!("missing packed normals"),
+    };
+    assert_eq!(oct16(&mesh), oct16(&packed.build(&map)));
+}
+
+#[test]
+fn update_normals_with_chunk_frame_matches_rebuild() {
+    let mut map = ramp_map(9, 9, 1.0);
+    let builder = HeightMapMeshBuilder::new().with_chunk_frame(Vec2::new(4.0, 2.0));
+    let mut mesh = builder.build(&map);
+
+    for (x, z, dh) in [(3, 2, 1.5), (4, 2, 0.75), (3, 3, -0.5)] {
+        map.set(x, z, map.get(x, z) + dh);
+    }
+    builder.update_normals(&mut mesh, &map);
+    assert_eq!(normals(&mesh), normals(&builder.build(&map)));
+}
+
+#[test]
+fn update_normals_on_chunk_matches_chunked_rebuild() {
+    let mut map = ramp_map(9, 9, 1.0);
+    let builder = HeightMapMeshBuilder::new().with_normal_smoothing(1);
+    let mut chunk = builder.build_chunked(&map, 4).swap_remove(0).1;
+
+    sculpt(&mut map, &mut chunk);
+    builder.update_normals(&mut chunk, &map);
+
+    let rebuilt = builder.build_chunked(&map, 4).swap_remove(0).1;
+    assert_eq!(normals(&chunk), normals(&rebuilt));
+}
+
+#[test]
+fn recomputed_tangents_follow_world_x() {
+    use bevy_symbios_ground::recompute_tangents;
+
+    let mut map = ramp_map(6, 6, 1.0);
+    map.set(2, 3, 1.2);
+    let mut mesh = HeightMapMeshBuilder::new().build(&map);
+    recompute_tangents(&mut mesh).unwrap();
+
+    let Some(bevy::mesh::VertexAttributeValues::Float32x4(tangents)) =
+        mesh.attribute(Mesh::ATTRIBUTE_TANGENT)
+    else {
+        panic!("missing tangents");
+    };
+    let normals = normals(&mesh);
+    assert_eq!(tangents.len(), normals.len());
+    for (t, n) in tangents.iter().zip(&normals) {
+        let tangent = Vec3::new(t[0], t[1], t[2]);
+        assert!((tangent.length() - 1.0).abs() < 1e-4);
+        assert!(tangent.dot(Vec3::from(*n)).abs() < 1e-4);
+        // U increases with world X, so tangents lean towards +X.
+        assert!(tangent.dot(Vec3::X) > 0.5, "tangent {tangent} not along +X");
+        assert!(t[3] == 1.0 || t[3] == -1.0);
+    }
+}
+
+#[test]
+fn recompute_tangents_reports_missing_uvs() {
+    use bevy_symbios_ground::{TangentError, recompute_tangents};
+
+    let mut mesh = HeightMapMeshBuilder::new().build(&flat_map(3, 3, 1.0));
+    mesh.remove_attribute(Mesh::ATTRIBUTE_UV_0);
+    assert_eq!(
+        recompute_tangents(&mut mesh),
+        Err(TangentError::MissingAttribute("UV_0"))
+    );
+    assert!(mesh.attribute(Mesh::ATTRIBUTE_TANGENT).is_none());
+}
+
+#[test]
+fn nan_policy_replace_sanitizes_mesh() {
+    use bevy_symbios_ground::NanPolicy;
+
+    let mut map = ramp_map(5, 5, 1.0);
+    map.set(2, 2, f32::NAN);
+    map.set(4, 1, f32::INFINITY);
+
+    let raw = HeightMapMeshBuilder::new().build(&map);
+    let positions = raw
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert!(positions.iter().flatten().any(|v| !v.is_finite()));
+
+    let mesh = HeightMapMeshBuilder::new()
+        .with_nan_policy(NanPolicy::ReplaceWith(0.0))
+        .build(&map);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert!(positions.iter().flatten().all(|v| v.is_finite()));
+    assert!(normals(&mesh).iter().flatten().all(|v| v.is_finite()));
+    assert_eq!(positions[2 * 5 + 2][1], 0.0);
+    assert_eq!(
+        positions[3][1],
+        map.get(3, 0),
+        "finite heights are untouched"
+    );
+}
+
+#[test]
+fn nan_policy_clamp_bounds_heights() {
+    use bevy_symbios_ground::NanPolicy;
+
+    let mut map = ramp_map(4, 4, 1.0);
+    map.set(1, 1, f32::NAN);
+    map.set(2, 1, f32::NEG_INFINITY);
+    let mesh = HeightMapMeshBuilder::new()
+        .with_nan_policy(NanPolicy::Clamp(-1.0, 2.0))
+        .build(&map);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert!(positions.iter().all(|p| (-1.0..=2.0).contains(&p[1])));
+    assert_eq!(positions[4 + 1][1], -1.0);
+    assert_eq!(positions[4 + 2][1], -1.0);
+    assert_eq!(positions[3][1], 2.0);
+}
+
+fn mesh_height(mesh: &Mesh, w: usize, x: usize, z: usize) -> f32 {
+    mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap()[z * w + x][1]
+}
+
+#[test]
+fn full_geomorph_matches_coarse_surface() {
+    let mut map = HeightMap::new(7, 7, 1.0);
+    for z in 0..7 {
+        for x in 0..7 {
+            map.set(x, z, ((x * 7 + z * 3) % 5) as f32);
+        }
+    }
+    let mesh = HeightMapMeshBuilder::new().with_geomorph(1.0).build(&map);
+    let at = |x, z| mesh_height(&mesh, 7, x, z);
+
+    for z in 0..7 {
+        for x in 0..7 {
+            let expected = match (x % 2, z % 2) {
+                (0, 0) => map.get(x, z),
+                (1, 0) => 0.5 * (map.get(x - 1, z) + map.get(x + 1, z)),
+                (0, 1) => 0.5 * (map.get(x, z - 1) + map.get(x, z + 1)),
+                _ => 0.5 * (map.get(x + 1, z - 1) + map.get(x - 1, z + 1)),
+            };
+            assert!((at(x, z) - expected).abs() < 1e-6, "vertex ({x}, {z})");
+        }
+    }
+}
+
+#[test]
+fn zero_geomorph_keeps_full_detail() {
+    let mut bumpy = ramp_map(5, 5, 1.0);
+    bumpy.set(1, 1, 3.0);
+    let mesh = HeightMapMeshBuilder::new().with_geomorph(0.0).build(&bumpy);
+    assert_eq!(mesh_height(&mesh, 5, 1, 1), 3.0);
+
+    let half = HeightMapMeshBuilder::new().with_geomorph(0.5).build(&bumpy);
+    let coarse = 0.5 * (bumpy.get(2, 0) + bumpy.get(0, 2));
+    assert!((mesh_height(&half, 5, 1, 1) - 0.5 * (3.0 + coarse)).abs() < 1e-6);
+}
+
+#[test]
+fn selector_picks_normal_method_per_chunk() {
+    use std::cell::RefCell;
+
+    let mut map = ramp_map(9, 9, 1.0);
+    for z in 0..9 {
+        for x in 0..9 {
+            map.set(x, z, ((x * 5 + z * 3) % 4) as f32 * 0.7);
+        }
+    }
+    let calls = RefCell::new(Vec::new());
+    let pick = |c: IVec2| {
+        calls.borrow_mut().push(c);
+        if c == IVec2::ZERO {
+            NormalMethod::AreaWeighted
+        } else {
+            NormalMethod::Sobel
+        }
+    };
+    let chunks = HeightMapMeshBuilder::new().build_chunked_with_selector(&map, 4, &pick);
+
+    let coords: Vec<IVec2> = chunks.iter().map(|(c, _)| *c).collect();
+    assert_eq!(*calls.borrow(), coords, "selector runs once per chunk");
+
+    let area = HeightMapMeshBuilder::new().build_chunked(&map, 4);
+    let sobel = HeightMapMeshBuilder::new()
+        .with_normal_method(NormalMethod::Sobel)
+        .build_chunked(&map, 4);
+    for (i, (coord, mesh)) in chunks.iter().enumerate() {
+        let expected = if *coord == IVec2::ZERO {
+            &area[i].1
+        } else {
+            &sobel[i].1
+        };
+        assert_eq!(normals(mesh), normals(expected), "chunk {coord}");
+    }
+    assert_ne!(normals(&area[1].1), normals(&sobel[1].1));
+}
+
+/// Expands a mesh's index buffer into CCW triangles, skipping degenerates.
+fn triangles(mesh: &Mesh) -> Vec<[usize; 3]> {
+    use bevy::mesh::PrimitiveTopology;
+
+    let idx: Vec<usize> = mesh.indices().unwrap().iter().collect();
+    match mesh.primitive_topology() {
+        PrimitiveTopology::TriangleList => {
+            idx.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect()
+        }
+        PrimitiveTopology::TriangleStrip => (0..idx.len() - 2)
+            .map(|i| {
+                if i % 2 == 0 {
+                    [idx[i], idx[i + 1], idx[i + 2]]
+                } else {
+                    [idx[i + 1], idx[i], idx[i + 2]]
+                }
+            })
+            .filter(|t| t[0] != t[1] && t[1] != t[2] && t[0] != t[2])
+            .collect(),
+        other => panic!("unexpected topology {other:?}"),
+    }
+}
+
+/// Rotates a triangle so its smallest index comes first, preserving winding.
+fn canonical(t: [usize; 3]) -> [usize; 3] {
+    let min = (0..3).min_by_key(|&i| t[i]).unwrap();
+    [t[min], t[(min + 1) % 3], t[(min + 2) % 3]]
+}
+
+#[test]
+fn strip_topology_matches_list_surface() {
+    use bevy::mesh::PrimitiveTopology;
+    use bevy_symbios_ground::{QuadDiagonal, Topology};
+
+    let map = ramp_map(5, 4, 1.0);
+    for diagonal in [
+        QuadDiagonal::TopRightBottomLeft,
+        QuadDiagonal::TopLeftBottomRight,
+    ] {
+        let builder = HeightMapMeshBuilder::new().with_diagonal(diagonal);
+        let list = builder.clone().build(&map);
+        let strip = builder.with_topology(Topology::Strip).build(&map);
+        assert_eq!(strip.primitive_topology(), PrimitiveTopology::TriangleStrip);
+        assert!(strip.indices().unwrap().len() < list.indices().unwrap().len());
+        assert_eq!(normals(&strip), normals(&list));
+
+        let mut expected: Vec<_> = triangles(&list).into_iter().map(canonical).collect();
+        let mut actual: Vec<_> = triangles(&strip).into_iter().map(canonical).collect();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected, "{diagonal:?}");
+    }
+}
+
+#[test]
+fn custom_attribute_receives_grid_and_height() {
+    use bevy::mesh::{MeshVertexAttribute, VertexAttributeValues, VertexFormat};
+
+    const ATTRIBUTE_HEIGHT_TWICE: MeshVertexAttribute =
+        MeshVertexAttribute::new("HeightTwice", 611_843_201, VertexFormat::Float32x2);
+    const ATTRIBUTE_GRID: MeshVertexAttribute =
+        MeshVertexAttribute::new("Grid", 611_843_202, VertexFormat::Float32);
+
+    let map = ramp_map(5, 4, 0.5);
+    let mesh = HeightMapMeshBuilder::new()
+        .with_custom_attribute(ATTRIBUTE_HEIGHT_TWICE, |_, _, h| [h, h])
+        .with_custom_attribute(ATTRIBUTE_GRID, |x, z, _| [(z * 10 + x) as f32])
+        .build(&map);
+
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let Some(VertexAttributeValues::Float32x2(twice)) = mesh.attribute(ATTRIBUTE_HEIGHT_TWICE)
+    else {
+        panic!("missing custom attribute");
+    };
+    assert_eq!(twice.len(), positions.len());
+    for (p, v) in positions.iter().zip(twice) {
+        assert_eq!(*v, [p[1], p[1]]);
+    }
+
+    let Some(VertexAttributeValues::Float32(grid)) = mesh.attribute(ATTRIBUTE_GRID) else {
+        panic!("missing custom attribute");
+    };
+    assert_eq!(grid[2 * 5 + 3], 23.0);
+}
+
+#[test]
+#[should_panic(expected = "format")]
+fn custom_attribute_rejects_mismatched_format() {
+    use bevy::mesh::{MeshVertexAttribute, VertexFormat};
+
+    const ATTRIBUTE: MeshVertexAttribute =
+        MeshVertexAttribute::new("Mismatch", 611_843_203, VertexFormat::Float32x3);
+    let _ = HeightMapMeshBuilder::new().with_custom_attribute(ATTRIBUTE, |_, _, h| [h]);
+}
+
+#[test]
+fn hole_mask_omits_cell_triangles() {
+    let map = ramp_map(5, 5, 1.0);
+    let mut holes = vec![false; 16];
+    holes[5] = true; // cell (1, 1)
+    holes[6] = true; // cell (2, 1)
+
+    for builder in [
+        HeightMapMeshBuilder::new(),
+        HeightMapMeshBuilder::new().with_flat_merge(0.0),
+    ] {
+        let mesh = builder.with_hole_mask(holes.clone()).build(&map);
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        for t in triangles(&mesh) {
+            let c = t.iter().map(|&i| Vec3::from(positions[i])).sum::<Vec3>() / 3.0;
+            assert!(
+                !((1.0..3.0).contains(&c.x) && (1.0..2.0).contains(&c.z)),
+                "triangle over hole at {c}"
+            );
+        }
+        assert_eq!(triangles(&mesh).len(), (16 - 2) * 2);
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_chunks_match_serial() {
+    let mut map = ramp_map(33, 25, 1.0);
+    for z in 0..25 {
+        for x in 0..33 {
+            map.set(x, z, map.get(x, z) + ((x * z) as f32 * 0.3).sin());
+        }
+    }
+    let builder = HeightMapMeshBuilder::new().with_normal_method(NormalMethod::Sobel);
+    let serial = builder.build_chunked(&map, 8);
+    let parallel = builder.build_chunked_parallel(&map, 8);
+
+    assert_eq!(serial.len(), parallel.len());
+    for ((sc, sm), (pc, pm)) in serial.iter().zip(&parallel) {
+        assert_eq!(sc, pc);
+        assert_eq!(
+            sm.attribute(Mesh::ATTRIBUTE_POSITION).unwrap().as_float3(),
+            pm.attribute(Mesh::ATTRIBUTE_POSITION).unwrap().as_float3()
+        );
+        assert_eq!(normals(sm), normals(pm));
+        assert_eq!(uvs(sm), uvs(pm));
+        let si: Vec<usize> = sm.indices().unwrap().iter().collect();
+        let pi: Vec<usize> = pm.indices().unwrap().iter().collect();
+        assert_eq!(si, pi);
+    }
+}
+
+#[test]
+fn f64_build_is_precise_near_floating_origin() {
+    let mut map = HeightMap::new(33, 33, 0.37);
+    for z in 0..33 {
+        for x in 0..33 {
+            map.set(
+                x,
+                z,
+                812.3 + (x as f32 * 0.4).sin() * (z as f32 * 0.3).cos(),
+            );
+        }
+    }
+    let placement = DVec3::new(4_718_592.123, -1_500.0, -3_145_728.987);
+    let origin = placement + DVec3::new(6.1, 790.0, 5.3);
+    let mesh = HeightMapMeshBuilder::new().build_f64(&map, placement, origin);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+
+    let s = map.scale() as f64;
+    let mut naive_error = 0.0_f64;
+    for z in 0..33 {
+        for x in 0..33 {
+            let world = placement + DVec3::new(x as f64 * s, map.get(x, z) as f64, z as f64 * s);
+            let expected = world - origin;
+            let actual = Vec3::from(positions[z * 33 + x]).as_dvec3();
+            assert!(
+                (actual - expected).length() < 1e-3,
+                "vertex ({x}, {z}): {actual} vs {expected}"
+            );
+            // Subtracting after an f32 round-trip loses the fraction.
+            let naive = (world.as_vec3() - origin.as_vec3()).as_dvec3();
+            naive_error = naive_error.max((naive - expected).length());
+        }
+    }
+    assert!(naive_error > 1e-2);
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics_callback_reports_each_build() {
+    use bevy_symbios_ground::BuildMetrics;
+    use std::sync::{Arc, Mutex};
+
+    let reports: Arc<Mutex<Vec<BuildMetrics>>> = Arc::default();
+    let sink = reports.clone();
+    let builder = HeightMapMeshBuilder::new()
+        .with_metrics_callback(move |metrics| sink.lock().unwrap().push(metrics));
+
+    let map = ramp_map(9, 5, 1.0);
+    builder.build(&map);
+    let reports_after_build = reports.lock().unwrap().clone();
+    assert_eq!(reports_after_build.len(), 1);
+    assert_eq!(reports_after_build[0].vertex_count, 9 * 5);
+    assert_eq!(reports_after_build[0].triangle_count, 8 * 4 * 2);
+
+    builder.build_chunked(&map, 4);
+    assert_eq!(reports.lock().unwrap().len(), 1 + 2);
+
+    let strip = builder.with_topology(bevy_symbios_ground::Topology::Strip);
+    strip.build(&map);
+    assert_eq!(reports.lock().unwrap()[3].triangle_count, 8 * 4 * 2);
+}
+
+#[test]
+fn shared_indices_match_generated() {
+    use bevy_symbios_ground::{QuadDiagonal, shared_index_buffer};
+
+    let shared = shared_index_buffer(3, 2, QuadDiagonal::TopRightBottomLeft);
+    // tl=0, tr=1, bl=3, br=4 for the first cell.
+    assert_eq!(shared.indices(), [0, 3, 1, 1, 3, 4, 1, 4, 2, 2, 4, 5]);
+    assert_eq!((shared.width(), shared.height()), (3, 2));
+    assert_eq!(shared.diagonal(), QuadDiagonal::TopRightBottomLeft);
+
+    let map = ramp_map(9, 9, 1.0);
+    for diagonal in [
+        QuadDiagonal::TopRightBottomLeft,
+        QuadDiagonal::TopLeftBottomRight,
+    ] {
+        let builder = HeightMapMeshBuilder::new().with_diagonal(diagonal);
+        let own = builder.clone().build(&map);
+        let reused = builder
+            .clone()
+            .with_shared_indices(shared_index_buffer(9, 9, diagonal))
+            .build(&map);
+        let indices = |m: &Mesh| m.indices().unwrap().iter().collect::<Vec<_>>();
+        assert_eq!(indices(&own), indices(&reused));
+        assert_eq!(normals(&own), normals(&reused));
+
+        // A buffer for 5×5 chunks also serves a 9×9 map split into 4-cell chunks.
+        let chunked = builder.clone().build_chunked(&map, 4);
+        let chunked_shared = builder
+            .with_shared_indices(shared_index_buffer(5, 5, diagonal))
+            .build_chunked(&map, 4);
+        for ((_, a), (_, b)) in chunked.iter().zip(&chunked_shared) {
+            assert_eq!(indices(a), indices(b));
+        }
+    }
+}
+
+#[test]
+fn shared_indices_are_used_verbatim() {
+    use bevy_symbios_ground::{QuadDiagonal, shared_index_buffer};
+
+    let map = ramp_map(4, 4, 1.0);
+    let builder = HeightMapMeshBuilder::new().with_diagonal(QuadDiagonal::TopLeftBottomRight);
+    let shared = shared_index_buffer(4, 4, QuadDiagonal::TopLeftBottomRight);
+    let mesh = builder
+        .clone()
+        .with_shared_indices(shared.clone())
+        .build(&map);
+    let indices = |m: &Mesh| {
+        m.indices()
+            .unwrap()
+            .iter()
+            .map(|i| i as u32)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(indices(&mesh), shared.indices());
+
+    // A buffer of the wrong size is ignored.
+    let mesh = builder
+        .clone()
+        .with_shared_indices(shared_index_buffer(3, 3, QuadDiagonal::TopLeftBottomRight))
+        .build(&map);
+    assert_eq!(mesh.indices().unwrap().len(), 3 * 3 * 6);
+
+    // So is a buffer built for the other diagonal.
+    let mesh = HeightMapMeshBuilder::new()
+        .with_shared_indices(shared)
+        .build(&map);
+    assert_eq!(
+        indices(&mesh),
+        indices(&HeightMapMeshBuilder::new().build(&map))
+    );
+}
+
+#[test]
+fn shared_indices_for_transposed_grid_are_ignored() {
+    use bevy_symbios_ground::{QuadDiagonal, shared_index_buffer};
+
+    // 5×3 and 3×5 grids have the same index count but different layouts.
+    let map = ramp_map(5, 3, 1.0);
+    let builder = HeightMapMeshBuilder::new();
+    let mesh = builder
+        .clone()
+        .with_shared_indices(shared_index_buffer(3, 5, QuadDiagonal::default()))
+        .build(&map);
+    let own = builder.build(&map);
+    let indices = |m: &Mesh| m.indices().unwrap().iter().collect::<Vec<_>>();
+    assert_eq!(indices(&mesh), indices(&own));
+    assert_eq!(normals(&mesh), normals(&own));
+}
+
+#[test]
+fn per_cell_uvs_span_each_quad() {
+    use bevy_symbios_ground::UvMode;
+    use std::collections::HashMap;
+
+    let map = ramp_map(5, 4, 2.0);
+    let mesh = HeightMapMeshBuilder::new()
+        .with_uv_tile_size(7.0)
+        .with_uv_mode(UvMode::PerCell)
+        .build(&map);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let uvs = uvs(&mesh);
+    let tris = triangles(&mesh);
+    assert_eq!(tris.len(), 4 * 3 * 2);
+    // Every triangle owns its vertices.
+    assert_eq!(positions.len(), tris.len() * 3);
+
+    let mut corners: HashMap<(i32, i32), Vec<[f32; 2]>> = HashMap::new();
+    for t in &tris {
+        let cell = t
+            .iter()
+            .map(|&i| (Vec3::from(positions[i]).xz() / 2.0).round().as_ivec2())
+            .reduce(IVec2::min)
+            .unwrap();
+        for &i in t {
+            let local = Vec3::from(positions[i]).xz() / 2.0 - cell.as_vec2();
+            assert_eq!(Vec2::from(uvs[i]), local);
+            corners.entry((cell.x, cell.y)).or_default().push(uvs[i]);
+        }
+    }
+    assert_eq!(corners.len(), 4 * 3);
+    for uvs in corners.values() {
+        for corner in [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]] {
+            assert!(uvs.contains(&corner), "cell is missing UV {corner:?}");
+        }
+    }
+}
+
+#[test]
+fn sharp_angle_splits_cliff_edges_only() {
+    // Plateau at 0 for x ≤ 4, cliff up to 6 at x = 5, plateau beyond.
+    let mut map = HeightMap::new(10, 6, 1.0);
+    for z in 0..6 {
+        for x in 0..10 {
+            map.set(x, z, if x >= 5 { 6.0 } else { 0.0 });
+        }
+    }
+    let smooth = HeightMapMeshBuilder::new().build(&map);
+    let sharp = HeightMapMeshBuilder::new()
+        .with_sharp_angle(45.0)
+        .build(&map);
+    assert_eq!(triangles(&smooth).len(), triangles(&sharp).len());
+
+    let positions = sharp
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let normals = normals(&sharp);
+    let copies = |x: f32, z: f32| -> Vec<Vec3> {
+        positions
+            .iter()
+            .zip(&normals)
+            .filter(|(p, _)| p[0] == x && p[2] == z)
+            .map(|(_, n)| Vec3::from(*n))
+            .collect()
+    };
+
+    // Both crease rows are split, with one upward and one cliff-facing normal.
+    for x in [4.0, 5.0] {
+        let split = copies(x, 2.0);
+        assert_eq!(split.len(), 2, "vertex ({x}, 2) should be split");
+        assert!(split.iter().any(|n| n.abs_diff_eq(Vec3::Y, 1e-5)));
+        assert!(split.iter().any(|n| n.x < -0.9), "{split:?}");
+    }
+    // Plateau vertices stay shared and smooth.
+    for x in [1.0, 2.0, 8.0] {
+        let shared = copies(x, 2.0);
+        assert_eq!(shared.len(), 1);
+        assert!(shared[0].abs_diff_eq(Vec3::Y, 1e-5));
+    }
+    assert_eq!(positions.len(), 10 * 6 + 2 * 6);
+}
+
+#[test]
+fn detail_normal_strength_endpoints() {
+    use bevy_symbios_ground::NormalField;
+
+    let mut field = NormalField::new(6, 5);
+    for (i, n) in field.data.iter_mut().enumerate() {
+        *n = Vec3::new((i as f32 * 0.7).sin(), 2.0, (i as f32 * 0.3).cos());
+    }
+
+    // Strength 0 leaves any terrain's normals untouched.
+    let map = ramp_map(6, 5, 1.0);
+    let plain = normals(&HeightMapMeshBuilder::new().build(&map));
+    let none = normals(
+        &HeightMapMeshBuilder::new()
+            .with_detail_normals(&field, 0.0)
+            .build(&map),
+    );
+    for (a, b) in plain.iter().zip(&none) {
+        assert!(Vec3::from(*a).abs_diff_eq(Vec3::from(*b), 1e-6));
+    }
+
+    // Strength 1 on flat terrain reproduces the renormalized detail.
+    let full = normals(
+        &HeightMapMeshBuilder::new()
+            .with_detail_normals(&field, 1.0)
+            .build(&flat_map(6, 5, 1.0)),
+    );
+    for (n, d) in full.iter().zip(&field.data) {
+        assert!(
+            Vec3::from(*n).abs_diff_eq(d.normalize(), 1e-5),
+            "{n:?} vs {d}"
+        );
+    }
+}
+
+#[test]
+fn wireframe_lists_each_edge_once() {
+    use bevy::mesh::PrimitiveTopology;
+    use std::collections::HashSet;
+
+    let (w, h) = (5, 4);
+    let map = ramp_map(w, h, 1.0);
+    let wire = HeightMapMeshBuilder::new().build_wireframe(&map);
+    assert_eq!(wire.primitive_topology(), PrimitiveTopology::LineList);
+
+    let indices: Vec<usize> = wire.indices().unwrap().iter().collect();
+    let edges = indices.len() / 2;
+    // Horizontal, vertical, and one diagonal per cell.
+    assert_eq!(edges, (w - 1) * h + w * (h - 1) + (w - 1) * (h - 1));
+    let unique: HashSet<(usize, usize)> = indices
+        .chunks_exact(2)
+        .map(|e| (e[0].min(e[1]), e[0].max(e[1])))
+        .collect();
+    assert_eq!(unique.len(), edges);
+    assert_eq!(wire.count_vertices(), w * h);
+}
+
+#[test]
+fn weld_seam_unifies_shared_edge_normals() {
+    use bevy_symbios_ground::{Edge, weld_seam};
+
+    // Two 5×5 patches of one surface, each built from its own heightmap,
+    // sharing the column x = 4 of the left patch / x = 0 of the right one.
+    let surface = |x: usize, z: usize| ((x * x + 3 * z) as f32 * 0.2).sin() * 2.0;
+    let patch = |x0: usize| {
+        let mut map = HeightMap::new(5, 5, 1.0);
+        for z in 0..5 {
+            for x in 0..5 {
+                map.set(x, z, surface(x0 + x, z));
+            }
+        }
+        map
+    };
+    let builder = HeightMapMeshBuilder::new();
+    let mut left = builder.build(&patch(0));
+    let mut right = builder.build(&patch(4));
+
+    let edge_normals = |mesh: &Mesh, x: f32| -> Vec<(i32, Vec3)> {
+        let positions = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        let mut edge: Vec<(i32, Vec3)> = positions
+            .iter()
+            .zip(normals(mesh))
+            .filter(|(p, _)| p[0] == x)
+            .map(|(p, n)| (p[2] as i32, Vec3::from(n)))
+            .collect();
+        edge.sort_by_key(|e| e.0);
+        edge
+    };
+    let differs = edge_normals(&left, 4.0)
+        .iter()
+        .zip(edge_normals(&right, 0.0))
+        .any(|(a, b)| !a.1.abs_diff_eq(b.1, 1e-3));
+    assert!(differs, "independent builds should disagree at the seam");
+
+    let welded = weld_seam(&mut left, Edge::MaxX, &mut right, Edge::MinX);
+    assert_eq!(welded, 5);
+    for (a, b) in edge_normals(&left, 4.0)
+        .iter()
+        .zip(edge_normals(&right, 0.0))
+    {
+        assert_eq!(a.0, b.0);
+        assert_eq!(a.1, b.1);
+        assert!((a.1.length() - 1.0).abs() < 1e-5);
+    }
+    assert_eq!(mesh_height(&left, 5, 4, 2), mesh_height(&right, 5, 0, 2));
+}
+
+#[test]
+fn triangle_budget_picks_finest_fitting_step() {
+    let map = ramp_map(65, 65, 1.0);
+    let builder = HeightMapMeshBuilder::new();
+
+    let (full, step) = builder.build_within_budget(&map, 2 * 64 * 64);
+    assert_eq!(step, 1);
+    assert_eq!(triangles(&full).len(), 2 * 64 * 64);
+
+    let mut last_step = 1;
+    for budget in [5000, 2000, 500, 100, 2] {
+        let (mesh, step) = builder.build_within_budget(&map, budget);
+        assert!(triangles(&mesh).len() <= budget, "budget {budget}");
+        assert!(step >= last_step, "tighter budget must not refine");
+        last_step = step;
+        // Same world space as a full-resolution build.
+        let max_x = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap()
+            .iter()
+            .map(|p| p[0])
+            .fold(0.0, f32::max);
+        assert!(max_x <= 64.0);
+    }
+    assert!(last_step > 1);
+    assert_eq!(builder.build_within_budget(&map, 5000).1, 2);
+}
+
+#[test]
+fn triangle_budget_step_matches_exhaustive_search() {
+    let map = ramp_map(23, 11, 1.0);
+    let builder = HeightMapMeshBuilder::new();
+    for budget in 0..=2 * 22 * 10 + 1 {
+        // The coarsest step still leaves one cell along the short axis.
+        let expected = (1..=10)
+            .find(|&s| 2 * (22 / s) * (10 / s) <= budget)
+            .unwrap_or(10);
+        assert_eq!(
+            builder.build_within_budget(&map, budget).1,
+            expected,
+            "budget {budget}"
+        );
+    }
+}
+
+#[test]
+fn triangle_budget_counts_subdivisions_sides_and_risers() {
+    let flat = HeightMap::new(65, 65, 1.0);
+    let builders = [
+        HeightMapMeshBuilder::new().with_subdivisions(1),
+        HeightMapMeshBuilder::new().with_double_sided(true),
+        HeightMapMeshBuilder::new()
+            .with_subdivisions(1)
+            .with_double_sided(true),
+    ];
+    for builder in builders {
+        let (mesh, step) = builder.build_within_budget(&flat, 2000);
+        let count = triangles(&mesh).len();
+        assert!(count <= 2000, "step {step} gave {count} triangles");
+        // The next finer step would not have fit.
+        let finer = builder.build(&bevy_symbios_ground::ops::downsample(&flat, step - 1));
+        assert!(triangles(&finer).len() > 2000);
+    }
+
+    // Terrace risers add triangles the grid estimate cannot see.
+    let steps = ramp_map(65, 65, 0.5);
+    let builder = HeightMapMeshBuilder::new().with_height_quantization(1.0);
+    for budget in [20_000, 5000, 2000] {
+        let (mesh, step) = builder.build_within_budget(&steps, budget);
+        let count = triangles(&mesh).len();
+        assert!(count <= budget, "budget {budget}: step {step} gave {count}");
+    }
+}
+
+#[test]
+fn subdivisions_insert_bilinear_vertices() {
+    let mut hm = HeightMap::new(2, 2, 2.0);
+    hm.set(0, 0, 1.0);
+    hm.set(1, 0, 3.0);
+    hm.set(0, 1, 5.0);
+    hm.set(1, 1, 11.0);
+    let mesh = HeightMapMeshBuilder::new().with_subdivisions(1).build(&hm);
+
+    assert_eq!(mesh.count_vertices(), 9);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let center = positions[4];
+    assert!((center[0] - 1.0).abs() < 1e-5 && (center[2] - 1.0).abs() < 1e-5);
+    assert!(
+        (center[1] - 5.0).abs() < 1e-5,
+        "center height {}",
+        center[1]
+    );
+    assert_eq!(triangles(&mesh).len(), 8);
+}
+
+#[test]
+fn build_region_matches_full_map_vertices() {
+    let mut map = HeightMap::new(5, 5, 2.0);
+    for z in 0..5 {
+        for x in 0..5 {
+            map.set(x, z, ((x * x + 3 * z) % 7) as f32);
+        }
+    }
+    let builder = HeightMapMeshBuilder::new();
+    let region = builder.build_region(&map, 1, 2, 3, 3);
+    let full = builder.build(&map);
+
+    assert_eq!(region.count_vertices(), 9);
+    let region_positions = region
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let full_positions = full
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    for z in 0..3 {
+        for x in 0..3 {
+            let p = region_positions[z * 3 + x];
+            assert_eq!(p[0], (x + 1) as f32 * 2.0);
+            assert_eq!(p[2], (z + 2) as f32 * 2.0);
+            assert_eq!(p, full_positions[(z + 2) * 5 + x + 1]);
+            assert_eq!(
+                normals(&region)[z * 3 + x],
+                normals(&full)[(z + 2) * 5 + x + 1]
+            );
+            assert_eq!(uvs(&region)[z * 3 + x], uvs(&full)[(z + 2) * 5 + x + 1]);
+        }
+    }
+}
+
+/// Average cache miss ratio (vertex transforms per triangle) of a list
+/// index buffer through a simulated `size`-entry LRU cache.
+fn acmr(indices: &[usize], size: usize) -> f32 {
+    let mut cache: Vec<usize> = Vec::new();
+    let mut misses = 0;
+    for &v in indices {
+        match cache.iter().position(|&c| c == v) {
+            Some(i) => {
+                cache.remove(i);
+            }
+            None => misses += 1,
+        }
+        cache.insert(0, v);
+        cache.truncate(size);
+    }
+    misses as f32 / (indices.len() / 3) as f32
+}
+
+#[test]
+fn cache_optimization_keeps_triangles_and_improves_reuse() {
+    let map = ramp_map(64, 64, 1.0);
+    let plain = HeightMapMeshBuilder::new().build(&map);
+    let optimized = HeightMapMeshBuilder::new()
+        .with_cache_optimization(true)
+        .build(&map);
+
+    assert_eq!(
+        plain
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3(),
+        optimized
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+    );
+    let mut before: Vec<_> = triangles(&plain).into_iter().map(canonical).collect();
+    let mut after: Vec<_> = triangles(&optimized).into_iter().map(canonical).collect();
+    before.sort();
+    after.sort();
+    assert_eq!(before, after);
+
+    let plain_indices: Vec<usize> = plain.indices().unwrap().iter().collect();
+    let optimized_indices: Vec<usize> = optimized.indices().unwrap().iter().collect();
+    assert!(acmr(&optimized_indices, 32) < acmr(&plain_indices, 32));
+}
+
+#[test]
+fn oct16_normals_decode_to_float_normals() {
+    use bevy::mesh::VertexAttributeValues;
+    use bevy_symbios_ground::{ATTRIBUTE_NORMAL_OCT16, NormalEncoding, decode_octahedral};
+
+    let mut map = HeightMap::new(9, 9, 1.0);
+    for z in 0..9 {
+        for x in 0..9 {
+            map.set(x, z, ((x * 7 + z * 3) % 5) as f32 * 1.5);
+        }
+    }
+    let float = HeightMapMeshBuilder::new().build(&map);
+    let packed = HeightMapMeshBuilder::new()
+        .with_normal_encoding(NormalEncoding::Oct16)
+        .build(&map);
+
+    assert!(packed.attribute(Mesh::ATTRIBUTE_NORMAL).is_none());
+    let Some(VertexAttributeValues::Snorm16x2(encoded)) = packed.attribute(ATTRIBUTE_NORMAL_OCT16)
+    else {
+        panic!("expected Snorm16x2 octahedral normals");
+    };
+    let max_error = 1f32.to_radians().cos();
+    for (e, n) in encoded.iter().zip(normals(&float)) {
+        let decoded = decode_octahedral(*e);
+        assert!(decoded.dot(Vec3::from(n)) > max_error, "{decoded} vs {n:?}");
+    }
+
+    // The lower hemisphere folds over the corners and round-trips too.
+    for n in [Vec3::NEG_Y, Vec3::new(0.3, -0.8, -0.5).normalize(), Vec3::X] {
+        let decoded = decode_octahedral(bevy_symbios_ground::encode_octahedral(n));
+        assert!(decoded.dot(n) > max_error, "{decoded} vs {n}");
+    }
+}
+
+#[test]
+fn overlay_uv_is_normalized_while_uv0_stays_tiled() {
+    let map = ramp_map(5, 9, 2.0);
+    let mesh = HeightMapMeshBuilder::new()
+        .with_uv_tile_size(4.0)
+        .with_overlay_uv(true)
+        .build(&map);
+
+    let overlay = match mesh.attribute(Mesh::ATTRIBUTE_UV_1).unwrap() {
+        bevy::mesh::VertexAttributeValues::Float32x2(v) => v.clone(),
+        other => panic!("unexpected UV_1 format {other:?}"),
+    };
+    let tiled = uvs(&mesh);
+    for z in 0..9 {
+        for x in 0..5 {
+            let i = z * 5 + x;
+            assert_eq!(overlay[i], [x as f32 / 4.0, z as f32 / 8.0]);
+            assert_eq!(tiled[i], [x as f32 * 2.0 / 4.0, z as f32 * 2.0 / 4.0]);
+        }
+    }
+    assert_eq!(overlay[0], [0.0, 0.0]);
+    assert_eq!(overlay[5 * 9 - 1], [1.0, 1.0]);
+    assert!(
+        HeightMapMeshBuilder::new()
+            .build(&map)
+            .attribute(Mesh::ATTRIBUTE_UV_1)
+            .is_none()
+    );
+}
+
+#[test]
+fn height_floor_culls_submerged_cells_and_keeps_shore_shared() {
+    // Land on the left, a deep trench in columns x >= 4.
+    let mut map = HeightMap::new(8, 6, 1.0);
+    for z in 0..6 {
+        for x in 0..8 {
+            map.set(x, z, if x >= 4 { -10.0 } else { 2.0 });
+        }
+    }
+    let mesh = HeightMapMeshBuilder::new()
+        .with_height_floor(0.0)
+        .build(&map);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+
+    // Cells x = 0..=3 straddle or sit above the floor; x = 4..=6 are culled.
+    assert_eq!(triangles(&mesh).len(), 4 * 5 * 2);
+    for tri in triangles(&mesh) {
+        assert!(tri.iter().any(|&i| positions[i][1] >= 0.0));
+    }
+    // Watertight: no two vertices share a position, so neighbouring cells
+    // (including the shoreline column) reference the same vertices.
+    let mut unique: Vec<[u32; 3]> = positions.iter().map(|p| p.map(f32::to_bits)).collect();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), positions.len());
+    assert_eq!(positions.len(), 5 * 6);
+
+    let snapped = HeightMapMeshBuilder::new()
+        .with_height_floor(0.0)
+        .with_height_floor_snap(true)
+        .build(&map);
+    let snapped_positions = snapped
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert!(snapped_positions.iter().all(|p| p[1] >= 0.0));
+}
+
+#[test]
+fn chunk_tree_root_encloses_every_leaf() {
+    use bevy::camera::primitives::MeshAabb;
+    use bevy_symbios_ground::ChunkNode;
+
+    let mut map = HeightMap::new(17, 17, 1.0);
+    for z in 0..17 {
+        for x in 0..17 {
+            map.set(x, z, ((x * 3 + z * 7) % 11) as f32);
+        }
+    }
+    let tree = HeightMapMeshBuilder::new().build_chunked_tree(&map, 4);
+
+    let leaves: Vec<_> = tree.leaves().collect();
+    assert_eq!(leaves.len(), 16);
+    let root = tree.root.aabb();
+    for (coord, mesh, aabb) in &leaves {
+        assert_eq!(Some(*aabb), mesh.compute_aabb(), "chunk {coord}");
+        assert!(Vec3::from(root.min()).cmple(aabb.min().into()).all());
+        assert!(Vec3::from(root.max()).cmpge(aabb.max().into()).all());
+    }
+    let ChunkNode::Internal { children, .. } = &tree.root else {
+        panic!("a 4×4 grid has an internal root");
+    };
+    assert_eq!(children.len(), 4);
+    for child in children {
+        let ChunkNode::Internal { children, .. } = child else {
+            panic!("each quadrant holds 2×2 chunks");
+        };
+        assert_eq!(children.len(), 4);
+    }
+}
+
+#[test]
+fn barycentric_gives_each_triangle_the_three_basis_vectors() {
+    let map = ramp_map(5, 4, 1.0);
+    let mesh = HeightMapMeshBuilder::new()
+        .with_barycentric(true)
+        .build(&map);
+
+    let barycentric = match mesh
+        .attribute(bevy_symbios_ground::ATTRIBUTE_BARYCENTRIC)
+        .unwrap()
+    {
+        bevy::mesh::VertexAttributeValues::Float32x3(v) => v.clone(),
+        other => panic!("unexpected barycentric format {other:?}"),
+    };
+    let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+    assert_eq!(indices.len(), 4 * 3 * 6);
+    // De-indexed: no vertex is shared between triangles.
+    assert_eq!(mesh.count_vertices(), indices.len());
+    for tri in indices.chunks_exact(3) {
+        let mut corners: Vec<[f32; 3]> = tri.iter().map(|&i| barycentric[i]).collect();
+        corners.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        assert_eq!(
+            corners,
+            vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        );
+    }
+}
+
+#[test]
+fn radial_mask_culls_outside_circle_and_tapers_band() {
+    let mut map = HeightMap::new(21, 21, 0.5);
+    for h in map.data_mut() {
+        *h = 4.0;
+    }
+    let (radius, falloff) = (2.0, 2.0);
+    let mesh = HeightMapMeshBuilder::new()
+        .with_radial_mask(radius, falloff)
+        .build(&map);
+
+    let center = Vec2::splat(5.0);
+    let mut banded = 0;
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    for p in positions {
+        let d = Vec2::new(p[0], p[2]).distance(center);
+        assert!(d <= radius + falloff + 1e-4, "vertex at distance {d}");
+        if d <= radius {
+            assert_eq!(p[1], 4.0);
+        } else {
+            assert!(p[1] < 4.0, "height {} at distance {d}", p[1]);
+            banded += 1;
+        }
+    }
+    assert!(banded > 0);
+    let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+    assert!(indices.len() < 20 * 20 * 6);
+}
+
+#[test]
+fn double_sided_doubles_indices_with_flipped_faces() {
+    let map = flat_map(4, 5, 1.0);
+    let single = HeightMapMeshBuilder::new().build(&map);
+    let double = HeightMapMeshBuilder::new()
+        .with_double_sided(true)
+        .build(&map);
+
+    let count = single.indices().unwrap().len();
+    assert_eq!(double.indices().unwrap().len(), 2 * count);
+    assert_eq!(double.count_vertices(), 2 * single.count_vertices());
+    let normals = normals(&double);
+    assert!(normals.contains(&[0.0, 1.0, 0.0]));
+    assert!(normals.contains(&[0.0, -1.0, 0.0]));
+
+    // Each back face's geometric normal points down.
+    let positions = double
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    for (i, [a, b, c]) in triangles(&double).into_iter().enumerate() {
+        let [a, b, c] = [a, b, c].map(|v| Vec3::from(positions[v]));
+        let face = (b - a).cross(c - a);
+        let expected = if i < count / 3 { 1.0 } else { -1.0 };
+        assert_eq!(face.y.signum(), expected, "triangle {i}");
+    }
+}
+
+#[test]
+fn lod_chain_builds_one_mesh_per_step_coarsening() {
+    let map = ramp_map(17, 17, 1.0);
+    let builder = HeightMapMeshBuilder::new();
+    let chain = builder.build_lod_chain(&map, &[1, 2, 4, 8]);
+    assert_eq!(chain.len(), 4);
+
+    let triangle_counts: Vec<usize> = chain
+        .iter()
+        .map(|m| m.indices().unwrap().len() / 3)
+        .collect();
+    assert_eq!(
+        triangle_counts,
+        vec![2 * 16 * 16, 2 * 8 * 8, 2 * 4 * 4, 2 * 2 * 2]
+    );
+    assert_eq!(triangle_counts.iter().min(), triangle_counts.last());
+    assert_eq!(
+        chain[0]
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3(),
+        builder
+            .build(&map)
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+    );
+}
+
+#[test]
+fn height_attribute_matches_grid_heights() {
+    let mut map = HeightMap::new(6, 5, 1.5);
+    for z in 0..5 {
+        for x in 0..6 {
+            map.set(x, z, (x * 7 + z * 3) as f32 * 0.1 - 1.0);
+        }
+    }
+    let mesh = HeightMapMeshBuilder::new()
+        .with_height_attribute(true)
+        .build(&map);
+
+    let heights = match mesh
+        .attribute(bevy_symbios_ground::ATTRIBUTE_HEIGHT)
+        .unwrap()
+    {
+        bevy::mesh::VertexAttributeValues::Float32(v) => v.clone(),
+        other => panic!("unexpected height format {other:?}"),
+    };
+    assert_eq!(heights.len(), 6 * 5);
+    for z in 0..5 {
+        for x in 0..6 {
+            assert_eq!(heights[z * 6 + x], map.get(x, z), "vertex ({x}, {z})");
+        }
+    }
+}
+
+#[test]
+fn ring_is_full_detail_inside_coarse_outside_and_watertight() {
+    use std::collections::HashMap;
+
+    let mut map = HeightMap::new(17, 13, 1.0);
+    for z in 0..13 {
+        for x in 0..17 {
+            map.set(x, z, ((x * 3 + z * 7) % 5) as f32 * 0.3);
+        }
+    }
+    let mesh = HeightMapMeshBuilder::new().build_ring(&map, 4, 4);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let grid: Vec<(usize, usize)> = positions
+        .iter()
+        .map(|p| (p[0] as usize, p[2] as usize))
+        .collect();
+
+    // 4×3 coarse cells; the 1×1 inner block is coarse cell (1, 1),
+    // i.e. full-detail vertices x, z ∈ 4..=8.
+    let inner = |x: usize, z: usize| (4..=8).contains(&x) && (4..=8).contains(&z);
+    for z in 0..13 {
+        for x in 0..17 {
+            let coarse = x % 4 == 0 && z % 4 == 0;
+            assert_eq!(
+                grid.contains(&(x, z)),
+                inner(x, z) || coarse,
+                "vertex ({x}, {z})"
+            );
+        }
+    }
+    for (p, &(x, z)) in positions.iter().zip(&grid) {
+        assert_eq!(p[1], map.get(x, z));
+    }
+
+    // Every edge is shared by two triangles, except on the map border.
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for [a, b, c] in triangles(&mesh) {
+        for (u, v) in [(a, b), (b, c), (c, a)] {
+            *edges.entry((u.min(v), u.max(v))).or_default() += 1;
+        }
+    }
+    for ((u, v), count) in edges {
+        let (a, b) = (grid[u], grid[v]);
+        let border =
+            (a.0 == b.0 && (a.0 == 0 || a.0 == 16)) || (a.1 == b.1 && (a.1 == 0 || a.1 == 12));
+        assert_eq!(count, if border { 1 } else { 2 }, "edge {a:?}–{b:?}");
+    }
+
+    // Every triangle faces up.
+    for [a, b, c] in triangles(&mesh) {
+        let [a, b, c] = [a, b, c].map(|i| Vec3::from(positions[i]));
+        assert!((b - a).cross(c - a).y > 0.0);
+    }
+}
+
+#[test]
+fn normal_border_emits_interior_with_full_map_normals() {
+    let mut big = HeightMap::new(12, 12, 1.0);
+    for z in 0..12 {
+        for x in 0..12 {
+            big.set(x, z, ((x * 5 + z * 3) % 7) as f32 * 0.4);
+        }
+    }
+    // A chunk covering vertices 3..10 of the big map, with a 1-vertex border.
+    let mut chunk = HeightMap::new(7, 7, 1.0);
+    for z in 0..7 {
+        for x in 0..7 {
+            chunk.set(x, z, big.get(x + 3, z + 3));
+        }
+    }
+    let mesh = HeightMapMeshBuilder::new()
+        .with_normal_border(1)
+        .build(&chunk);
+    assert_eq!(mesh.count_vertices(), 5 * 5);
+
+    let reference = normals(&HeightMapMeshBuilder::new().build(&big));
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert_eq!(positions[0], [1.0, chunk.get(1, 1), 1.0]);
+    for (i, n) in normals(&mesh).iter().enumerate() {
+        let (x, z) = (i % 5 + 1 + 3, i / 5 + 1 + 3);
+        let expected = reference[z * 12 + x];
+        let error = Vec3::from(*n).distance(Vec3::from(expected));
+        assert!(error < 1e-6, "vertex ({x}, {z}): {n:?} vs {expected:?}");
+    }
+}
+
+#[test]
+fn vertex_splat_colors_match_weight_pixels() {
+    use symbios_ground::WeightMap;
+
+    let map = ramp_map(5, 4, 2.0);
+    let mut weights = WeightMap::new(5, 4);
+    for (i, pixel) in weights.data.iter_mut().enumerate() {
+        let a = (i * 13 % 256) as u8;
+        *pixel = [a, 255 - a, (i * 7) as u8, 0];
+    }
+    let mesh = HeightMapMeshBuilder::new()
+        .with_vertex_splat(&weights)
+        .build(&map);
+
+    let colors = match mesh.attribute(Mesh::ATTRIBUTE_COLOR).unwrap() {
+        bevy::mesh::VertexAttributeValues::Float32x4(v) => v.clone(),
+        other => panic!("unexpected color format {other:?}"),
+    };
+    assert_eq!(colors.len(), weights.data.len());
+    for (color, pixel) in colors.iter().zip(&weights.data) {
+        for c in 0..4 {
+            assert!((color[c] - pixel[c] as f32 / 255.0).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "one pixel per heightmap vertex")]
+fn vertex_splat_rejects_mismatched_dimensions() {
+    let weights = symbios_ground::WeightMap::new(4, 4);
+    HeightMapMeshBuilder::new()
+        .with_vertex_splat(&weights)
+        .build(&ramp_map(5, 4, 1.0));
+}
+
+#[test]
+fn spherical_flat_map_lies_on_the_sphere_with_radial_normals() {
+    let radius = 10.0;
+    let map = flat_map(9, 7, 1.0);
+    let mesh = HeightMapMeshBuilder::new().build_spherical(&map, radius);
+    let center = Vec3::new(4.0, -radius, 3.0);
+
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    assert_eq!(positions.len(), 9 * 7);
+    // The map centre touches the top of the sphere.
+    assert!(Vec3::from(positions[3 * 9 + 4]).distance(Vec3::new(4.0, 0.0, 3.0)) < 1e-5);
+    for (i, (p, n)) in positions.iter().zip(normals(&mesh)).enumerate() {
+        let radial = Vec3::from(*p) - center;
+        assert!((radial.length() - radius).abs() < 1e-4, "{p:?}");
+        // Border normals only see the triangles on one side.
+        let (x, z) = (i % 9, i / 9);
+        let interior = (1..8).contains(&x) && (1..6).contains(&z);
+        let min_dot = if interior { 0.9999 } else { 0.99 };
+        assert!(
+            Vec3::from(n).dot(radial.normalize()) > min_dot,
+            "{n:?} at {p:?}"
+        );
+    }
+
+    // Heights lift vertices along the radial direction.
+    let mut raised = map.clone();
+    raised.set(0, 0, 2.0);
+    let mesh = HeightMapMeshBuilder::new().build_spherical(&raised, radius);
+    let corner = Vec3::from(
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap()[0],
+    );
+    assert!((corner.distance(center) - (radius + 2.0)).abs() < 1e-4);
+}
+
+#[test]
+fn estimate_mesh_bytes_matches_built_buffers() {
+    use bevy_symbios_ground::{
+        NormalEncoding, QuadDiagonal, Topology, UvMode, estimate_mesh_bytes,
+    };
+
+    let hm = ramp_map(9, 7, 1.5);
+    let configs = [
+        HeightMapMeshBuilder::new(),
+        HeightMapMeshBuilder::new()
+            .with_normal_encoding(NormalEncoding::Oct16)
+            .with_overlay_uv(true)
+            .with_height_attribute(true),
+        HeightMapMeshBuilder::new()
+            .with_subdivisions(1)
+            .with_barycentric(true)
+            .with_double_sided(true),
+        HeightMapMeshBuilder::new().with_uv_mode(UvMode::PerCell),
+        HeightMapMeshBuilder::new().with_normal_border(1),
+        HeightMapMeshBuilder::new().with_topology(Topology::Strip),
+        HeightMapMeshBuilder::new()
+            .with_topology(Topology::Strip)
+            .with_diagonal(QuadDiagonal::TopLeftBottomRight),
+    ];
+    for builder in configs {
+        let estimate = estimate_mesh_bytes(&hm, &builder);
+        let mut mesh = builder.build(&hm);
+        assert_eq!(estimate.vertex_count, mesh.count_vertices());
+        assert_eq!(estimate.vertex_bytes, mesh.get_vertex_buffer_size());
+        assert_eq!(
+            estimate.index_bytes,
+            mesh.get_index_buffer_bytes().unwrap().len()
+        );
+        assert_eq!(estimate.total, estimate.vertex_bytes + estimate.index_bytes);
+
+        if mesh.primitive_topology() == bevy::mesh::PrimitiveTopology::TriangleList
+            && mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_some()
+        {
+            bevy_symbios_ground::recompute_tangents(&mut mesh).unwrap();
+            assert_eq!(
+                estimate.with_tangents().vertex_bytes,
+                mesh.get_vertex_buffer_size()
+            );
+        }
+    }
+}
+
+#[test]
+fn height_quantization_builds_flat_terraces_and_vertical_risers() {
+    let mut map = HeightMap::new(12, 4, 0.1);
+    for z in 0..4 {
+        for x in 0..12 {
+            map.set(x, z, x as f32 * 0.3);
+        }
+    }
+    let mesh = HeightMapMeshBuilder::new()
+        .with_height_quantization(1.0)
+        .build(&map);
+
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    for p in positions {
+        assert_eq!(
+            p[1],
+            p[1].round(),
+            "height {} is not a multiple of the step",
+            p[1]
+        );
+    }
+
+    let normals = normals(&mesh);
+    let mut risers = 0;
+    let mut top_area = 0.0;
+    for [a, b, c] in triangles(&mesh) {
+        let [pa, pb, pc] = [a, b, c].map(|i| Vec3::from(positions[i]));
+        let face = (pb - pa).cross(pc - pa);
+        if pa.y == pb.y && pb.y == pc.y {
+            // Terrace tops are flat, upward-facing, and flat-shaded.
+            assert!(face.y > 0.0);
+            top_area += face.y * 0.5;
+            for i in [a, b, c] {
+                assert_eq!(Vec3::from(normals[i]), Vec3::Y);
+            }
+        } else {
+            // Risers are vertical and face down the ramp, toward -X.
+            let face = face.normalize();
+            assert!(face.y.abs() < 1e-6, "riser normal {face}");
+            assert!(face.x < -0.99, "riser normal {face}");
+            for i in [a, b, c] {
+                assert!(Vec3::from(normals[i]).abs_diff_eq(face, 1e-5));
+            }
+            risers += 1;
+        }
+    }
+    assert!(risers > 0);
+    // The tops tile the footprint exactly: no gaps or overlaps in plan view.
+    assert!((top_area - 11.0 * 3.0 * 0.01).abs() < 1e-5, "{top_area}");
+}
+
+#[test]
+fn normal_flatten_blends_normals_toward_up() {
+    let mut map = ramp_map(8, 6, 0.5);
+    map.set(3, 2, 4.0);
+    for method in [NormalMethod::AreaWeighted, NormalMethod::Sobel] {
+        let builder = HeightMapMeshBuilder::new().with_normal_method(method);
+        let base = normals(&builder.clone().build(&map));
+
+        let unchanged = normals(&builder.clone().with_normal_flatten(0.0).build(&map));
+        assert_eq!(unchanged, base);
+
+        let flat = normals(&builder.clone().with_normal_flatten(1.0).build(&map));
+        assert!(
+            flat.iter()
+                .all(|n| Vec3::from(*n).abs_diff_eq(Vec3::Y, 1e-6))
+        );
+
+        let half = normals(&builder.with_normal_flatten(0.5).build(&map));
+        for (b, h) in base.iter().zip(&half) {
+            assert!(h[1] >= b[1] - 1e-6);
+            assert!((Vec3::from(*h).length() - 1.0).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn height_bands_partition_the_index_buffer() {
+    let map = ramp_map(10, 6, 1.0);
+    let bounds = [6.0, 2.5, 4.0];
+    let (mesh, ranges) = HeightMapMeshBuilder::new()
+        .with_height_bands(&bounds)
+        .with_cache_optimization(true)
+        .build_banded(&map);
+
+    let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+    assert_eq!(ranges.first().unwrap().1.start, 0);
+    assert_eq!(ranges.last().unwrap().1.end, indices.len());
+    for pair in ranges.windows(2) {
+        assert_eq!(pair[0].1.end, pair[1].1.start);
+        assert!(pair[0].0 < pair[1].0);
+    }
+    assert_eq!(
+        ranges.iter().map(|(band, _)| *band).collect::<Vec<_>>(),
+        [0, 1, 2, 3]
+    );
+
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let sorted = [2.5, 4.0, 6.0];
+    for (band, range) in &ranges {
+        assert_eq!(range.len() % 3, 0);
+        for t in indices[range.clone()].chunks_exact(3) {
+            let average = t.iter().map(|&i| positions[i][1]).sum::<f32>() / 3.0;
+            let lo = if *band == 0 {
+                f32::NEG_INFINITY
+            } else {
+                sorted[band - 1]
+            };
+            let hi = sorted.get(*band).copied().unwrap_or(f32::INFINITY);
+            assert!(lo <= average && average < hi, "band {band}: {average}");
+        }
+    }
+
+    let plain = HeightMapMeshBuilder::new().build(&map);
+    assert_eq!(triangles(&mesh).len(), triangles(&plain).len());
+    let (_, whole) = HeightMapMeshBuilder::new().build_banded(&map);
+    assert_eq!(whole, [(0, 0..indices.len())]);
+}
+
+#[test]
+fn build_with_cliffs_separates_steep_faces() {
+    // A low plain, a one-cell cliff at x = 4..5, and a high plateau.
+    let mut map = HeightMap::new(10, 6, 1.0);
+    for z in 0..6 {
+        for x in 5..10 {
+            map.set(x, z, 5.0);
+        }
+    }
+    let builder = HeightMapMeshBuilder::new();
+    let (ground, cliff) = builder.build_with_cliffs(&map, 45.0);
+
+    let face_normal = |mesh: &Mesh, [a, b, c]: [usize; 3]| {
+        let p = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        let [a, b, c] = [a, b, c].map(|i| Vec3::from(p[i]));
+        (b - a).cross(c - a).normalize()
+    };
+    let ground_tris = triangles(&ground);
+    let cliff_tris = triangles(&cliff);
+    assert_eq!(cliff_tris.len(), 2 * 5);
+    assert_eq!(ground_tris.len() + cliff_tris.len(), 2 * 9 * 5);
+    for t in ground_tris {
+        assert!(face_normal(&ground, t).abs_diff_eq(Vec3::Y, 1e-5));
+    }
+    for t in cliff_tris {
+        assert!(face_normal(&cliff, t).y < 0.5);
+    }
+
+    // Vertices on the shared cliff edges are identical in both submeshes.
+    let full = builder.build(&map);
+    let vertices = |mesh: &Mesh| {
+        let p = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap()
+            .to_vec();
+        p.into_iter().zip(normals(mesh)).collect::<Vec<_>>()
+    };
+    let full = vertices(&full);
+    let ground = vertices(&ground);
+    let shared: Vec<_> = vertices(&cliff)
+        .into_iter()
+        .filter(|v| ground.contains(v))
+        .collect();
+    assert_eq!(shared.len(), 2 * 6);
+    assert!(shared.iter().all(|v| full.contains(v)));
+}
+
+#[test]
+fn chunked_overlap_adds_border_rings_that_tile_seamlessly() {
+    let map = noisy_map(13, 13);
+    let builder = HeightMapMeshBuilder::new();
+    let full = builder.build(&map);
+    let full_positions = full
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let full_normals = normals(&full);
+
+    let plain = builder.build_chunked(&map, 4);
+    let overlapped = builder.build_chunked_with_overlap(&map, 4, 2);
+    assert_eq!(plain.len(), overlapped.len());
+    assert_eq!(
+        builder.build_chunked_with_overlap(&map, 4, 0)[4]
+            .1
+            .count_vertices(),
+        plain[4].1.count_vertices()
+    );
+
+    // The centre chunk spans [4, 8]² plus two rings: [2, 10]².
+    assert_eq!(overlapped[4].0, IVec2::new(1, 1));
+    assert_eq!(plain[4].1.count_vertices(), 5 * 5);
+    assert_eq!(overlapped[4].1.count_vertices(), 9 * 9);
+    // The corner chunk is clamped to the map on its outer sides: [0, 6]².
+    assert_eq!(overlapped[0].1.count_vertices(), 7 * 7);
+
+    // Every chunk vertex, including the overlap rings, matches the full build.
+    for (_, chunk) in &overlapped {
+        let positions = chunk
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap();
+        for (p, n) in positions.iter().zip(normals(chunk)) {
+            let (x, z) = (p[0] as usize, p[2] as usize);
+            assert_eq!(*p, full_positions[z * 13 + x]);
+            assert_eq!(n, full_normals[z * 13 + x], "vertex ({x}, {z})");
+        }
+    }
+}
+
+#[test]
+fn chunk_frame_centers_positions_and_keeps_global_uvs() {
+    let map = ramp_map(17, 17, 2.0);
+    // Region of vertices [8, 12] × [4, 8]: world X 16..24, Z 8..16.
+    let center = Vec2::new(20.0, 12.0);
+    let global = HeightMapMeshBuilder::new()
+        .with_uv_tile_size(3.0)
+        .build_region(&map, 8, 4, 5, 5);
+    let local = HeightMapMeshBuilder::new()
+        .with_uv_tile_size(3.0)
+        .with_chunk_frame(center)
+        .build_region(&map, 8, 4, 5, 5);
+
+    let positions = |mesh: &Mesh| {
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            .unwrap()
+            .as_float3()
+            .unwrap()
+            .to_vec()
+    };
+    let (global_positions, local_positions) = (positions(&global), positions(&local));
+    let (mut lo, mut hi) = (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY));
+    for (g, l) in global_positions.iter().zip(&local_positions) {
+        assert_eq!(l[0], g[0] - center.x);
+        assert_eq!(l[1], g[1]);
+        assert_eq!(l[2], g[2] - center.y);
+        lo = lo.min(Vec2::new(l[0], l[2]));
+        hi = hi.max(Vec2::new(l[0], l[2]));
+    }
+    assert_eq!(lo, Vec2::splat(-4.0));
+    assert_eq!(hi, Vec2::splat(4.0));
+
+    // UVs come from the global map coordinates, not the chunk-local ones.
+    assert_eq!(uvs(&local), uvs(&global));
+    for (uv, g) in uvs(&local).iter().zip(&global_positions) {
+        assert!((Vec2::from(*uv) - Vec2::new(g[0], g[2]) / 3.0).length() < 1e-6);
+    }
+}
+
+#[test]
+fn detail_normals_follow_subdivided_and_lod_grids() {
+    use bevy_symbios_ground::NormalField;
+
+    let mut field = NormalField::new(9, 7);
+    for (i, n) in field.data.iter_mut().enumerate() {
+        let (x, z) = (i % 9, i / 9);
+        *n = Vec3::new(x as f32 * 0.1, 1.0, z as f32 * -0.05).normalize();
+    }
+    let map = flat_map(9, 7, 1.0);
+    let builder = HeightMapMeshBuilder::new().with_detail_normals(&field, 1.0);
+
+    // Every third vertex of the subdivided grid is a source vertex and gets
+    // that vertex's detail normal; the ones between are interpolated.
+    let dense = normals(&builder.clone().with_subdivisions(2).build(&map));
+    assert_eq!(dense.len(), 25 * 19);
+    for z in 0..7 {
+        for x in 0..9 {
+            let n = Vec3::from(dense[3 * z * 25 + 3 * x]);
+            assert!(
+                n.abs_diff_eq(field.data[z * 9 + x], 1e-5),
+                "({x}, {z}): {n}"
+            );
+        }
+    }
+
+    // LOD level 2 keeps every other vertex, with the matching detail normal.
+    let chain = builder.build_lod_chain(&map, &[1, 2]);
+    let coarse = normals(&chain[1]);
+    assert_eq!(coarse.len(), 5 * 4);
+    for z in 0..4 {
+        for x in 0..5 {
+            let n = Vec3::from(coarse[z * 5 + x]);
+            assert!(
+                n.abs_diff_eq(field.data[2 * z * 9 + 2 * x], 1e-5),
+                "({x}, {z}): {n}"
+            );
+        }
+    }
+    assert_eq!(normals(&chain[0]), normals(&builder.build(&map)));
+}
+
+#[test]
+fn lod_paths_stride_full_resolution_masks() {
+    use symbios_ground::WeightMap;
+
+    let map = ramp_map(9, 9, 1.0);
+    let mut holes = vec![false; 8 * 8];
+    holes[3 * 8 + 5] = true;
+    let weights = WeightMap::new(9, 9);
+    let builder = HeightMapMeshBuilder::new()
+        .with_hole_mask(holes)
+        .with_vertex_splat(&weights);
+
+    // Fine cell (5, 3) lies in coarse cell (2, 1), which becomes a hole.
+    let chain = builder.build_lod_chain(&map, &[1, 2]);
+    assert_eq!(triangles(&chain[0]).len(), 2 * 64 - 2);
+    assert_eq!(triangles(&chain[1]).len(), 2 * 16 - 2);
+    assert!(chain[1].attribute(Mesh::ATTRIBUTE_COLOR).is_some());
+
+    let (mesh, step) = builder.build_within_budget(&map, 40);
+    assert_eq!(step, 2);
+    assert_eq!(triangles(&mesh).len(), 2 * 16 - 2);
+}
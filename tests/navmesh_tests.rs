@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use bevy_symbios_ground::{NavmeshOptions, build_navmesh_geometry};
+use symbios_ground::HeightMap;
+
+/// Flat map with a cliff rising 10 units per cell for `x ≥ 4`.
+fn cliff_map() -> HeightMap {
+    let mut map = HeightMap::new(9, 5, 1.0);
+    for z in 0..5 {
+        for x in 0..9 {
+            map.set(x, z, x.saturating_sub(4) as f32 * 10.0);
+        }
+    }
+    map
+}
+
+fn centroids(vertices: &[[f32; 3]], indices: &[u32]) -> Vec<Vec3> {
+    indices
+        .chunks_exact(3)
+        .map(|t| {
+            t.iter()
+                .map(|&i| Vec3::from(vertices[i as usize]))
+                .sum::<Vec3>()
+                / 3.0
+        })
+        .collect()
+}
+
+#[test]
+fn steep_cells_are_excluded() {
+    let map = cliff_map();
+    let (vertices, indices) = build_navmesh_geometry(&map, &NavmeshOptions::default());
+
+    let centers = centroids(&vertices, &indices);
+    // Only the 4×4 flat cells west of the cliff remain.
+    assert_eq!(centers.len(), 4 * 4 * 2);
+    assert!(centers.iter().all(|c| c.x < 4.0), "{centers:?}");
+    for t in indices.chunks_exact(3) {
+        let [a, b, c] = [t[0], t[1], t[2]].map(|i| Vec3::from(vertices[i as usize]));
+        assert!((b - a).cross(c - a).y > 0.0, "triangle must face up");
+    }
+    // Unreferenced vertices are dropped.
+    assert_eq!(vertices.len(), 5 * 5);
+}
+
+#[test]
+fn permissive_slope_keeps_everything() {
+    let options = NavmeshOptions {
+        max_slope_degrees: 89.9,
+        ..default()
+    };
+    let (_, indices) = build_navmesh_geometry(&cliff_map(), &options);
+    assert_eq!(indices.len() / 3, 8 * 4 * 2);
+}
+
+#[test]
+fn downsampling_and_holes() {
+    let map = HeightMap::new(9, 9, 0.5);
+    let mut holes = vec![false; 8 * 8];
+    holes[5 * 8 + 6] = true; // grid cell (6, 5) → coarse cell (1, 1) at step 4
+    let options = NavmeshOptions {
+        downsample: 4,
+        hole_mask: Some(holes),
+        ..default()
+    };
+    let (vertices, indices) = build_navmesh_geometry(&map, &options);
+
+    assert_eq!(indices.len() / 3, (4 - 1) * 2);
+    assert!(
+        vertices
+            .iter()
+            .all(|v| v[0] % 2.0 == 0.0 && v[2] % 2.0 == 0.0)
+    );
+    for c in centroids(&vertices, &indices) {
+        assert!(!(c.x > 2.0 && c.z > 2.0), "triangle over hole at {c}");
+    }
+}
@@ -0,0 +1,154 @@
+use bevy_symbios_ground::{MirrorMode, clamp_max_slope, despike, mirror_heightmap};
+use symbios_ground::HeightMap;
+
+/// Asymmetric map: every vertex has a distinct height.
+fn distinct_map(w: usize, h: usize) -> HeightMap {
+    let mut map = HeightMap::new(w, h, 0.5);
+    for z in 0..h {
+        for x in 0..w {
+            map.set(x, z, (z * w + x) as f32);
+        }
+    }
+    map
+}
+
+#[test]
+fn mirror_both_is_symmetric_about_center() {
+    let n = 5;
+    let src = distinct_map(n, n);
+    let out = mirror_heightmap(&src, MirrorMode::Both);
+
+    assert_eq!(out.width(), 2 * n - 1);
+    assert_eq!(out.height(), 2 * n - 1);
+    assert_eq!(out.scale(), src.scale());
+
+    let last = 2 * n - 2;
+    for z in 0..out.height() {
+        for x in 0..out.width() {
+            let h = out.get(x, z);
+            assert_eq!(h, out.get(last - x, z));
+            assert_eq!(h, out.get(x, last - z));
+        }
+    }
+    // The source quadrant is preserved, including the shared center.
+    for z in 0..n {
+        for x in 0..n {
+            assert_eq!(out.get(x, z), src.get(x, z));
+        }
+    }
+}
+
+#[test]
+fn mirror_single_axis_keeps_other_dimension() {
+    let src = distinct_map(4, 3);
+
+    let x = mirror_heightmap(&src, MirrorMode::X);
+    assert_eq!((x.width(), x.height()), (7, 3));
+    assert_eq!(x.get(4, 1), src.get(2, 1));
+
+    let z = mirror_heightmap(&src, MirrorMode::Z);
+    assert_eq!((z.width(), z.height()), (4, 5));
+    assert_eq!(z.get(1, 4), src.get(1, 0));
+}
+
+#[test]
+fn downsample_keeps_strided_vertices() {
+    use bevy_symbios_ground::downsample;
+
+    let src = distinct_map(9, 7);
+    let out = downsample(&src, 2);
+    assert_eq!((out.width(), out.height()), (5, 4));
+    assert_eq!(out.scale(), 1.0);
+    for z in 0..4 {
+        for x in 0..5 {
+            assert_eq!(out.get(x, z), src.get(2 * x, 2 * z));
+        }
+    }
+    // A non-dividing factor drops the trailing vertices.
+    let out = downsample(&src, 3);
+    assert_eq!((out.width(), out.height()), (3, 3));
+    assert_eq!(out.get(2, 2), src.get(6, 6));
+}
+
+#[test]
+fn resample_upsamples_bilinearly() {
+    use bevy_symbios_ground::resample;
+
+    let mut src = HeightMap::new(3, 3, 2.0);
+    for (i, h) in [0.0, 4.0, 8.0, 2.0, 6.0, 10.0, 4.0, 8.0, 20.0]
+        .into_iter()
+        .enumerate()
+    {
+        src.set(i % 3, i / 3, h);
+    }
+    let out = resample(&src, 5, 5);
+    assert_eq!((out.width(), out.height()), (5, 5));
+    assert_eq!(out.scale(), 1.0);
+    // Source vertices land on even indices.
+    for z in 0..3 {
+        for x in 0..3 {
+            assert_eq!(out.get(2 * x, 2 * z), src.get(x, z));
+        }
+    }
+    assert_eq!(out.get(1, 0), 2.0);
+    assert_eq!(out.get(0, 1), 1.0);
+    assert_eq!(out.get(3, 3), (6.0 + 10.0 + 8.0 + 20.0) / 4.0);
+}
+
+#[test]
+fn resample_downsamples_to_corners() {
+    use bevy_symbios_ground::resample;
+
+    let src = distinct_map(7, 5);
+    let out = resample(&src, 2, 2);
+    assert_eq!(out.scale(), 0.5 * 6.0);
+    assert_eq!(out.get(0, 0), src.get(0, 0));
+    assert_eq!(out.get(1, 0), src.get(6, 0));
+    assert_eq!(out.get(0, 1), src.get(0, 4));
+    assert_eq!(out.get(1, 1), src.get(6, 4));
+}
+
+#[test]
+fn clamp_max_slope_limits_every_neighbour_pair() {
+    // A 10-unit cliff down the middle of an otherwise flat map.
+    let mut map = HeightMap::new(16, 8, 1.0);
+    for z in 0..8 {
+        for x in 8..16 {
+            map.set(x, z, 10.0);
+        }
+    }
+    let total: f32 = map.data().iter().sum();
+    clamp_max_slope(&mut map, 45.0, 1000);
+
+    let limit = 45f32.to_radians().tan() * map.scale() + 1e-3;
+    for z in 0..8 {
+        for x in 0..16 {
+            if x + 1 < 16 {
+                assert!((map.get(x, z) - map.get(x + 1, z)).abs() <= limit);
+            }
+            if z + 1 < 8 {
+                assert!((map.get(x, z) - map.get(x, z + 1)).abs() <= limit);
+            }
+        }
+    }
+    let clamped: f32 = map.data().iter().sum();
+    assert!((total - clamped).abs() < 1e-2);
+}
+
+#[test]
+fn despike_removes_spike_and_keeps_ramp() {
+    // A ramp rising 1 unit per vertex along X, with one spike on it.
+    let mut map = HeightMap::new(8, 8, 1.0);
+    for z in 0..8 {
+        for x in 0..8 {
+            map.set(x, z, x as f32);
+        }
+    }
+    let ramp = map.clone();
+    map.set(4, 3, 40.0);
+
+    despike(&mut map, 2.0);
+
+    assert_eq!(map.get(4, 3), 4.0);
+    assert_eq!(map.data(), ramp.data());
+}
@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy_symbios_ground::{
+    buildable_mask_image, buildable_mask_image_feathered, edge_fade_image, height_palette_image,
+    render_terrain_preview,
+};
+use symbios_ground::{HeightMap, WeightMap};
+
+/// Flat on the left half; a steep slope falling towards +X (away from the
+/// north-west light) on the right half.
+fn half_cliff_map(n: usize) -> HeightMap {
+    let mut map = HeightMap::new(n, n, 1.0);
+    for z in 0..n {
+        for x in n / 2..n {
+            map.set(x, z, -3.0 * (x - n / 2) as f32);
+        }
+    }
+    map
+}
+
+fn luminance(image: &Image, x: u32, y: u32) -> u32 {
+    let width = image.texture_descriptor.size.width;
+    let i = ((y * width + x) * 4) as usize;
+    let data = image.data.as_ref().unwrap();
+    data[i] as u32 + data[i + 1] as u32 + data[i + 2] as u32
+}
+
+#[test]
+fn preview_has_requested_dimensions() {
+    let map = half_cliff_map(16);
+    let image = render_terrain_preview(&map, &WeightMap::new(16, 16), UVec2::new(24, 10));
+    assert_eq!(image.texture_descriptor.size.width, 24);
+    assert_eq!(image.texture_descriptor.size.height, 10);
+    assert_eq!(image.data.as_ref().unwrap().len(), 24 * 10 * 4);
+}
+
+#[test]
+fn flat_region_is_brighter_than_shadowed_slope() {
+    let map = half_cliff_map(32);
+    let image = render_terrain_preview(&map, &WeightMap::new(32, 32), UVec2::splat(32));
+
+    let flat = luminance(&image, 4, 16);
+    let slope = luminance(&image, 26, 16);
+    assert!(
+        flat > slope,
+        "flat {flat} should outshine shadowed slope {slope}"
+    );
+}
+
+#[test]
+fn two_stop_palette_maps_extremes_to_black_and_white() {
+    let mut map = HeightMap::new(4, 3, 1.0);
+    map.set(0, 0, -2.0);
+    map.set(3, 2, 5.0);
+    map.set(1, 1, 1.5);
+    let palette = [(1.0, Color::WHITE), (0.0, Color::BLACK)];
+    let image = height_palette_image(&map, &palette);
+
+    assert_eq!(image.texture_descriptor.size.width, 4);
+    assert_eq!(image.texture_descriptor.size.height, 3);
+    assert!(luminance(&image, 0, 0) <= 3, "lowest texel should be black");
+    assert!(
+        luminance(&image, 3, 2) >= 3 * 252,
+        "highest texel should be white"
+    );
+    let mid = luminance(&image, 1, 1);
+    assert!(mid > 3 && mid < 3 * 252);
+}
+
+#[test]
+fn composite_weight_colors_blends_layers() {
+    use bevy_symbios_ground::composite_weight_colors;
+
+    let colors = [
+        Color::srgb(1.0, 0.0, 0.0),
+        Color::srgb_u8(20, 160, 90),
+        Color::srgb(0.0, 0.0, 1.0),
+        Color::WHITE,
+    ];
+    let mut wm = WeightMap::new(2, 1);
+    wm.data = vec![[0, 255, 0, 0], [0, 128, 127, 0]];
+    let image = composite_weight_colors(&wm, colors);
+    assert_eq!(
+        image.texture_descriptor.format,
+        TextureFormat::Rgba8UnormSrgb
+    );
+    let data = image.data.as_ref().unwrap();
+
+    assert_eq!(&data[0..4], &[20, 160, 90, 255]);
+
+    let expected = colors[1]
+        .to_linear()
+        .mix(&colors[2].to_linear(), 127.0 / 255.0);
+    let expected = Color::from(expected).to_srgba().to_u8_array();
+    for (got, want) in data[4..8].iter().zip(expected) {
+        assert!(got.abs_diff(want) <= 1, "{:?} vs {expected:?}", &data[4..8]);
+    }
+}
+
+#[test]
+fn buildable_mask_is_opaque_on_flats_and_clear_on_cliffs() {
+    let n = 8;
+    let map = half_cliff_map(n);
+    let image = buildable_mask_image(&map, 30.0);
+    assert_eq!(image.texture_descriptor.format, TextureFormat::R8Unorm);
+    let data = image.data.as_ref().unwrap();
+    assert_eq!(data.len(), n * n);
+
+    for z in 0..n {
+        // Columns 0..3 are flat, and 5.. lie on the 3:1 cliff.
+        for x in 0..3 {
+            assert_eq!(data[z * n + x], 255, "flat ({x}, {z})");
+        }
+        for x in 5..n - 1 {
+            assert_eq!(data[z * n + x], 0, "cliff ({x}, {z})");
+        }
+    }
+    assert!(data.iter().all(|&b| b == 0 || b == 255));
+
+    // A feather wider than the cliff angle only softens, never widens, the mask.
+    let soft = buildable_mask_image_feathered(&map, 30.0, 10.0);
+    let soft = soft.data.as_ref().unwrap();
+    assert!(soft.iter().zip(data).all(|(&s, &d)| s <= d));
+    assert_eq!(soft[0], 255);
+}
+
+#[test]
+fn edge_fade_is_clear_on_the_border_and_opaque_inside() {
+    let (w, h) = (12, 9);
+    let map = HeightMap::new(w, h, 1.0);
+    let image = edge_fade_image(&map, 3);
+    assert_eq!(image.texture_descriptor.format, TextureFormat::R8Unorm);
+    let data = image.data.as_ref().unwrap();
+    assert_eq!(data.len(), w * h);
+
+    for z in 0..h {
+        for x in 0..w {
+            let edge = x.min(z).min(w - 1 - x).min(h - 1 - z);
+            let texel = data[z * w + x];
+            match edge {
+                0 => assert_eq!(texel, 0, "border ({x}, {z})"),
+                1 | 2 => assert!(0 < texel && texel < 255, "band ({x}, {z})"),
+                _ => assert_eq!(texel, 255, "interior ({x}, {z})"),
+            }
+        }
+    }
+    // The ramp rises monotonically into the interior.
+    assert!(data[w + 1] < data[2 * w + 2]);
+    assert!(
+        edge_fade_image(&map, 0)
+            .data
+            .unwrap()
+            .iter()
+            .all(|&b| b == 255)
+    );
+}
+
+#[test]
+fn bake_ao_image_darkens_valleys() {
+    use bevy_symbios_ground::bake_ao_image;
+
+    // A plateau at height 6 cut by a trench along x ∈ [14, 18].
+    let mut map = HeightMap::new(33, 17, 1.0);
+    for z in 0..17 {
+        for x in 0..33 {
+            map.set(x, z, if (14..=18).contains(&x) { 0.0 } else { 6.0 });
+        }
+    }
+    let image = bake_ao_image(&map, 8, UVec2::new(64, 32));
+    assert_eq!((image.width(), image.height()), (64, 32));
+    assert_eq!(image.texture_descriptor.format, TextureFormat::R8Unorm);
+
+    // Texel px covers world x = (px + 0.5) / 64 · 32.
+    let data = image.data.as_ref().unwrap();
+    let texel = |px: usize, py: usize| data[py * 64 + px];
+    let plateau = texel(7, 16);
+    let valley = texel(31, 16);
+    assert_eq!(plateau, 255, "open plateau is unoccluded");
+    assert!(valley < 200, "valley texel {valley} should be dark");
+
+    let flat = bake_ao_image(&HeightMap::new(9, 9, 1.0), 4, UVec2::splat(16));
+    assert!(flat.data.as_ref().unwrap().iter().all(|&v| v == 255));
+}
@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use bevy_symbios_ground::{
+    QuadDiagonal, cross_section, pick_cell, sample_height_bilinear, sample_heights_bilinear,
+    sample_normal_bilinear, sample_normals_bilinear, triangle_at, triangle_at_with_diagonal,
+};
+use symbios_ground::HeightMap;
+
+fn bumpy_map() -> HeightMap {
+    let mut map = HeightMap::new(4, 4, 2.0);
+    for z in 0..4 {
+        for x in 0..4 {
+            map.set(x, z, (x * 3 + z * 5) as f32 * 0.25);
+        }
+    }
+    map
+}
+
+#[test]
+fn upper_left_half_returns_first_triangle() {
+    let map = bumpy_map();
+    // Cell (1, 1) spans world [2, 4] × [2, 4]; (2.5, 2.5) has fx + fz = 0.5.
+    let (verts, _) = triangle_at(&map, 2.5, 2.5).expect("point is inside the map");
+    let tl = Vec3::new(2.0, map.get(1, 1), 2.0);
+    let bl = Vec3::new(2.0, map.get(1, 2), 4.0);
+    let tr = Vec3::new(4.0, map.get(2, 1), 2.0);
+    assert_eq!(verts, [tl, bl, tr]);
+}
+
+#[test]
+fn lower_right_half_returns_second_triangle() {
+    let map = bumpy_map();
+    // fx + fz = 1.5 → second triangle of cell (1, 1).
+    let (verts, _) = triangle_at(&map, 3.5, 3.5).expect("point is inside the map");
+    let tr = Vec3::new(4.0, map.get(2, 1), 2.0);
+    let bl = Vec3::new(2.0, map.get(1, 2), 4.0);
+    let br = Vec3::new(4.0, map.get(2, 2), 4.0);
+    assert_eq!(verts, [tr, bl, br]);
+}
+
+#[test]
+fn flat_face_normal_points_up() {
+    let map = HeightMap::new(3, 3, 1.0);
+    let (_, normal) = triangle_at(&map, 0.5, 0.25).unwrap();
+    assert!((normal - Vec3::Y).length() < 1e-6, "got {normal:?}");
+}
+
+#[test]
+fn out_of_bounds_returns_none() {
+    let map = bumpy_map();
+    assert!(triangle_at(&map, -0.1, 1.0).is_none());
+    assert!(triangle_at(&map, 1.0, 6.1).is_none());
+    // Far corner is still on the surface.
+    assert!(triangle_at(&map, 6.0, 6.0).is_some());
+}
+
+#[test]
+fn zigzag_diagonal_splits_along_tl_br() {
+    let map = bumpy_map();
+    let diagonal = QuadDiagonal::TopLeftBottomRight;
+    let tl = Vec3::new(2.0, map.get(1, 1), 2.0);
+    let tr = Vec3::new(4.0, map.get(2, 1), 2.0);
+    let bl = Vec3::new(2.0, map.get(1, 2), 4.0);
+    let br = Vec3::new(4.0, map.get(2, 2), 4.0);
+
+    // fz > fx → lower-left triangle.
+    let (verts, _) = triangle_at_with_diagonal(&map, 2.25, 3.5, diagonal).unwrap();
+    assert_eq!(verts, [tl, bl, br]);
+    // fx > fz → upper-right triangle.
+    let (verts, _) = triangle_at_with_diagonal(&map, 3.5, 2.25, diagonal).unwrap();
+    assert_eq!(verts, [tl, br, tr]);
+}
+
+fn scatter_points() -> Vec<Vec2> {
+    // Includes points outside the map and exactly on its far edges.
+    let mut points: Vec<Vec2> = (0..200u32)
+        .map(|i| {
+            let t = i as f32;
+            Vec2::new(
+                (t * 0.618_034).fract() * 8.0 - 1.0,
+                (t * 0.754_877).fract() * 8.0 - 1.0,
+            )
+        })
+        .collect();
+    points.extend([Vec2::new(6.0, 6.0), Vec2::new(6.0, 1.0), Vec2::ZERO]);
+    points
+}
+
+#[test]
+fn batched_sampling_matches_scalar() {
+    let mut map = bumpy_map();
+    map.set(2, 1, 4.0);
+    let points = scatter_points();
+
+    let heights = sample_heights_bilinear(&map, &points);
+    let normals = sample_normals_bilinear(&map, &points);
+    assert_eq!(heights.len(), points.len());
+    for ((p, h), n) in points.iter().zip(&heights).zip(&normals) {
+        assert_eq!(*h, sample_height_bilinear(&map, p.x, p.y));
+        assert_eq!(*n, sample_normal_bilinear(&map, p.x, p.y));
+        assert!((*h - map.get_height_at(p.x, p.y)).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn bilinear_sampling_hits_grid_heights() {
+    let map = bumpy_map();
+    for z in 0..4 {
+        for x in 0..4 {
+            let h = sample_height_bilinear(&map, x as f32 * 2.0, z as f32 * 2.0);
+            assert_eq!(h, map.get(x, z));
+        }
+    }
+}
+
+#[test]
+fn cross_section_along_ramp_rises_linearly() {
+    // Height = 0.5 · world x.
+    let mut map = HeightMap::new(5, 5, 2.0);
+    for z in 0..5 {
+        for x in 0..5 {
+            map.set(x, z, x as f32);
+        }
+    }
+    let profile = cross_section(&map, Vec2::new(0.0, 3.0), Vec2::new(8.0, 3.0), 9);
+
+    assert_eq!(profile.len(), 9);
+    for (i, p) in profile.iter().enumerate() {
+        assert!((p.x - i as f32).abs() < 1e-5, "distance {p}");
+        assert!((p.y - 0.5 * i as f32).abs() < 1e-5, "height {p}");
+    }
+}
+
+#[test]
+fn pick_cell_hits_the_cell_under_the_cursor() {
+    use bevy::camera::RenderTargetInfo;
+
+    let mut camera = Camera::default();
+    camera.computed.clip_from_view =
+        Mat4::perspective_infinite_reverse_rh(std::f32::consts::FRAC_PI_4, 800.0 / 600.0, 0.1);
+    camera.computed.target_info = Some(RenderTargetInfo {
+        physical_size: UVec2::new(800, 600),
+        scale_factor: 1.0,
+    });
+    let centre = Vec2::new(400.0, 300.0);
+
+    let map = HeightMap::new(11, 11, 1.0);
+    let terrain = Transform::from_xyz(-5.0, 2.0, -5.0);
+    // World (1.5, 2, -2.5) is local (6.5, 0, 2.5), inside cell (6, 2).
+    let target = Vec3::new(1.5, 2.0, -2.5);
+    let eye = GlobalTransform::from(
+        Transform::from_translation(target + Vec3::new(3.0, 8.0, 6.0)).looking_at(target, Vec3::Y),
+    );
+    assert_eq!(
+        pick_cell(&camera, &eye, centre, &map, &terrain),
+        Some((6, 2))
+    );
+
+    // A wall at x = 5 occludes the cells behind it from a low camera.
+    let mut wall = map.clone();
+    for z in 0..11 {
+        wall.set(5, z, 6.0);
+    }
+    let low = GlobalTransform::from(
+        Transform::from_translation(target + Vec3::new(-9.0, 1.0, 0.0)).looking_at(target, Vec3::Y),
+    );
+    let (x, _) = pick_cell(&camera, &low, centre, &wall, &terrain).unwrap();
+    assert!(x < 5, "hit cell {x} behind the wall");
+
+    // The upper edge of the screen looks past the far side of the map.
+    assert_eq!(
+        pick_cell(&camera, &eye, Vec2::new(400.0, 0.0), &map, &terrain),
+        None
+    );
+    assert_eq!(
+        pick_cell(&Camera::default(), &eye, centre, &map, &terrain),
+        None
+    );
+}
@@ -0,0 +1,157 @@
+use bevy::camera::primitives::Aabb;
+use bevy::ecs::world::CommandQueue;
+use bevy::prelude::*;
+use bevy_symbios_ground::{
+    HeightMapMeshBuilder, fit_to_box, spawn_terrain, terrain_alignment, world_bounds, world_extent,
+};
+use symbios_ground::{HeightMap, WeightMap};
+
+fn spawn_in_world(heightmap: &HeightMap) -> (World, Entity) {
+    let mut world = World::new();
+    let mut queue = CommandQueue::default();
+    let mut meshes = Assets::<Mesh>::default();
+    let mut materials = Assets::<StandardMaterial>::default();
+    let mut images = Assets::<Image>::default();
+    let weight_map = WeightMap::new(heightmap.width(), heightmap.height());
+
+    let entity = {
+        let mut commands = Commands::new(&mut queue, &world);
+        spawn_terrain(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut images,
+            heightmap,
+            &weight_map,
+            &HeightMapMeshBuilder::new(),
+        )
+    };
+    queue.apply(&mut world);
+
+    let material_handle = world
+        .get::<MeshMaterial3d<StandardMaterial>>(entity)
+        .unwrap();
+    let material = materials
+        .get(&material_handle.0)
+        .expect("material was added");
+    let splat = material
+        .base_color_texture
+        .as_ref()
+        .expect("splat texture is set");
+    assert!(images.get(splat).is_some());
+
+    (world, entity)
+}
+
+#[test]
+fn spawned_terrain_has_render_components() {
+    let heightmap = HeightMap::new(9, 5, 2.0);
+    let (world, entity) = spawn_in_world(&heightmap);
+    assert!(world.get::<Mesh3d>(entity).is_some());
+    assert!(
+        world
+            .get::<MeshMaterial3d<StandardMaterial>>(entity)
+            .is_some()
+    );
+
+    let transform = world.get::<Transform>(entity).unwrap();
+    // 8 cells × 2.0 → 16 wide; 4 cells × 2.0 → 8 deep.
+    assert_eq!(transform.translation, Vec3::new(-8.0, 0.0, -4.0));
+    assert_eq!(*transform, terrain_alignment(&heightmap));
+}
+
+#[cfg(feature = "physics")]
+#[test]
+fn spawned_terrain_has_aligned_collider_child() {
+    use avian3d::prelude::Collider;
+
+    let heightmap = HeightMap::new(9, 5, 2.0);
+    let (world, entity) = spawn_in_world(&heightmap);
+    let children = world.get::<Children>(entity).expect("collider child");
+    let child = children[0];
+    assert!(world.get::<Collider>(child).is_some());
+    assert_eq!(
+        world.get::<Transform>(child).unwrap().translation,
+        Vec3::new(8.0, 0.0, 4.0)
+    );
+}
+
+fn sloped_map() -> HeightMap {
+    // 9×5 vertices at scale 2 → 16×8 footprint; heights span [-1, 3].
+    let mut map = HeightMap::new(9, 5, 2.0);
+    for z in 0..5 {
+        for x in 0..9 {
+            map.set(x, z, x as f32 * 0.5 - 1.0);
+        }
+    }
+    map
+}
+
+#[test]
+fn stretched_fit_maps_corners_to_box() {
+    let map = sloped_map();
+    let target = Aabb::from_min_max(Vec3::new(-10.0, 5.0, 20.0), Vec3::new(30.0, 9.0, 26.0));
+    let transform = fit_to_box(&map, target, true);
+
+    let lo = transform.transform_point(Vec3::new(0.0, -1.0, 0.0));
+    let hi = transform.transform_point(Vec3::new(16.0, 3.0, 8.0));
+    assert!(lo.abs_diff_eq(Vec3::from(target.min()), 1e-4), "{lo}");
+    assert!(hi.abs_diff_eq(Vec3::from(target.max()), 1e-4), "{hi}");
+}
+
+#[test]
+fn uniform_fit_preserves_aspect_and_stays_inside() {
+    let map = sloped_map();
+    let target = Aabb::from_min_max(Vec3::ZERO, Vec3::new(32.0, 100.0, 100.0));
+    let transform = fit_to_box(&map, target, false);
+
+    // X is the limiting axis: 16 → 32.
+    assert_eq!(transform.scale, Vec3::splat(2.0));
+    let lo = transform.transform_point(Vec3::new(0.0, -1.0, 0.0));
+    let hi = transform.transform_point(Vec3::new(16.0, 3.0, 8.0));
+    assert!((lo.x - 0.0).abs() < 1e-4 && (hi.x - 32.0).abs() < 1e-4);
+    assert!(lo.cmpge(Vec3::ZERO).all() && hi.cmple(Vec3::new(32.0, 100.0, 100.0)).all());
+    // Centered on the other axes.
+    assert!(((lo + hi) * 0.5).abs_diff_eq(Vec3::new(16.0, 50.0, 50.0), 1e-4));
+}
+
+#[test]
+fn terrain_material_defaults() {
+    use bevy_symbios_ground::terrain_standard_material;
+
+    let mut images = Assets::<Image>::default();
+    let splat = images.add(Image::default());
+    let material = terrain_standard_material(splat.clone());
+    assert_eq!(material.base_color_texture, Some(splat));
+    assert_eq!(material.perceptual_roughness, 0.9);
+    assert_eq!(material.metallic, 0.0);
+    assert_eq!(material.reflectance, 0.2);
+    assert!(!material.unlit);
+    assert!(!material.double_sided);
+}
+
+#[test]
+fn world_bounds_match_built_mesh() {
+    let mut heightmap = HeightMap::new(7, 5, 1.5);
+    for z in 0..5 {
+        for x in 0..7 {
+            heightmap.set(x, z, (x as f32 - 2.5) * (z as f32 + 0.5));
+        }
+    }
+    let mesh = HeightMapMeshBuilder::new().build(&heightmap);
+    let positions = mesh
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+        .unwrap()
+        .as_float3()
+        .unwrap();
+    let (min, max) = positions
+        .iter()
+        .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), &p| {
+            (min.min(Vec3::from(p)), max.max(Vec3::from(p)))
+        });
+
+    let bounds = world_bounds(&heightmap);
+    assert_eq!(Vec3::from(bounds.min()), min);
+    assert_eq!(Vec3::from(bounds.max()), max);
+    assert_eq!(world_extent(&heightmap), Vec2::new(9.0, 6.0));
+}
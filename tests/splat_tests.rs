@@ -1,4 +1,8 @@
-use bevy_symbios_ground::splat_to_image;
+use bevy::prelude::*;
+use bevy_symbios_ground::{
+    HeightMapMeshBuilder, SplatError, dominant_layer, sample_weights_bilinear, splat_chunked,
+    splat_to_image, splat_write_into,
+};
 use symbios_ground::{HeightMap, SplatMapper, WeightMap};
 
 fn make_weight_map(w: usize, h: usize) -> WeightMap {
@@ -74,3 +78,455 @@ fn rgba8_unorm_format() {
     let image = splat_to_image(&wm);
     assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm);
 }
+
+#[test]
+fn chunk_images_carry_overlap_border() {
+    let wm = make_weight_map(9, 9);
+    let chunks = splat_chunked(&wm, 4);
+    assert_eq!(chunks.len(), 4);
+
+    // Chunk (1, 0) covers vertices x ∈ [4, 8], z ∈ [0, 4] → 7×7 image with border.
+    let (coord, image) = &chunks[1];
+    assert_eq!(*coord, IVec2::new(1, 0));
+    assert_eq!(image.texture_descriptor.size.width, 7);
+    assert_eq!(image.texture_descriptor.size.height, 7);
+
+    let data = image.data.as_ref().unwrap();
+    let texel = |tx: usize, tz: usize| -> [u8; 4] {
+        let i = (tz * 7 + tx) * 4;
+        [data[i], data[i + 1], data[i + 2], data[i + 3]]
+    };
+    // Left border column holds the neighbouring chunk's pixel at x = 3.
+    assert_eq!(texel(0, 1), wm.data[3]);
+    // Top border row is outside the map, so it repeats row z = 0.
+    assert_eq!(texel(1, 0), wm.data[4]);
+    // Right border column is outside the map, so it repeats column x = 8.
+    assert_eq!(texel(6, 1), wm.data[8]);
+}
+
+#[test]
+fn chunk_images_cover_whole_map() {
+    let wm = make_weight_map(11, 7);
+    let chunk_size = 4;
+    let mut covered = [false; 11 * 7];
+    for (coord, image) in splat_chunked(&wm, chunk_size) {
+        let iw = image.texture_descriptor.size.width as usize;
+        let ih = image.texture_descriptor.size.height as usize;
+        let data = image.data.as_ref().unwrap();
+        let x0 = coord.x as usize * chunk_size;
+        let z0 = coord.y as usize * chunk_size;
+        for tz in 1..ih - 1 {
+            for tx in 1..iw - 1 {
+                let (x, z) = (x0 + tx - 1, z0 + tz - 1);
+                let i = (tz * iw + tx) * 4;
+                assert_eq!(&data[i..i + 4], &wm.data[z * 11 + x]);
+                covered[z * 11 + x] = true;
+            }
+        }
+    }
+    assert!(
+        covered.iter().all(|&c| c),
+        "every pixel must be in some chunk"
+    );
+}
+
+#[test]
+fn chunk_coordinates_match_mesh_chunks() {
+    let heightmap = HeightMap::new(11, 7, 1.0);
+    let wm = make_weight_map(11, 7);
+    let mesh_coords: Vec<IVec2> = HeightMapMeshBuilder::new()
+        .build_chunked(&heightmap, 4)
+        .into_iter()
+        .map(|(c, _)| c)
+        .collect();
+    let splat_coords: Vec<IVec2> = splat_chunked(&wm, 4).into_iter().map(|(c, _)| c).collect();
+    assert_eq!(mesh_coords, splat_coords);
+}
+
+#[test]
+fn tint_scales_uploaded_alpha_only() {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_symbios_ground::{
+        GroundMaterialSettings, SplatTexture, SplatTint, sync_splat_texture,
+    };
+
+    let mut wm = WeightMap::new(2, 2);
+    wm.data = vec![[10, 20, 30, 200]; 4];
+
+    let mut world = World::new();
+    let mut images = Assets::<Image>::default();
+    let handle = images.add(splat_to_image(&wm));
+    world.insert_resource(images);
+    world.insert_resource(SplatTexture {
+        handle: handle.clone(),
+    });
+    world.insert_resource(GroundMaterialSettings::new(wm.clone()));
+    world.insert_resource(SplatTint {
+        per_channel_scale: [1.0, 1.0, 1.0, 0.5],
+    });
+
+    world.run_system_once(sync_splat_texture).unwrap();
+
+    let images = world.resource::<Assets<Image>>();
+    let data = images.get(&handle).unwrap().data.as_ref().unwrap();
+    for pixel in data.chunks_exact(4) {
+        assert_eq!(pixel, &[10, 20, 30, 100]);
+    }
+    let settings = world.resource::<GroundMaterialSettings>();
+    assert_eq!(
+        settings.weight_map.data, wm.data,
+        "source map must be untouched"
+    );
+}
+
+#[test]
+fn streamed_upload_writes_bounded_row_bands() {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_symbios_ground::{GroundMaterialSettings, SplatTexture, sync_splat_texture};
+
+    let (w, h, rows) = (3, 10, 3);
+    let mut blank = WeightMap::new(w, h);
+    blank.data = vec![[0; 4]; w * h];
+    let mut wm = WeightMap::new(w, h);
+    wm.data = (0..w * h).map(|i| [i as u8, 1, 2, 3]).collect();
+
+    let mut world = World::new();
+    let mut images = Assets::<Image>::default();
+    let handle = images.add(splat_to_image(&blank));
+    world.insert_resource(images);
+    world.insert_resource(SplatTexture {
+        handle: handle.clone(),
+    });
+    world.insert_resource(GroundMaterialSettings::new(wm.clone()).with_rows_per_frame(rows));
+
+    let read = |world: &World| {
+        let images = world.resource::<Assets<Image>>();
+        images.get(&handle).unwrap().data.clone().unwrap()
+    };
+
+    let mut runs = 0;
+    let mut before = read(&world);
+    loop {
+        world.run_system_once(sync_splat_texture).unwrap();
+        runs += 1;
+        let after = read(&world);
+        let touched = before
+            .chunks_exact(w * 4)
+            .zip(after.chunks_exact(w * 4))
+            .filter(|(a, b)| a != b)
+            .count();
+        assert!(touched <= rows, "run {runs} touched {touched} rows");
+        before = after;
+        if !world.resource::<GroundMaterialSettings>().is_uploading() {
+            break;
+        }
+        assert!(runs < 10, "upload never finished");
+    }
+
+    assert_eq!(runs, h.div_ceil(rows));
+    let expected: Vec<u8> = wm.data.iter().flatten().copied().collect();
+    assert_eq!(read(&world), expected);
+}
+
+fn two_pixel_map() -> WeightMap {
+    let mut wm = WeightMap::new(2, 2);
+    wm.data = vec![
+        [255, 0, 0, 0],
+        [0, 0, 255, 0],
+        [0, 255, 0, 0],
+        [0, 0, 0, 255],
+    ];
+    wm
+}
+
+#[test]
+fn weights_at_pixel_center_match_pixel() {
+    let wm = two_pixel_map();
+    let scale = 2.0;
+    for z in 0..2 {
+        for x in 0..2 {
+            let w = sample_weights_bilinear(&wm, x as f32 * scale, z as f32 * scale, scale);
+            let expected = wm.data[z * 2 + x].map(|v| v as f32 / 255.0);
+            assert_eq!(w, expected, "pixel ({x}, {z})");
+        }
+    }
+    assert_eq!(dominant_layer(&wm, 2.0, 0.0, scale), 2);
+    // Out-of-bounds points clamp to the border.
+    assert_eq!(dominant_layer(&wm, 50.0, 50.0, scale), 3);
+}
+
+#[test]
+fn weights_interpolate_between_pixels() {
+    let wm = two_pixel_map();
+    let mid_edge = sample_weights_bilinear(&wm, 1.0, 0.0, 2.0);
+    assert_eq!(mid_edge, [0.5, 0.0, 0.5, 0.0]);
+    let center = sample_weights_bilinear(&wm, 1.0, 1.0, 2.0);
+    assert_eq!(center, [0.25; 4]);
+}
+
+#[test]
+fn write_into_matching_image_overwrites_data() {
+    let mut image = splat_to_image(&WeightMap::new(3, 2));
+    let wm = two_pixel_map();
+    let mut wm3 = WeightMap::new(3, 2);
+    wm3.data = (0..6).map(|i| [i, 2 * i, 3 * i, 255 - i]).collect();
+
+    splat_write_into(&wm3, &mut image).unwrap();
+    assert_eq!(image.data.as_deref(), splat_to_image(&wm3).data.as_deref());
+
+    assert_eq!(
+        splat_write_into(&wm, &mut image),
+        Err(SplatError::SizeMismatch {
+            expected: UVec2::new(2, 2),
+            actual: UVec2::new(3, 2),
+        })
+    );
+    // Failed writes leave the image untouched.
+    assert_eq!(image.data.as_deref(), splat_to_image(&wm3).data.as_deref());
+}
+
+#[test]
+fn column_major_layout_matches_row_major_image() {
+    use bevy_symbios_ground::{WeightMapLayout, splat_to_image_with_layout};
+
+    let (w, h) = (4, 3);
+    let pixel = |x: usize, z: usize| [(x * 10 + z) as u8, x as u8, z as u8, 255 - x as u8];
+    let mut row_major = WeightMap::new(w, h);
+    row_major.data = (0..h)
+        .flat_map(|z| (0..w).map(move |x| pixel(x, z)))
+        .collect();
+    let mut column_major = WeightMap::new(w, h);
+    column_major.data = (0..w)
+        .flat_map(|x| (0..h).map(move |z| pixel(x, z)))
+        .collect();
+
+    let expected = splat_to_image(&row_major);
+    let from_columns = splat_to_image_with_layout(&column_major, WeightMapLayout::ColumnMajor);
+    assert_eq!(from_columns.data, expected.data);
+    assert_eq!(from_columns.size(), expected.size());
+    assert_eq!(
+        splat_to_image_with_layout(&row_major, WeightMapLayout::RowMajor).data,
+        expected.data
+    );
+    // Reading column-major data as row-major would scramble it.
+    assert_ne!(splat_to_image(&column_major).data, expected.data);
+}
+
+#[test]
+fn splat_options_apply_mirror_repeat_to_both_axes() {
+    use bevy::image::{ImageAddressMode, ImageSampler};
+    use bevy_symbios_ground::{SplatImageOptions, splat_to_image_with_options};
+
+    let wm = WeightMap::new(4, 4);
+    let options = SplatImageOptions {
+        address_mode: ImageAddressMode::MirrorRepeat,
+        ..Default::default()
+    };
+    let image = splat_to_image_with_options(&wm, &options);
+
+    let ImageSampler::Descriptor(sampler) = &image.sampler else {
+        panic!("expected an explicit sampler descriptor");
+    };
+    assert_eq!(sampler.address_mode_u, ImageAddressMode::MirrorRepeat);
+    assert_eq!(sampler.address_mode_v, ImageAddressMode::MirrorRepeat);
+    assert_eq!(image.data, splat_to_image(&wm).data);
+}
+
+#[test]
+fn premultiplied_interpretation_scales_rgb_by_alpha() {
+    use bevy_symbios_ground::{
+        SplatImageOptions, WeightInterpretation, splat_to_image_with_options,
+    };
+
+    let mut wm = WeightMap::new(2, 1);
+    wm.data[1] = [200, 100, 51, 128];
+    let options = SplatImageOptions {
+        interpretation: WeightInterpretation::PremultipliedAlpha,
+        ..Default::default()
+    };
+    let image = splat_to_image_with_options(&wm, &options);
+    let data = image.data.as_ref().unwrap();
+
+    // 200·128/255 = 100.4, 100·128/255 = 50.2, 51·128/255 = 25.6.
+    assert_eq!(&data[4..8], &[100, 50, 26, 128]);
+    // The default pixel [255, 0, 0, 0] has zero alpha, clearing its weights.
+    assert_eq!(&data[..4], &[0, 0, 0, 0]);
+    let straight = splat_to_image_with_options(&wm, &SplatImageOptions::default());
+    assert_eq!(straight.data, splat_to_image(&wm).data);
+}
+
+#[test]
+fn eight_layer_pixel_splits_across_two_images() {
+    use bevy_symbios_ground::{WeightMap8, splat_to_images_split};
+
+    let mut wm = WeightMap8::new(2, 2);
+    wm.data[3] = [10, 20, 30, 40, 50, 60, 70, 80];
+    let (primary, secondary) = splat_to_images_split(&wm);
+
+    assert_eq!(primary.size(), secondary.size());
+    assert_eq!(primary.sampler, secondary.sampler);
+    let first = primary.data.as_ref().unwrap();
+    let second = secondary.data.as_ref().unwrap();
+    assert_eq!(&first[12..16], &[10, 20, 30, 40]);
+    assert_eq!(&second[12..16], &[50, 60, 70, 80]);
+    assert_eq!(&first[0..4], &[255, 0, 0, 0]);
+    assert_eq!(&second[0..4], &[0, 0, 0, 0]);
+}
+
+#[test]
+fn regeneration_starts_crossfade_that_completes() {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_symbios_ground::{
+        GroundMaterialSettings, SplatCrossfade, SplatTexture, sync_splat_texture,
+        update_splat_crossfade,
+    };
+
+    let wm = WeightMap::new(2, 2);
+    let mut world = World::new();
+    let mut images = Assets::<Image>::default();
+    let handle = images.add(splat_to_image(&wm));
+    world.insert_resource(images);
+    world.insert_resource(SplatTexture {
+        handle: handle.clone(),
+    });
+    world.insert_resource(GroundMaterialSettings::new(wm).with_crossfade(1.0));
+    world.insert_resource(Time::<()>::default());
+
+    // The initial upload has nothing to fade from.
+    world.run_system_once(sync_splat_texture).unwrap();
+    assert!(world.get_resource::<SplatCrossfade>().is_none());
+
+    {
+        let mut settings = world.resource_mut::<GroundMaterialSettings>();
+        settings.weight_map.data = vec![[0, 255, 0, 0]; 4];
+        settings.mark_dirty();
+    }
+    world.run_system_once(sync_splat_texture).unwrap();
+    let fade = world.resource::<SplatCrossfade>().clone();
+    assert_eq!(fade.new, handle);
+    assert_eq!(fade.blend(), 0.0);
+    let images = world.resource::<Assets<Image>>();
+    assert_eq!(
+        images.get(&fade.old).unwrap().data.as_ref().unwrap()[..4],
+        [255, 0, 0, 0]
+    );
+    assert_eq!(
+        images.get(&handle).unwrap().data.as_ref().unwrap()[..4],
+        [0, 255, 0, 0]
+    );
+
+    let mut last = 0.0;
+    for _ in 0..6 {
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(250));
+        world.run_system_once(update_splat_crossfade).unwrap();
+        let blend = world.resource::<SplatCrossfade>().blend();
+        assert!(blend >= last);
+        last = blend;
+    }
+    assert!((world.resource::<SplatCrossfade>().blend() - 1.0).abs() < 1e-6);
+    assert!(world.resource::<SplatCrossfade>().is_complete());
+}
+
+#[test]
+fn streamed_regeneration_fades_only_after_the_last_row() {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_symbios_ground::{
+        GroundMaterialSettings, SplatCrossfade, SplatTexture, sync_splat_texture,
+    };
+
+    let (w, h, rows) = (2, 6, 2);
+    let wm = WeightMap::new(w, h);
+    let mut world = World::new();
+    let mut images = Assets::<Image>::default();
+    let handle = images.add(splat_to_image(&wm));
+    world.insert_resource(images);
+    world.insert_resource(SplatTexture {
+        handle: handle.clone(),
+    });
+    world.insert_resource(
+        GroundMaterialSettings::new(wm)
+            .with_rows_per_frame(rows)
+            .with_crossfade(0.1),
+    );
+    loop {
+        world.run_system_once(sync_splat_texture).unwrap();
+        if !world.resource::<GroundMaterialSettings>().is_uploading() {
+            break;
+        }
+    }
+    assert!(world.get_resource::<SplatCrossfade>().is_none());
+
+    let regenerate = |world: &mut World, pixel: [u8; 4]| {
+        let mut settings = world.resource_mut::<GroundMaterialSettings>();
+        settings.weight_map.data = vec![pixel; w * h];
+        settings.mark_dirty();
+    };
+    regenerate(&mut world, [0, 255, 0, 0]);
+    world.run_system_once(sync_splat_texture).unwrap();
+    assert!(world.get_resource::<SplatCrossfade>().is_none());
+
+    // Regenerating mid-stream restarts the upload but keeps the snapshot of
+    // the last complete texture.
+    regenerate(&mut world, [0, 0, 255, 0]);
+    let mut runs = 0;
+    loop {
+        world.run_system_once(sync_splat_texture).unwrap();
+        runs += 1;
+        if !world.resource::<GroundMaterialSettings>().is_uploading() {
+            break;
+        }
+        assert!(
+            world.get_resource::<SplatCrossfade>().is_none(),
+            "fade started while rows were still streaming"
+        );
+    }
+    assert_eq!(runs, h.div_ceil(rows));
+
+    let fade = world.resource::<SplatCrossfade>().clone();
+    assert_eq!(fade.blend(), 0.0);
+    let images = world.resource::<Assets<Image>>();
+    let old = images.get(&fade.old).unwrap().data.clone().unwrap();
+    let new = images.get(&fade.new).unwrap().data.clone().unwrap();
+    assert!(old.chunks_exact(4).all(|p| p == [255, 0, 0, 0]));
+    assert!(new.chunks_exact(4).all(|p| p == [0, 0, 255, 0]));
+}
+
+#[test]
+fn pack_layers_to_image_normalizes_and_quantizes_ramps() {
+    use bevy_symbios_ground::pack_layers_to_image;
+
+    // Four ramps over a 4×2 grid; every texel sums to 6 before normalizing.
+    let up: Vec<f32> = (0..8).map(|i| (i % 4) as f32).collect();
+    let down: Vec<f32> = up.iter().map(|v| 3.0 - v).collect();
+    let image = pack_layers_to_image(&[&up, &down, &up, &down], 4, 2);
+
+    assert_eq!(image.width(), 4);
+    assert_eq!(image.height(), 2);
+    let data = image.data.as_ref().unwrap();
+    let expected_row: [[u8; 4]; 4] = [
+        [0, 128, 0, 128],
+        [43, 85, 43, 85],
+        [85, 43, 85, 43],
+        [128, 0, 128, 0],
+    ];
+    for (texel, expected) in data.chunks_exact(4).zip(expected_row.iter().cycle()) {
+        assert_eq!(texel, expected);
+    }
+}
+
+#[test]
+fn pack_layers_to_image_fills_missing_and_empty_texels() {
+    use bevy_symbios_ground::pack_layers_to_image;
+
+    let a = [0.0, 2.0, -1.0];
+    let b = [0.0, 2.0, f32::NAN];
+    let image = pack_layers_to_image(&[&a, &b], 3, 1);
+    assert_eq!(
+        image.data.as_ref().unwrap().as_slice(),
+        &[255, 0, 0, 0, 128, 128, 0, 0, 255, 0, 0, 0]
+    );
+}
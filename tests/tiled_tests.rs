@@ -0,0 +1,135 @@
+#![cfg(feature = "tiled")]
+
+use std::io::Cursor;
+
+use bevy::prelude::*;
+use bevy_symbios_ground::{TiledHeightMap, TiledHeightMapError, write_tiled_heightmap};
+use symbios_ground::HeightMap;
+
+fn wavy_map(w: usize, h: usize) -> HeightMap {
+    let mut map = HeightMap::new(w, h, 0.5);
+    for z in 0..h {
+        for x in 0..w {
+            map.set(x, z, (x as f32 * 0.37).sin() * 3.0 + z as f32 * 0.01);
+        }
+    }
+    map
+}
+
+fn written(map: &HeightMap, tile_size: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_tiled_heightmap(map, tile_size, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn round_trip_reproduces_source_heights() {
+    let map = wavy_map(21, 14);
+    let mut tiled = TiledHeightMap::from_reader(Cursor::new(written(&map, 8))).unwrap();
+    assert_eq!(tiled.tile_count(), UVec2::new(3, 2));
+    assert_eq!(tiled.dimensions(), UVec2::new(21, 14));
+
+    for tz in 0..2 {
+        for tx in 0..3 {
+            let tile = tiled.tile(tx, tz).unwrap();
+            assert_eq!(tile.scale(), 0.5);
+            let (x0, z0) = (tx as usize * 8, tz as usize * 8);
+            // Edge tiles are clipped; neighbours share their border vertices.
+            assert_eq!(tile.width(), (x0 + 8).min(20) - x0 + 1);
+            assert_eq!(tile.height(), (z0 + 8).min(13) - z0 + 1);
+            for z in 0..tile.height() {
+                for x in 0..tile.width() {
+                    assert_eq!(tile.get(x, z), map.get(x0 + x, z0 + z));
+                }
+            }
+            assert_eq!(
+                tiled.tile_origin(tx, tz),
+                Vec2::new(x0 as f32, z0 as f32) * 0.5
+            );
+        }
+    }
+}
+
+#[test]
+fn only_requested_tiles_are_loaded() {
+    let map = wavy_map(65, 65);
+    let mut tiled = TiledHeightMap::from_reader(Cursor::new(written(&map, 16))).unwrap();
+    assert_eq!(tiled.tiles_loaded(), 0);
+
+    let tile = tiled.tile(2, 1).unwrap();
+    assert_eq!(tile.get(0, 0), map.get(32, 16));
+    assert_eq!(tiled.tiles_loaded(), 1);
+
+    assert!(matches!(
+        tiled.tile(4, 0),
+        Err(TiledHeightMapError::TileOutOfRange(c)) if c == IVec2::new(4, 0)
+    ));
+    assert_eq!(tiled.tiles_loaded(), 1);
+}
+
+#[test]
+fn rejects_foreign_data() {
+    let err = TiledHeightMap::from_reader(Cursor::new(b"not a tiled map".to_vec())).unwrap_err();
+    assert!(
+        matches!(err, TiledHeightMapError::InvalidFormat(_)),
+        "{err}"
+    );
+}
+
+fn header(width: u32, height: u32, tile_size: u32, scale: f32) -> Vec<u8> {
+    let mut bytes = b"SYGT".to_vec();
+    for value in [1, width, height, tile_size] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&scale.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn rejects_corrupt_headers() {
+    let open = |bytes: Vec<u8>| TiledHeightMap::from_reader(Cursor::new(bytes)).unwrap_err();
+
+    for scale in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        let err = open(header(9, 9, 4, scale));
+        assert!(
+            matches!(err, TiledHeightMapError::InvalidFormat(_)),
+            "{err}"
+        );
+    }
+    assert!(matches!(
+        open(header(9, 9, 0, 1.0)),
+        TiledHeightMapError::InvalidFormat(_)
+    ));
+
+    // A huge tile count with no index behind it fails on the missing data
+    // instead of allocating or overflowing.
+    let err = open(header(u32::MAX, u32::MAX, 1, 1.0));
+    assert!(matches!(err, TiledHeightMapError::Io(_)), "{err}");
+}
+
+#[test]
+fn oversized_tile_header_fails_without_allocating_the_tile() {
+    // One 65535² tile claimed by a ~50-byte file: decoding must stop at the
+    // few bytes present instead of reserving 16 GiB for the tile first.
+    let mut bytes = header(65535, 65535, 65535, 1.0);
+    let payload: &[u8] = &[0x03, 0x00];
+    let offset = bytes.len() as u64 + 12;
+    bytes.extend_from_slice(&offset.to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(payload);
+
+    let mut tiled = TiledHeightMap::from_reader(Cursor::new(bytes)).unwrap();
+    let err = tiled.tile(0, 0).unwrap_err();
+    assert!(
+        matches!(err, TiledHeightMapError::InvalidFormat(_)),
+        "{err}"
+    );
+    assert_eq!(tiled.tiles_loaded(), 0);
+}
+
+#[test]
+fn writer_rejects_sizes_beyond_the_format() {
+    let map = wavy_map(5, 5);
+    let err = write_tiled_heightmap(&map, u32::MAX as usize + 1, Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}